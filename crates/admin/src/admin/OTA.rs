@@ -5,11 +5,19 @@ use anyhow::{Context, bail};
 use tonic::Status;
 use tracing::debug;
 
+use std::collections::HashMap;
+
 use crate::endpoint::EndpointConfig;
 use crate::pb::SetGenerationResponse;
+use crate::pb::admin::{
+    CollectGarbageResponse, RegistryProgress, RollbackGenerationResponse, UpdateProgress,
+};
 use crate::utils::tonic::{Stream, wrap_error};
 use givc_client::exec::ExecClient;
 use givc_common::pb::Generation;
+use ota_update::generation::{GcReport, RollbackTarget};
+use ota_update::progress::UpdateEvent;
+use ota_update::registry::progress::RegistryEvent;
 use ota_update::types::GenerationDetails;
 
 type SetGenerationStream = Stream<SetGenerationResponse>;
@@ -25,53 +33,72 @@ impl OTA {
     }
 
     pub async fn list(&self) -> anyhow::Result<Vec<Generation>> {
+        let stdout = self.run(vec!["get".to_string()]).await?;
+        parse_generations(&stdout)
+    }
+
+    /// Run `ota-update` with `args` on host, returning its stdout
+    async fn run(&self, args: Vec<String>) -> anyhow::Result<Vec<u8>> {
         let mut exec = ExecClient::connect(self.endpoint.clone()).await?;
         let (stdout, stderr, rc) = exec
-            .get_program_output(
-                "ota-update".to_string(),
-                vec!["get".to_string()],
-                None,
-                None,
-                None,
-                None,
-            )
+            .get_program_output("ota-update".to_string(), args, None, None, None, None)
             .await?;
         if rc > 0 {
             bail!("Exec error: {}", String::from_utf8_lossy(&stderr))
         }
         debug!("stdout: {}", String::from_utf8_lossy(&stdout));
-        let gens: Vec<GenerationDetails> = serde_json::from_slice(&stdout)?;
-        gens.into_iter()
-            .map(|g| {
-                Ok(Generation {
-                    current: g.current,
-                    generation: g.generation,
-                    store_path: g
-                        .store_path
-                        .into_os_string()
-                        .into_string()
-                        .ok()
-                        .context("Decode UTF-8")?,
-                    configuration_revision: g
-                        .configuration_revision
-                        .unwrap_or_else(|| "unknown".into()),
-                    nixos_version: g.nixos_version,
-                    kernel_version: g.kernel_version,
-                    specialisations: Vec::new(),
-                    date: "bogus".into(),
-                })
-            })
-            .collect()
+        Ok(stdout)
     }
 
-    // FIXME: Update going silently, it should report
+    pub async fn rollback(&self) -> anyhow::Result<RollbackGenerationResponse> {
+        let stdout = self
+            .run(vec![
+                "rollback".to_owned(),
+                "--output".to_owned(),
+                "jsonl".to_owned(),
+            ])
+            .await?;
+        Ok(match serde_json::from_slice(last_line(&stdout))? {
+            RollbackTarget::Generation {
+                generation,
+                store_path,
+            } => RollbackGenerationResponse {
+                generation: Some(generation),
+                target: store_path.display().to_string(),
+            },
+            RollbackTarget::Slot { version } => RollbackGenerationResponse {
+                generation: None,
+                target: version,
+            },
+        })
+    }
+
+    pub async fn collect_garbage(&self, keep: u32) -> anyhow::Result<CollectGarbageResponse> {
+        let stdout = self
+            .run(vec![
+                "gc".to_owned(),
+                "--keep".to_owned(),
+                keep.to_string(),
+                "--output".to_owned(),
+                "jsonl".to_owned(),
+            ])
+            .await?;
+        let report: GcReport = serde_json::from_slice(last_line(&stdout))?;
+        Ok(CollectGarbageResponse {
+            removed: report.removed,
+            kept: report.kept,
+        })
+    }
+
+    /// Install pinned release from cachix, relaying fetch and activation progress
     pub async fn install_via_cachix(
         &self,
         cachix_request: crate::pb::admin::Cachix,
     ) -> anyhow::Result<Stream<SetGenerationResponse>> {
-        let mut exec = ExecClient::connect(self.endpoint.clone()).await?;
         let mut args = vec![
             "cachix".to_owned(),
+            "--output".to_owned(),
+            "jsonl".to_owned(),
             cachix_request.pin,
             "--cache".to_owned(),
             cachix_request.cache,
@@ -84,24 +111,104 @@ impl OTA {
             args.push("--cachix-host".to_owned());
             args.push(cachix_host);
         }
+        self.run_streaming(args, HashMap::new(), |line| {
+            let event = serde_json::from_str::<UpdateEvent>(line).ok()?;
+            Some(SetGenerationResponse {
+                finished: false,
+                output: None,
+                error: None,
+                registry: None,
+                progress: Some(update_progress(event)),
+            })
+        })
+        .await
+    }
+
+    /// Pull (and optionally install) update from OCI registry, relaying registry progress
+    pub async fn install_via_oci(
+        &self,
+        oci: crate::pb::admin::Oci,
+    ) -> anyhow::Result<Stream<SetGenerationResponse>> {
+        anyhow::ensure!(!oci.reference.is_empty(), "OCI reference is required");
+        let mut args = vec![
+            "registry".to_owned(),
+            "--output".to_owned(),
+            "jsonl".to_owned(),
+        ];
+        if oci.insecure {
+            args.push("--insecure".to_owned());
+        }
+        args.extend(["pull".to_owned(), oci.reference]);
+        if let Some(destination) = oci.destination {
+            args.push("--destination".to_owned());
+            args.push(destination);
+        }
+        if oci.validate {
+            args.push("--validate".to_owned());
+        }
+        if oci.install {
+            args.push("--install".to_owned());
+        }
+        // Credentials passed through environment, to keep them out of process list
+        let env = [
+            ("GIVC_REGISTRY_USERNAME", oci.username),
+            ("GIVC_REGISTRY_PASSWORD", oci.password),
+            ("GIVC_REGISTRY_TOKEN", oci.token),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name.to_owned(), value?)))
+        .collect();
+        self.run_streaming(args, env, |line| {
+            let event = serde_json::from_str::<RegistryEvent>(line).ok()?;
+            Some(SetGenerationResponse {
+                finished: false,
+                output: None,
+                error: None,
+                registry: Some(registry_progress(event)),
+                progress: None,
+            })
+        })
+        .await
+    }
+
+    /// Run `ota-update` on host, relaying its output. Complete stdout lines are offered
+    /// to `progress` first, lines it doesn't recognize are passed as plain output.
+    async fn run_streaming(
+        &self,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+        progress: fn(&str) -> Option<SetGenerationResponse>,
+    ) -> anyhow::Result<Stream<SetGenerationResponse>> {
+        let mut exec = ExecClient::connect(self.endpoint.clone()).await?;
+        let output = move |line: String| {
+            progress(&line).unwrap_or(SetGenerationResponse {
+                finished: false,
+                output: Some(line),
+                error: None,
+                registry: None,
+                progress: None,
+            })
+        };
         let stream = async_fn_stream::try_fn_stream(async move |emitter| {
             debug!("Invoke ota-update: {args:?}");
+            let emitter = &emitter;
+            let mut lines = LineBuffer::default();
             let rc = exec
                 .start_command(
                     "ota-update".to_string(),
                     args,
                     None,
-                    None,
+                    Some(env),
                     None,
                     None,
                     |stdout| {
-                        let out = String::from_utf8_lossy(&stdout);
-                        debug!("stdout: {}", out);
-                        emitter.emit(SetGenerationResponse {
-                            finished: false,
-                            output: Some(out.into()),
-                            error: None,
-                        })
+                        debug!("stdout: {}", String::from_utf8_lossy(&stdout));
+                        let items: Vec<_> = lines.feed(&stdout).into_iter().map(output).collect();
+                        async move {
+                            for item in items {
+                                emitter.emit(item).await;
+                            }
+                        }
                     },
                     |stderr| {
                         let err = String::from_utf8_lossy(&stderr);
@@ -110,16 +217,23 @@ impl OTA {
                             finished: false,
                             output: None,
                             error: Some(err.into()),
+                            registry: None,
+                            progress: None,
                         })
                     },
                 )
                 .await
                 .map_err(|e| Status::unknown(e.to_string()))?;
+            if let Some(rest) = lines.finish() {
+                emitter.emit(output(rest)).await;
+            }
             emitter
                 .emit(SetGenerationResponse {
                     finished: true,
                     output: None,
                     error: None,
+                    registry: None,
+                    progress: None,
                 })
                 .await;
             if rc > 0 {
@@ -132,3 +246,285 @@ impl OTA {
         Ok(Box::pin(stream) as SetGenerationStream)
     }
 }
+
+/// Splits output chunks, which may break lines at any point, into complete lines
+#[derive(Default)]
+struct LineBuffer(Vec<u8>);
+
+impl LineBuffer {
+    fn feed(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.0.extend_from_slice(chunk);
+        let Some(end) = self.0.iter().rposition(|b| *b == b'\n') else {
+            return Vec::new();
+        };
+        let complete: Vec<u8> = self.0.drain(..=end).collect();
+        String::from_utf8_lossy(&complete)
+            .lines()
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+
+    /// Unterminated last line, if any
+    fn finish(&mut self) -> Option<String> {
+        let rest = std::mem::take(&mut self.0);
+        (!rest.is_empty()).then(|| String::from_utf8_lossy(&rest).into_owned())
+    }
+}
+
+/// Generations, as printed by `ota-update get`
+fn parse_generations(stdout: &[u8]) -> anyhow::Result<Vec<Generation>> {
+    let gens: Vec<GenerationDetails> = serde_json::from_slice(stdout)?;
+    gens.into_iter()
+        .map(|g| {
+            Ok(Generation {
+                current: g.current,
+                generation: g.generation,
+                store_path: g
+                    .store_path
+                    .into_os_string()
+                    .into_string()
+                    .ok()
+                    .context("Decode UTF-8")?,
+                configuration_revision: g
+                    .configuration_revision
+                    .unwrap_or_else(|| "unknown".into()),
+                nixos_version: g.nixos_version,
+                kernel_version: g.kernel_version,
+                specialisations: g.specialisations,
+                date: g.date.unwrap_or_default(),
+                closure_size: g.closure_size,
+                boot_entry: <&str>::from(g.boot_entry).to_owned(),
+                booted: g.booted,
+            })
+        })
+        .collect()
+}
+
+/// Last non-blank line of `output`, without line terminator
+fn last_line(output: &[u8]) -> &[u8] {
+    output
+        .split(|b| *b == b'\n')
+        .rfind(|line| !line.trim_ascii().is_empty())
+        .unwrap_or_default()
+}
+
+fn update_progress(event: UpdateEvent) -> UpdateProgress {
+    match event {
+        UpdateEvent::Resolved { store_path } => UpdateProgress {
+            phase: "resolved".into(),
+            store_path: Some(store_path),
+            ..UpdateProgress::default()
+        },
+        UpdateEvent::Fetching {
+            paths_done,
+            paths_total,
+            bytes_done,
+            bytes_total,
+            current,
+        } => UpdateProgress {
+            phase: "fetching".into(),
+            paths_done,
+            paths_total,
+            bytes_done,
+            bytes_total,
+            store_path: current,
+            step: None,
+        },
+        UpdateEvent::Activating { step } => UpdateProgress {
+            phase: "activating".into(),
+            step: serde_json::to_value(step)
+                .ok()
+                .and_then(|v| v.as_str().map(ToOwned::to_owned)),
+            ..UpdateProgress::default()
+        },
+        UpdateEvent::Done => UpdateProgress {
+            phase: "done".into(),
+            ..UpdateProgress::default()
+        },
+    }
+}
+
+fn count(n: usize) -> Option<u64> {
+    u64::try_from(n).ok()
+}
+
+fn registry_progress(event: RegistryEvent) -> RegistryProgress {
+    let mut progress = RegistryProgress {
+        event: <&str>::from(&event).to_owned(),
+        ..RegistryProgress::default()
+    };
+    match event {
+        RegistryEvent::DiscoverStarted { reference, total } => {
+            progress.reference = Some(reference);
+            progress.total = count(total);
+        }
+        RegistryEvent::TagDiscovered {
+            repository,
+            tag,
+            current,
+            total,
+        }
+        | RegistryEvent::ManifestFetched {
+            repository,
+            tag,
+            current,
+            total,
+        } => {
+            progress.reference = Some(format!("{repository}:{tag}"));
+            progress.current = count(current);
+            progress.total = count(total);
+        }
+        RegistryEvent::PullStarted {
+            reference,
+            destination,
+        } => {
+            progress.reference = Some(reference);
+            progress.path = Some(destination);
+        }
+        RegistryEvent::BlobDownloading {
+            digest,
+            downloaded,
+            total,
+        } => {
+            progress.digest = Some(digest);
+            progress.current = Some(downloaded);
+            progress.total = total;
+        }
+        RegistryEvent::BlobResumed {
+            digest,
+            offset,
+            total,
+        } => {
+            progress.digest = Some(digest);
+            progress.current = Some(offset);
+            progress.total = total;
+        }
+        RegistryEvent::BlobCached { digest } | RegistryEvent::BlobVerified { digest } => {
+            progress.digest = Some(digest);
+        }
+        RegistryEvent::ManifestWritten { path } => {
+            progress.path = Some(path.to_string_lossy().into_owned());
+        }
+        RegistryEvent::ChangelogFetched { bytes } => progress.current = count(bytes),
+        RegistryEvent::InstallStarted { manifest } => progress.path = Some(manifest),
+        RegistryEvent::PushStarted { reference, layers } => {
+            progress.reference = Some(reference);
+            progress.total = count(layers);
+        }
+        RegistryEvent::LayerUploading {
+            kind,
+            uploaded,
+            total,
+        } => {
+            progress.detail = Some(kind.to_string());
+            progress.current = Some(uploaded);
+            progress.total = total;
+        }
+        RegistryEvent::LayerUploaded { kind, digest } => {
+            progress.detail = Some(kind.to_string());
+            progress.digest = Some(digest);
+        }
+        RegistryEvent::ManifestPushed {
+            reference,
+            manifest_url,
+            digest,
+        } => {
+            progress.reference = Some(reference);
+            progress.digest = Some(digest);
+            progress.detail = Some(manifest_url);
+        }
+        RegistryEvent::Cancelled { stage } => progress.detail = Some(stage),
+        RegistryEvent::Done => (),
+    }
+    progress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_buffer() {
+        let mut lines = LineBuffer::default();
+        assert!(lines.feed(b"{\"event\":").is_empty());
+        assert_eq!(
+            lines.feed(b"\"done\"}\nfoo\nba"),
+            ["{\"event\":\"done\"}", "foo"]
+        );
+        assert_eq!(lines.finish().as_deref(), Some("ba"));
+        assert_eq!(lines.finish(), None);
+    }
+
+    #[test]
+    fn test_last_line() {
+        let stdout = b"Installing boot loader...\n{\"removed\":[],\"kept\":[]}\n\n";
+        assert_eq!(last_line(stdout), b"{\"removed\":[],\"kept\":[]}");
+        assert_eq!(last_line(b"{}"), b"{}");
+        assert!(last_line(b"").is_empty());
+    }
+
+    #[test]
+    fn test_parse_generations() -> anyhow::Result<()> {
+        let details: GenerationDetails = serde_json::from_value(serde_json::json!({
+            "name": "NixOS",
+            "generation": 42,
+            "nixosVersion": "25.11",
+            "kernelVersion": "6.12.0",
+            "configurationRevision": "abcdef",
+            "storePath": "/nix/store/aaaa-nixos-system-ghaf",
+            "date": "2026-01-02T03:04:05Z",
+            "specialisations": ["debug"],
+            "closureSize": 1024,
+            "current": true,
+            "booted": true,
+            "bootable": true,
+            "default": true,
+            "bootspec": {
+                "org.nixos.bootspec.v1": {
+                    "label": "NixOS",
+                    "kernel": "/nix/store/bbbb-linux/bzImage",
+                    "kernelParams": [],
+                    "init": "/nix/store/aaaa-nixos-system-ghaf/init",
+                    "system": "x86_64-linux",
+                    "toplevel": "/nix/store/aaaa-nixos-system-ghaf",
+                },
+            },
+        }))?;
+        // Same output as `ota-update get`, which is pretty printed
+        let stdout = serde_json::to_string_pretty(&vec![details])?;
+        assert!(stdout.lines().count() > 1);
+
+        let generations = parse_generations(stdout.as_bytes())?;
+        assert_eq!(generations.len(), 1);
+        let generation = &generations[0];
+        assert_eq!(generation.generation, 42);
+        assert_eq!(generation.store_path, "/nix/store/aaaa-nixos-system-ghaf");
+        assert_eq!(generation.configuration_revision, "abcdef");
+        assert_eq!(generation.specialisations, ["debug"]);
+        assert_eq!(generation.date, "2026-01-02T03:04:05Z");
+        assert_eq!(generation.closure_size, Some(1024));
+        assert!(generation.current && generation.booted);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_progress() -> anyhow::Result<()> {
+        let event = serde_json::from_str(r#"{"event":"activating","step":"set_profile"}"#)?;
+        let progress = update_progress(event);
+        assert_eq!(progress.phase, "activating");
+        assert_eq!(progress.step.as_deref(), Some("set_profile"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry_progress() -> anyhow::Result<()> {
+        let event = serde_json::from_str(
+            r#"{"event":"blob_downloading","digest":"sha256:abc","downloaded":10,"total":100}"#,
+        )?;
+        let progress = registry_progress(event);
+        assert_eq!(progress.event, "blob_downloading");
+        assert_eq!(progress.digest.as_deref(), Some("sha256:abc"));
+        assert_eq!((progress.current, progress.total), (Some(10), Some(100)));
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+// SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Cedar entities derived from registry contents. Hierarchy is:
+//   VmType::"AppVM", TrustLevel::"Warning"
+//     Vm::"chrome-vm"
+//       Unit::"givc-chrome-vm.service" (agent, also in ServiceType::"Mgr")
+//         Unit::"chrome@0.service" (also in ServiceType::"App")
+// Request source is member of agent units, which were registered by peer with its
+// hostname in certificate. TLS name and VM supplied by agent must match that
+// hostname, registrations claiming other names are refused.
+use std::collections::{HashMap, HashSet};
+
+use cedar_policy::{Entity, EntityUid, RestrictedExpression};
+use givc_common::query::QueryResult;
+
+use super::entry::Placement;
+use super::registry::Registry;
+use crate::utils::access_control::{EntityStore, entity_uid};
+
+pub const TYPE_VM: &str = "Vm";
+pub const TYPE_UNIT: &str = "Unit";
+pub const TYPE_VM_TYPE: &str = "VmType";
+pub const TYPE_SERVICE_TYPE: &str = "ServiceType";
+pub const TYPE_TRUST_LEVEL: &str = "TrustLevel";
+
+#[derive(Default)]
+struct Node {
+    attrs: HashMap<String, RestrictedExpression>,
+    parents: HashSet<EntityUid>,
+}
+
+fn string(value: impl ToString) -> RestrictedExpression {
+    RestrictedExpression::new_string(value.to_string())
+}
+
+impl EntityStore for Registry {
+    fn entities(&self) -> anyhow::Result<Vec<Entity>> {
+        let mut nodes: HashMap<EntityUid, Node> = HashMap::new();
+        for entry in self.contents() {
+            let unit = entity_uid(TYPE_UNIT, &entry.name)?;
+            let mut parents = HashSet::new();
+
+            let service_type = entity_uid(TYPE_SERVICE_TYPE, &entry.r#type.service.to_string())?;
+            nodes.entry(service_type.clone()).or_default();
+            parents.insert(service_type);
+
+            if let Placement::Managed { by, .. } = &entry.placement {
+                parents.insert(entity_uid(TYPE_UNIT, by)?);
+            }
+
+            let active_state = entry.status.active_state.clone();
+            let result = QueryResult::from(entry);
+            if let Some(vm_name) = result.vm_name.as_deref().filter(|vm| !vm.is_empty()) {
+                let vm = entity_uid(TYPE_VM, vm_name)?;
+                if !nodes.contains_key(&vm) {
+                    let vm_type = entity_uid(TYPE_VM_TYPE, &result.vm_type.to_string())?;
+                    let trust = entity_uid(TYPE_TRUST_LEVEL, &result.trust_level.to_string())?;
+                    nodes.entry(vm_type.clone()).or_default();
+                    nodes.entry(trust.clone()).or_default();
+                    nodes.insert(
+                        vm.clone(),
+                        Node {
+                            attrs: HashMap::from([
+                                ("vm_type".to_owned(), string(result.vm_type)),
+                                ("trust_level".to_owned(), string(result.trust_level)),
+                            ]),
+                            parents: HashSet::from([vm_type, trust]),
+                        },
+                    );
+                }
+                parents.insert(vm);
+            }
+
+            nodes.insert(
+                unit,
+                Node {
+                    attrs: HashMap::from([
+                        ("vm_type".to_owned(), string(result.vm_type)),
+                        ("service_type".to_owned(), string(result.service_type)),
+                        ("trust_level".to_owned(), string(result.trust_level)),
+                        ("active_state".to_owned(), string(active_state)),
+                    ]),
+                    parents,
+                },
+            );
+        }
+        nodes
+            .into_iter()
+            .map(|(uid, node)| Ok(Entity::new(uid, node.attrs, node.parents)?))
+            .collect()
+    }
+
+    fn memberships(&self, hostname: &str) -> anyhow::Result<HashSet<EntityUid>> {
+        self.filter_map(|entry| match &entry.placement {
+            Placement::Endpoint { .. } if entry.peer.as_deref() == Some(hostname) => {
+                Some(entity_uid(TYPE_UNIT, &entry.name))
+            }
+            _ => None,
+        })
+        .into_iter()
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::admin::entry::RegistryEntry;
+    use crate::types::{ServiceType, UnitType, VmType};
+    use crate::utils::access_control::Authorizer;
+    use crate::utils::x509::SecurityInfo;
+
+    fn authorizer(registry: &Registry, policy: &str) -> anyhow::Result<Authorizer> {
+        let path = std::env::temp_dir().join(format!(
+            "givc-entities-{}-{}.cedar",
+            std::process::id(),
+            policy.len()
+        ));
+        std::fs::write(&path, policy)?;
+        let authorizer = Authorizer::new(&path)?.with_entity_store(Arc::new(registry.clone()));
+        std::fs::remove_file(&path)?;
+        Ok(authorizer)
+    }
+
+    fn registry() -> Registry {
+        let registry = Registry::new();
+        let mut agent = RegistryEntry {
+            r#type: UnitType {
+                vm: VmType::AppVM,
+                service: ServiceType::Mgr,
+            },
+            ..RegistryEntry::dummy("givc-chrome-vm.service".to_string())
+        };
+        if let Placement::Endpoint { endpoint, vm } = &mut agent.placement {
+            endpoint.tls_name = "chrome-vm".into();
+            *vm = "chrome-vm".into();
+        }
+        agent.peer = Some("chrome-vm".into());
+        registry.register(agent);
+        registry.register(RegistryEntry {
+            placement: Placement::Managed {
+                by: "givc-chrome-vm.service".into(),
+                vm: "microvm@chrome-vm.service".into(),
+            },
+            ..RegistryEntry::dummy("chrome@0.service".to_string())
+        });
+        registry
+    }
+
+    #[test]
+    fn test_hierarchy() -> anyhow::Result<()> {
+        let registry = registry();
+        let entities = registry.entities()?;
+        let app = entity_uid(TYPE_UNIT, "chrome@0.service")?;
+        let app = entities
+            .iter()
+            .find(|e| e.uid() == app)
+            .expect("app entity");
+        assert!(app.attr("service_type").is_some());
+        assert_eq!(
+            registry.memberships("chrome-vm")?,
+            HashSet::from([entity_uid(TYPE_UNIT, "givc-chrome-vm.service")?])
+        );
+        assert!(registry.memberships("gui-vm")?.is_empty());
+
+        // Peer can't register units under TLS name or VM of other peer
+        let agent = registry.by_name("givc-chrome-vm.service")?;
+        assert!(agent.check_peer().is_ok());
+        let mut spoofed = RegistryEntry::dummy("givc-gui-vm.service".to_string());
+        if let Placement::Endpoint { endpoint, vm } = &mut spoofed.placement {
+            endpoint.tls_name = "gui-vm".into();
+            *vm = "gui-vm".into();
+        }
+        spoofed.peer = Some("chrome-vm".into());
+        assert!(spoofed.check_peer().is_err());
+        if let Placement::Endpoint { endpoint, .. } = &mut spoofed.placement {
+            endpoint.tls_name = "chrome-vm".into();
+        }
+        assert!(spoofed.check_peer().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_policy() -> anyhow::Result<()> {
+        let registry = registry();
+        let authorizer = authorizer(
+            &registry,
+            r#"permit(principal in VmType::"AppVM", action, resource)
+               when { principal.common_name == "chrome-vm" };"#,
+        )?;
+        let poweroff = |hostname: &str| {
+            authorizer.authorize(
+                &SecurityInfo::with_hostname(hostname),
+                "admin.AdminService",
+                "Poweroff",
+                serde_json::json!({}),
+            )
+        };
+        assert!(poweroff("chrome-vm").is_ok());
+        assert!(poweroff("gui-vm").is_err());
+        Ok(())
+    }
+}
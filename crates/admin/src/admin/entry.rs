@@ -4,11 +4,12 @@
 // This module contain literal translations of types from internal/pkgs/types/types.go
 // Some of them would be rewritten, replaced, or even removed
 use crate::pb;
-use anyhow::{Context, anyhow};
+use anyhow::{Context, anyhow, bail};
 use std::convert::TryFrom;
+use std::time::Duration;
 
 use crate::utils::naming::parse_vm_name;
-use givc_common::query::{QueryResult, TrustLevel, VMStatus};
+use givc_common::query::{Liveness, QueryResult, TrustLevel, VMStatus};
 use givc_common::types::{EndpointEntry, ServiceType, UnitStatus, UnitType, VmType};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -23,6 +24,24 @@ pub enum Placement {
     Host,
 }
 
+/// Health check schedule of single watched entry
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Probe {
+    /// Delay between two checks of same entry
+    pub interval: Duration,
+    /// Check not answered in time is counted as failed
+    pub timeout: Duration,
+}
+
+impl Default for Probe {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+            timeout: Duration::from_secs(3),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct RegistryEntry {
     pub name: String,
@@ -30,6 +49,9 @@ pub struct RegistryEntry {
     pub status: UnitStatus,
     pub placement: Placement,
     pub watch: bool,
+    pub liveness: Liveness, // Updated by monitor, meaningful only if `watch` is set
+    pub probe: Probe,       // Resolved by admin on registration
+    pub peer: Option<String>, // Certificate hostname of agent which registered entry
 }
 
 impl RegistryEntry {
@@ -63,6 +85,30 @@ impl RegistryEntry {
             Placement::Host => Err(anyhow!("Its a host!")),
         }
     }
+
+    /// Check that VM and TLS name claimed by registering agent are its own,
+    /// i.e. match hostname in its certificate. Otherwise agent could put its
+    /// units into VM (and so VM type) of other agent.
+    ///
+    /// # Errors
+    /// Fails if claimed names differ from `peer` hostname
+    pub(crate) fn check_peer(&self) -> anyhow::Result<()> {
+        let (Some(peer), Placement::Endpoint { endpoint, vm }) = (&self.peer, &self.placement)
+        else {
+            return Ok(());
+        };
+        if endpoint.tls_name != *peer {
+            bail!(
+                "Peer {peer} registers {} with TLS name {}",
+                self.name,
+                endpoint.tls_name
+            );
+        }
+        if !vm.is_empty() && vm != peer {
+            bail!("Peer {peer} registers {} in VM {vm}", self.name);
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -95,6 +141,9 @@ impl RegistryEntry {
                 vm: "bogus".into(),
             },
             watch: true,
+            liveness: Liveness::default(),
+            probe: Probe::default(),
+            peer: None,
         }
     }
 }
@@ -125,6 +174,9 @@ impl TryFrom<pb::RegistryRequest> for RegistryEntry {
                 endpoint,
                 vm: parse_vm_name(&req.parent).unwrap_or_default().into(),
             },
+            liveness: Liveness::default(),
+            probe: Probe::default(),
+            peer: None,
         })
     }
 }
@@ -149,6 +201,7 @@ impl From<RegistryEntry> for QueryResult {
             service_type: val.r#type.service,
             vm_name,
             agent_name,
+            liveness: val.watch.then_some(val.liveness),
         }
     }
 }
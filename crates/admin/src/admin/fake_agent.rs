@@ -0,0 +1,233 @@
+// SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// In-process stand-in for `givc-agent`, serving `UnitControlService` on unix socket
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use givc_common::address::EndpointAddress;
+use givc_common::query::Liveness;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::UnixListenerStream;
+use tonic::{Request, Response, Status};
+
+use super::entry::{Placement, Probe, RegistryEntry};
+use crate::pb;
+use crate::pb::systemd::unit_control_service_server::{
+    UnitControlService, UnitControlServiceServer,
+};
+use crate::types::{EndpointEntry, ServiceType, UnitStatus, UnitType, VmType};
+use crate::utils::tonic::Stream;
+
+static SOCKET_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Default)]
+struct State {
+    units: Mutex<HashMap<String, UnitStatus>>,
+    starts: Mutex<HashMap<String, usize>>,
+    // If set, start_unit brings unit to running state
+    heals: AtomicBool,
+    // If set, get_unit_status never answers
+    hangs: AtomicBool,
+}
+
+pub(crate) fn unit_status(name: &str, active: &str, sub: &str) -> UnitStatus {
+    UnitStatus {
+        name: name.to_owned(),
+        description: format!("Fake {name}"),
+        load_state: "loaded".to_owned(),
+        active_state: active.to_owned(),
+        sub_state: sub.to_owned(),
+        path: String::new(),
+        freezer_state: "running".to_owned(),
+    }
+}
+
+pub(crate) struct FakeAgent {
+    socket: PathBuf,
+    state: Arc<State>,
+    task: JoinHandle<()>,
+}
+
+impl FakeAgent {
+    pub(crate) fn spawn() -> anyhow::Result<Self> {
+        let socket = std::env::temp_dir().join(format!(
+            "givc-fake-agent-{}-{}.sock",
+            std::process::id(),
+            SOCKET_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_file(&socket);
+        let incoming = UnixListenerStream::new(tokio::net::UnixListener::bind(&socket)?);
+        let state = Arc::new(State::default());
+        let service = UnitControlServiceServer::new(FakeUnitControl {
+            state: state.clone(),
+        });
+        let task = tokio::spawn(async move {
+            let _ = tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming(incoming)
+                .await;
+        });
+        Ok(Self {
+            socket,
+            state,
+            task,
+        })
+    }
+
+    /// Registry entry of agent itself
+    pub(crate) fn entry(&self, name: &str, vm: &str) -> RegistryEntry {
+        RegistryEntry {
+            name: name.to_owned(),
+            r#type: UnitType {
+                vm: VmType::AppVM,
+                service: ServiceType::Mgr,
+            },
+            status: unit_status(name, "active", "running"),
+            placement: Placement::Endpoint {
+                endpoint: EndpointEntry {
+                    address: EndpointAddress::Unix(self.socket.to_string_lossy().into_owned()),
+                    tls_name: name.to_owned(),
+                },
+                vm: vm.to_owned(),
+            },
+            watch: false,
+            liveness: Liveness::default(),
+            probe: Probe::default(),
+            peer: None,
+        }
+    }
+
+    pub(crate) fn set_status(&self, name: &str, active: &str, sub: &str) {
+        self.state
+            .units
+            .lock()
+            .unwrap()
+            .insert(name.to_owned(), unit_status(name, active, sub));
+    }
+
+    pub(crate) fn set_heals(&self, heals: bool) {
+        self.state.heals.store(heals, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_hangs(&self, hangs: bool) {
+        self.state.hangs.store(hangs, Ordering::Relaxed);
+    }
+
+    pub(crate) fn starts(&self, name: &str) -> usize {
+        self.state
+            .starts
+            .lock()
+            .unwrap()
+            .get(name)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for FakeAgent {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+struct FakeUnitControl {
+    state: Arc<State>,
+}
+
+impl FakeUnitControl {
+    fn status(&self, name: &str) -> Result<Response<pb::systemd::UnitResponse>, Status> {
+        let status = self
+            .state
+            .units
+            .lock()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Status::not_found(name))?;
+        Ok(Response::new(pb::systemd::UnitResponse {
+            unit_status: Some(status.into()),
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl UnitControlService for FakeUnitControl {
+    async fn get_unit_status(
+        &self,
+        request: Request<pb::systemd::UnitRequest>,
+    ) -> Result<Response<pb::systemd::UnitResponse>, Status> {
+        if self.state.hangs.load(Ordering::Relaxed) {
+            std::future::pending::<()>().await;
+        }
+        self.status(&request.into_inner().unit_name)
+    }
+
+    async fn start_unit(
+        &self,
+        request: Request<pb::systemd::UnitRequest>,
+    ) -> Result<Response<pb::systemd::UnitResponse>, Status> {
+        let name = request.into_inner().unit_name;
+        *self
+            .state
+            .starts
+            .lock()
+            .unwrap()
+            .entry(name.clone())
+            .or_default() += 1;
+        if self.state.heals.load(Ordering::Relaxed) {
+            self.state
+                .units
+                .lock()
+                .unwrap()
+                .insert(name.clone(), unit_status(&name, "active", "running"));
+        }
+        self.status(&name)
+    }
+
+    async fn stop_unit(
+        &self,
+        _request: Request<pb::systemd::UnitRequest>,
+    ) -> Result<Response<pb::systemd::UnitResponse>, Status> {
+        Err(Status::unimplemented("fake"))
+    }
+
+    async fn kill_unit(
+        &self,
+        _request: Request<pb::systemd::UnitRequest>,
+    ) -> Result<Response<pb::systemd::UnitResponse>, Status> {
+        Err(Status::unimplemented("fake"))
+    }
+
+    async fn freeze_unit(
+        &self,
+        _request: Request<pb::systemd::UnitRequest>,
+    ) -> Result<Response<pb::systemd::UnitResponse>, Status> {
+        Err(Status::unimplemented("fake"))
+    }
+
+    async fn unfreeze_unit(
+        &self,
+        _request: Request<pb::systemd::UnitRequest>,
+    ) -> Result<Response<pb::systemd::UnitResponse>, Status> {
+        Err(Status::unimplemented("fake"))
+    }
+
+    type MonitorUnitStream = Stream<pb::systemd::UnitResourceResponse>;
+    async fn monitor_unit(
+        &self,
+        _request: Request<pb::systemd::UnitResourceRequest>,
+    ) -> Result<Response<Self::MonitorUnitStream>, Status> {
+        Err(Status::unimplemented("fake"))
+    }
+
+    async fn start_application(
+        &self,
+        _request: Request<pb::systemd::AppUnitRequest>,
+    ) -> Result<Response<pb::systemd::UnitResponse>, Status> {
+        Err(Status::unimplemented("fake"))
+    }
+}
@@ -0,0 +1,331 @@
+// SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// On-disk snapshot of registry contents, to survive `givc-admin` restarts.
+// Restored entries aren't trusted blindly, `AdminServiceImpl::reconcile` probes
+// them before putting back into the `Registry`.
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use anyhow::Context;
+use givc_common::query::Liveness;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, warn};
+
+use super::entry::{Placement, Probe, RegistryEntry};
+use crate::pb;
+use crate::types::{EndpointEntry, UnitStatus, UnitType};
+
+const JOURNAL_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    entries: Vec<StoredEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredEntry {
+    name: String,
+    r#type: u32,
+    status: StoredStatus,
+    placement: StoredPlacement,
+    watch: bool,
+    #[serde(default)]
+    peer: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredStatus {
+    name: String,
+    description: String,
+    load_state: String,
+    active_state: String,
+    sub_state: String,
+    path: String,
+    freezer_state: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum StoredPlacement {
+    Endpoint {
+        vm: String,
+        protocol: String,
+        address: String,
+        port: String,
+        tls_name: String,
+    },
+    Managed {
+        vm: String,
+        by: String,
+    },
+    Host,
+}
+
+impl From<&RegistryEntry> for StoredEntry {
+    fn from(entry: &RegistryEntry) -> Self {
+        let status = entry.status.clone();
+        Self {
+            name: entry.name.clone(),
+            r#type: entry.r#type.into(),
+            status: StoredStatus {
+                name: status.name,
+                description: status.description,
+                load_state: status.load_state,
+                active_state: status.active_state,
+                sub_state: status.sub_state,
+                path: status.path,
+                freezer_state: status.freezer_state,
+            },
+            placement: match &entry.placement {
+                Placement::Endpoint { endpoint, vm } => {
+                    let tc = pb::TransportConfig::from(endpoint.clone());
+                    StoredPlacement::Endpoint {
+                        vm: vm.clone(),
+                        protocol: tc.protocol,
+                        address: tc.address,
+                        port: tc.port,
+                        tls_name: tc.name,
+                    }
+                }
+                Placement::Managed { vm, by } => StoredPlacement::Managed {
+                    vm: vm.clone(),
+                    by: by.clone(),
+                },
+                Placement::Host => StoredPlacement::Host,
+            },
+            watch: entry.watch,
+            peer: entry.peer.clone(),
+        }
+    }
+}
+
+impl TryFrom<StoredEntry> for RegistryEntry {
+    type Error = anyhow::Error;
+    fn try_from(stored: StoredEntry) -> Result<Self, Self::Error> {
+        let status = stored.status;
+        let placement = match stored.placement {
+            StoredPlacement::Endpoint {
+                vm,
+                protocol,
+                address,
+                port,
+                tls_name,
+            } => Placement::Endpoint {
+                endpoint: EndpointEntry::try_from(pb::TransportConfig {
+                    protocol,
+                    address,
+                    port,
+                    name: tls_name,
+                })?,
+                vm,
+            },
+            StoredPlacement::Managed { vm, by } => Placement::Managed { vm, by },
+            StoredPlacement::Host => Placement::Host,
+        };
+        Ok(Self {
+            r#type: UnitType::try_from(stored.r#type)?,
+            status: UnitStatus {
+                name: status.name,
+                description: status.description,
+                load_state: status.load_state,
+                active_state: status.active_state,
+                sub_state: status.sub_state,
+                path: status.path,
+                freezer_state: status.freezer_state,
+            },
+            placement,
+            watch: stored.watch,
+            liveness: Liveness::default(),
+            // Not persisted, admin resolves it again when entry is reconciled
+            probe: Probe::default(),
+            peer: stored.peer,
+            name: stored.name,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    #[must_use]
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read entries saved by previous run. Missing journal is not an error,
+    /// unparseable entries are skipped.
+    /// # Errors
+    /// Return `Err()` if journal exists, but can't be read or decoded
+    pub fn load(&self) -> anyhow::Result<Vec<RegistryEntry>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("reading registry journal {}", self.path.display()));
+            }
+        };
+        let snapshot: Snapshot = serde_json::from_str(&content)
+            .with_context(|| format!("decoding registry journal {}", self.path.display()))?;
+        anyhow::ensure!(
+            snapshot.version == JOURNAL_VERSION,
+            "unsupported registry journal version {}",
+            snapshot.version
+        );
+        Ok(snapshot
+            .entries
+            .into_iter()
+            .filter_map(|stored| {
+                let name = stored.name.clone();
+                RegistryEntry::try_from(stored)
+                    .inspect_err(|e| warn!("Skipping journal entry {name}: {e}"))
+                    .ok()
+            })
+            .collect())
+    }
+
+    /// Atomically replace journal with `entries`
+    /// # Errors
+    /// Return `Err()` if write fails
+    pub fn save<'a>(&self, entries: impl Iterator<Item = &'a RegistryEntry>) -> anyhow::Result<()> {
+        let snapshot = Snapshot {
+            version: JOURNAL_VERSION,
+            entries: entries.map(StoredEntry::from).collect(),
+        };
+        let tmp = self.path.with_extension("tmp");
+        let mut file =
+            std::fs::File::create(&tmp).with_context(|| format!("creating {}", tmp.display()))?;
+        serde_json::to_writer(&mut file, &snapshot)?;
+        file.flush()?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, &self.path)
+            .with_context(|| format!("replacing registry journal {}", self.path.display()))?;
+        debug!(
+            "Saved {} entries to {}",
+            snapshot.entries.len(),
+            self.path.display()
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+enum Message {
+    Save(Vec<RegistryEntry>),
+    #[cfg(test)]
+    Flush(mpsc::Sender<()>),
+}
+
+/// Background thread, writing registry snapshots to `Journal`, so file IO
+/// never runs under registry lock or on async runtime. Snapshots are written
+/// in order they were queued.
+#[derive(Clone, Debug)]
+pub struct JournalWriter {
+    tx: mpsc::Sender<Message>,
+}
+
+impl JournalWriter {
+    /// Start writer thread, it exits when last `JournalWriter` is dropped.
+    /// # Errors
+    /// Return `Err()` if thread can't be spawned
+    pub fn spawn(journal: Journal) -> anyhow::Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("registry-journal".into())
+            .spawn(move || {
+                for message in rx {
+                    match message {
+                        Message::Save(entries) => {
+                            if let Err(e) = journal.save(entries.iter()) {
+                                error!("Failed to save registry journal: {e:#}");
+                            }
+                        }
+                        #[cfg(test)]
+                        Message::Flush(done) => {
+                            let _ = done.send(());
+                        }
+                    }
+                }
+            })
+            .context("spawning registry journal writer")?;
+        Ok(Self { tx })
+    }
+
+    /// Queue snapshot of registry for writing
+    pub fn save(&self, entries: Vec<RegistryEntry>) {
+        if self.tx.send(Message::Save(entries)).is_err() {
+            error!("Registry journal writer is gone, snapshot dropped");
+        }
+    }
+
+    /// Wait until all queued snapshots are written
+    #[cfg(test)]
+    pub(crate) fn flush(&self) {
+        let (done, wait) = mpsc::channel();
+        if self.tx.send(Message::Flush(done)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_journal(name: &str) -> Journal {
+        let path = std::env::temp_dir().join(format!("givc-{}-{name}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Journal::new(path)
+    }
+
+    #[test]
+    fn test_roundtrip() -> anyhow::Result<()> {
+        let journal = temp_journal("roundtrip");
+        assert!(journal.load()?.is_empty());
+
+        let agent = RegistryEntry {
+            peer: Some("foo-vm".into()),
+            ..RegistryEntry::dummy("givc-foo-vm.service".to_string())
+        };
+        let app = RegistryEntry {
+            placement: Placement::Managed {
+                by: "givc-foo-vm.service".into(),
+                vm: "microvm@foo-vm.service".into(),
+            },
+            ..RegistryEntry::dummy("foo@0.service".to_string())
+        };
+        journal.save([&agent, &app].into_iter())?;
+
+        let restored = journal.load()?;
+        assert_eq!(restored, vec![agent, app]);
+
+        std::fs::remove_file(journal.path())?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_bad_entry_skipped() -> anyhow::Result<()> {
+        let journal = temp_journal("bad-entry");
+        let good = RegistryEntry::dummy("good".to_string());
+        journal.save(std::iter::once(&good))?;
+        let content = std::fs::read_to_string(journal.path())?
+            .replace("]}", r#",{"name":"bad","type":999,"status":{"name":"","description":"","load_state":"","active_state":"","sub_state":"","path":"","freezer_state":""},"placement":{"kind":"host"},"watch":false}]}"#);
+        std::fs::write(journal.path(), content)?;
+
+        assert_eq!(journal.load()?, vec![good]);
+
+        std::fs::remove_file(journal.path())?;
+        Ok(())
+    }
+}
@@ -3,7 +3,12 @@
 
 #[allow(non_snake_case)]
 pub mod OTA;
+pub mod entities;
 pub mod entry;
+#[cfg(test)]
+mod fake_agent;
+pub mod journal;
 pub mod policyclient;
 pub mod registry;
+pub mod restart;
 pub mod server;
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::pb::policyadmin::{
-    StreamPolicyRequest, policy_admin_client::PolicyAdminClient as GrpcPolicyAdminClient,
+    PolicyChange, StreamPolicyRequest,
+    policy_admin_client::PolicyAdminClient as GrpcPolicyAdminClient,
 };
 use anyhow::Result;
 use async_stream::stream;
@@ -52,7 +53,12 @@ impl PolicyAdminClient {
      * # Errors
      * Fails if policy file is inaccessible or sending fails
      */
-    pub async fn upload_policy(&self, name: String, path: PathBuf) -> Result<()> {
+    pub async fn upload_policy(
+        &self,
+        name: String,
+        file_name: String,
+        path: PathBuf,
+    ) -> Result<()> {
         debug!("Uploading policy: {}", name);
         let outbound_stream = stream! {
             let mut file = match File::open(&path).await {
@@ -75,6 +81,9 @@ impl PolicyAdminClient {
                             // Only send name and version on the first chunk to save bandwidth
                             policy_name: if is_first_chunk { name.clone() } else { String::new() },
                             policy_chunk: buffer[..n].to_vec(),
+                            change: PolicyChange::Update.into(),
+                            file_name: if is_first_chunk { file_name.clone() } else { String::new() },
+                            new_file_name: String::new(),
                         };
                         is_first_chunk = false;
                     }
@@ -88,4 +97,49 @@ impl PolicyAdminClient {
 
         self.stream_policy(outbound_stream).await
     }
+
+    /**
+     * Notifies the target VM that policy file was deleted, so it stops enforcing it.
+     * Empty `file_name` removes the policy, whichever file it was installed from.
+     *
+     * # Errors
+     * Fails if sending fails
+     */
+    pub async fn delete_policy(&self, name: String, file_name: String) -> Result<()> {
+        debug!("Deleting policy: {} ({})", name, file_name);
+        self.stream_policy(tokio_stream::iter([StreamPolicyRequest {
+            policy_name: name,
+            policy_chunk: Vec::new(),
+            change: PolicyChange::Delete.into(),
+            file_name,
+            new_file_name: String::new(),
+        }]))
+        .await
+    }
+
+    /**
+     * Notifies the target VM that policy file was renamed.
+     *
+     * # Errors
+     * Fails if sending fails
+     */
+    pub async fn rename_policy(
+        &self,
+        name: String,
+        file_name: String,
+        new_file_name: String,
+    ) -> Result<()> {
+        debug!(
+            "Renaming policy: {} ({} -> {})",
+            name, file_name, new_file_name
+        );
+        self.stream_policy(tokio_stream::iter([StreamPolicyRequest {
+            policy_name: name,
+            policy_chunk: Vec::new(),
+            change: PolicyChange::Rename.into(),
+            file_name,
+            new_file_name,
+        }]))
+        .await
+    }
 }
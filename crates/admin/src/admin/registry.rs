@@ -3,13 +3,15 @@
 
 use std::collections::hash_map::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 
 use anyhow::{Context, anyhow, bail};
 use givc_common::query::{Event, QueryResult};
 use tokio::sync::broadcast;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use super::entry::RegistryEntry;
+use super::journal::{Journal, JournalWriter};
 use crate::types::{UnitStatus, UnitType};
 use crate::utils::naming::parse_vm_name;
 
@@ -38,6 +40,8 @@ pub struct Registry {
     /// sections are very small.
     map: Arc<Mutex<HashMap<String, RegistryEntry>>>,
     pubsub: broadcast::Sender<Event>,
+    /// Optional on-disk snapshot, rewritten on every registration change
+    journal: Option<JournalWriter>,
 }
 
 impl Default for Registry {
@@ -52,6 +56,37 @@ impl Registry {
         Self {
             map: Arc::new(Mutex::new(HashMap::new())),
             pubsub: broadcast::Sender::new(16),
+            journal: None,
+        }
+    }
+
+    /// Create empty registry persisted to `journal`.
+    /// Return it with entries saved by previous run, which need to be reconciled
+    /// before registering them again. Journal, which can't be read, is
+    /// ignored and overwritten by the next change.
+    /// # Errors
+    /// Return `Err()` if journal writer can't be started
+    pub fn with_journal(journal: Journal) -> anyhow::Result<(Self, Vec<RegistryEntry>)> {
+        let restored = journal.load().unwrap_or_else(|e| {
+            warn!("Ignoring registry journal: {e:#}, starting with empty registry");
+            Vec::new()
+        });
+        info!(
+            "Loaded {} entries from registry journal {}",
+            restored.len(),
+            journal.path().display()
+        );
+        let registry = Self {
+            journal: Some(JournalWriter::spawn(journal)?),
+            ..Self::new()
+        };
+        Ok((registry, restored))
+    }
+
+    /// Queue snapshot of `state`, journal is written by background thread
+    fn persist(&self, state: &HashMap<String, RegistryEntry>) {
+        if let Some(journal) = &self.journal {
+            journal.save(state.values().cloned().collect());
         }
     }
 
@@ -63,6 +98,7 @@ impl Registry {
             debug!("Replaced old entry {:?}", old);
             self.send_event(Event::UnitShutdown(old.into()));
         }
+        self.persist(&state);
         info!("Sending event {event:?}");
         self.send_event(event);
     }
@@ -79,6 +115,7 @@ impl Registry {
                     self.send_event(Event::UnitShutdown(entry.into()));
                 }
                 info!("Deregistering {entry:?}");
+                self.persist(&state);
                 self.send_event(Event::UnitShutdown(entry.into()));
                 Ok(())
             }
@@ -134,7 +171,6 @@ impl Registry {
         }
     }
 
-    #[allow(dead_code)]
     pub(crate) fn contains(&self, name: &str) -> bool {
         let state = self.map.lock().unwrap();
         state.contains_key(name)
@@ -157,17 +193,50 @@ impl Registry {
         state.values().filter(|x| x.watch).cloned().collect()
     }
 
-    pub(crate) fn update_state(&self, name: &str, status: UnitStatus) -> anyhow::Result<()> {
+    /// Save `status` of `name`, reported by successful health check which took `latency`
+    pub(crate) fn update_state(
+        &self,
+        name: &str,
+        status: UnitStatus,
+        latency: Duration,
+    ) -> anyhow::Result<()> {
         let mut state = self.map.lock().unwrap();
         state
             .get_mut(name)
             .map(|e| {
                 e.status = status;
+                e.liveness.last_seen = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .ok()
+                    .map(|t| t.as_secs());
+                e.liveness.latency_ms = Some(latency.as_millis().try_into().unwrap_or(u64::MAX));
+                e.liveness.consecutive_failures = 0;
                 self.send_event(Event::UnitStatusChanged(e.clone().into()));
             })
             .with_context(|| format!("Can't update state for {name}, is not registered"))
     }
 
+    /// Account failed (or timed out) health check of `name`.
+    /// Return number of consecutive failures.
+    pub(crate) fn update_unreachable(&self, name: &str) -> anyhow::Result<u32> {
+        let mut state = self.map.lock().unwrap();
+        state
+            .get_mut(name)
+            .map(|e| {
+                e.liveness.consecutive_failures += 1;
+                self.send_event(Event::UnitStatusChanged(e.clone().into()));
+                e.liveness.consecutive_failures
+            })
+            .with_context(|| format!("Can't update state for {name}, is not registered"))
+    }
+
+    /// Notify subscribers, that `name` failed permanently
+    pub(crate) fn report_failed(&self, name: &str) -> anyhow::Result<()> {
+        let entry = self.by_name(name)?;
+        self.send_event(Event::UnitFailed(entry.into()));
+        Ok(())
+    }
+
     // FIXME: Should we dump full contents here for `query`/`query_list` high-level API
     // FIXME: .by_types_many() should works, but I add this one for debug convenience
     pub(crate) fn contents(&self) -> Vec<RegistryEntry> {
@@ -258,6 +327,45 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_journal_persistence() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("givc-registry-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let (r, restored) = Registry::with_journal(Journal::new(path.clone()))?;
+        assert!(restored.is_empty());
+        r.register(RegistryEntry::dummy("foo".to_string()));
+        r.register(RegistryEntry::dummy("bar".to_string()));
+        r.deregister("bar")?;
+        r.journal.as_ref().unwrap().flush();
+
+        // Restored entries are handed out for reconciliation, not registered
+        let (r2, restored) = Registry::with_journal(Journal::new(path.clone()))?;
+        assert_eq!(restored, vec![RegistryEntry::dummy("foo".to_string())]);
+        assert!(!r2.contains("foo"));
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_journal_ignored() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("givc-corrupt-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"version":1,"entries":[{"name":"#)?;
+
+        let (r, restored) = Registry::with_journal(Journal::new(path.clone()))?;
+        assert!(restored.is_empty());
+        r.register(RegistryEntry::dummy("foo".to_string()));
+        r.journal.as_ref().unwrap().flush();
+        assert_eq!(
+            Journal::new(path.clone()).load()?,
+            vec![RegistryEntry::dummy("foo".to_string())]
+        );
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
     #[test]
     fn test_unique_name() -> anyhow::Result<()> {
         let r = Registry::new();
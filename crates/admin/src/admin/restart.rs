@@ -0,0 +1,418 @@
+// SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Restart policies, used by monitor to decide how to recover failed units
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use super::entry::{Probe, RegistryEntry};
+use crate::types::{ServiceType, UnitType, VmType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartMode {
+    /// Never restart, exited applications are just removed from registry
+    Never,
+    /// Restart only units which failed, or which agent is unreachable
+    OnFailure,
+    /// Restart also units which exited cleanly
+    Always,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RestartPolicy {
+    pub mode: RestartMode,
+    /// Give up after this many restart attempts without recovering, `None` means retry forever
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    /// Delay before second attempt, doubled on each next attempt
+    #[serde(default = "default_backoff_secs")]
+    pub backoff_secs: u64,
+    #[serde(default = "default_max_backoff_secs")]
+    pub max_backoff_secs: u64,
+    /// Health check schedule of matched units, monitor defaults are used if unset
+    #[serde(default)]
+    pub probe_interval_secs: Option<u64>,
+    #[serde(default)]
+    pub probe_timeout_secs: Option<u64>,
+}
+
+fn default_backoff_secs() -> u64 {
+    5
+}
+
+fn default_max_backoff_secs() -> u64 {
+    300
+}
+
+impl RestartPolicy {
+    #[must_use]
+    pub fn never() -> Self {
+        Self::new(RestartMode::Never)
+    }
+
+    #[must_use]
+    pub fn new(mode: RestartMode) -> Self {
+        Self {
+            mode,
+            max_retries: None,
+            backoff_secs: default_backoff_secs(),
+            max_backoff_secs: default_max_backoff_secs(),
+            probe_interval_secs: None,
+            probe_timeout_secs: None,
+        }
+    }
+
+    /// Delay after `attempt` (zero-based) before next one is allowed
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let secs = self
+            .backoff_secs
+            .saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX))
+            .min(self.max_backoff_secs);
+        Duration::from_secs(secs)
+    }
+
+    fn wants_restart(&self, failure: Failure) -> bool {
+        match (self.mode, failure) {
+            (RestartMode::Never, _) | (RestartMode::OnFailure, Failure::Exited) => false,
+            (RestartMode::OnFailure, Failure::Failed) | (RestartMode::Always, _) => true,
+        }
+    }
+}
+
+/// What's wrong with monitored unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    /// Unit is inactive, but not failed
+    Exited,
+    /// Unit failed, has invalid status, or its agent unreachable
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// Policy don't restart this unit
+    Ignore,
+    /// Restart is due, but backoff delay is not passed yet
+    Wait,
+    Restart,
+    /// Retries exhausted, returned only once
+    GiveUp,
+}
+
+/// Recovery progress of single unit
+#[derive(Debug, Clone, Default)]
+pub struct RestartState {
+    attempts: u32,
+    next_attempt: Option<Instant>,
+    gave_up: bool,
+}
+
+impl RestartState {
+    #[must_use]
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn decide(&mut self, policy: &RestartPolicy, failure: Failure, now: Instant) -> Decision {
+        if self.gave_up || !policy.wants_restart(failure) {
+            return Decision::Ignore;
+        }
+        if policy.max_retries.is_some_and(|max| self.attempts >= max) {
+            self.gave_up = true;
+            return Decision::GiveUp;
+        }
+        if self.next_attempt.is_some_and(|next| now < next) {
+            return Decision::Wait;
+        }
+        self.next_attempt = Some(now + policy.backoff(self.attempts));
+        self.attempts += 1;
+        Decision::Restart
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selector {
+    Name(String),
+    Type(UnitType),
+}
+
+impl Selector {
+    fn matches(&self, entry: &RegistryEntry) -> bool {
+        match self {
+            Self::Name(name) => &entry.name == name,
+            Self::Type(ty) => &entry.r#type == ty,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRule {
+    name: Option<String>,
+    vm: Option<String>,
+    service: Option<String>,
+    policy: RestartPolicy,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawPolicies {
+    default: Option<RestartPolicy>,
+    #[serde(default)]
+    rules: Vec<RawRule>,
+}
+
+/// Ordered list of rules, first match wins. Name rules always take precedence over type rules.
+#[derive(Debug, Clone)]
+pub struct RestartPolicies {
+    rules: Vec<(Selector, RestartPolicy)>,
+    default: RestartPolicy,
+    states: HashMap<String, RestartState>,
+}
+
+impl Default for RestartPolicies {
+    /// Same recovery as before policies were configurable: dead VM agents trigger
+    /// VM restart, exited applications are removed from registry.
+    fn default() -> Self {
+        let mgr = |vm| {
+            (
+                Selector::Type(UnitType {
+                    vm,
+                    service: ServiceType::Mgr,
+                }),
+                RestartPolicy::new(RestartMode::Always),
+            )
+        };
+        Self {
+            rules: vec![mgr(VmType::AppVM), mgr(VmType::SysVM)],
+            default: RestartPolicy::never(),
+            states: HashMap::new(),
+        }
+    }
+}
+
+impl FromStr for RestartPolicies {
+    type Err = anyhow::Error;
+    fn from_str(json: &str) -> Result<Self, Self::Err> {
+        let raw: RawPolicies = serde_json::from_str(json)?;
+        let defaults = Self::default();
+        let rules = raw
+            .rules
+            .into_iter()
+            .map(|rule| {
+                let selector = match (rule.name, rule.vm, rule.service) {
+                    (Some(name), None, None) => Selector::Name(name),
+                    (None, Some(vm), Some(service)) => Selector::Type(UnitType {
+                        vm: VmType::from_str(&vm)
+                            .with_context(|| format!("unknown vm type {vm}"))?,
+                        service: ServiceType::from_str(&service)
+                            .with_context(|| format!("unknown service type {service}"))?,
+                    }),
+                    _ => anyhow::bail!("rule must have either `name`, or both `vm` and `service`"),
+                };
+                Ok((selector, rule.policy))
+            })
+            // Configured rules go first, built-in ones are still used as fallback
+            .chain(defaults.rules.into_iter().map(Ok))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            rules,
+            default: raw.default.unwrap_or(defaults.default),
+            states: HashMap::new(),
+        })
+    }
+}
+
+impl RestartPolicies {
+    /// Read policies from JSON file
+    /// # Errors
+    /// Return `Err()` if file can't be read or parsed
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("reading restart policies {}", path.display()))?
+            .parse()
+            .with_context(|| format!("parsing restart policies {}", path.display()))
+    }
+
+    #[must_use]
+    pub fn policy_for(&self, entry: &RegistryEntry) -> &RestartPolicy {
+        let by_name = self
+            .rules
+            .iter()
+            .find(|(sel, _)| matches!(sel, Selector::Name(_)) && sel.matches(entry));
+        by_name
+            .or_else(|| self.rules.iter().find(|(sel, _)| sel.matches(entry)))
+            .map_or(&self.default, |(_, policy)| policy)
+    }
+
+    /// Health check schedule of `entry`, with `default` filling what policy doesn't set
+    #[must_use]
+    pub fn probe_for(&self, entry: &RegistryEntry, default: Probe) -> Probe {
+        let policy = self.policy_for(entry);
+        Probe {
+            interval: policy
+                .probe_interval_secs
+                .map_or(default.interval, Duration::from_secs),
+            timeout: policy
+                .probe_timeout_secs
+                .map_or(default.timeout, Duration::from_secs),
+        }
+    }
+
+    /// Decide what to do with failed `entry`, and account the attempt.
+    /// Applications are never restarted: they are transient units, started with
+    /// arguments which registry doesn't keep.
+    pub fn decide(&mut self, entry: &RegistryEntry, failure: Failure, now: Instant) -> Decision {
+        if entry.r#type.service == ServiceType::App {
+            return Decision::Ignore;
+        }
+        let policy = self.policy_for(entry).clone();
+        self.states
+            .entry(entry.name.clone())
+            .or_default()
+            .decide(&policy, failure, now)
+    }
+
+    /// Entry is healthy again (or gone), forget its failures
+    pub fn reset(&mut self, name: &str) {
+        self.states.remove(name);
+    }
+
+    #[must_use]
+    pub fn attempts(&self, name: &str) -> u32 {
+        self.states.get(name).map_or(0, RestartState::attempts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RestartPolicy {
+            backoff_secs: 2,
+            max_backoff_secs: 30,
+            ..RestartPolicy::new(RestartMode::Always)
+        };
+        assert_eq!(policy.backoff(0), Duration::from_secs(2));
+        assert_eq!(policy.backoff(1), Duration::from_secs(4));
+        assert_eq!(policy.backoff(3), Duration::from_secs(16));
+        assert_eq!(policy.backoff(4), Duration::from_secs(30));
+        assert_eq!(policy.backoff(200), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_decide() {
+        let policy = RestartPolicy {
+            max_retries: Some(2),
+            backoff_secs: 10,
+            ..RestartPolicy::new(RestartMode::OnFailure)
+        };
+        let mut state = RestartState::default();
+        let t0 = Instant::now();
+
+        assert_eq!(state.decide(&policy, Failure::Exited, t0), Decision::Ignore);
+        assert_eq!(
+            state.decide(&policy, Failure::Failed, t0),
+            Decision::Restart
+        );
+        assert_eq!(
+            state.decide(&policy, Failure::Failed, t0 + Duration::from_secs(5)),
+            Decision::Wait
+        );
+        assert_eq!(
+            state.decide(&policy, Failure::Failed, t0 + Duration::from_secs(10)),
+            Decision::Restart
+        );
+        let later = t0 + Duration::from_secs(100);
+        assert_eq!(
+            state.decide(&policy, Failure::Failed, later),
+            Decision::GiveUp
+        );
+        assert_eq!(
+            state.decide(&policy, Failure::Failed, later),
+            Decision::Ignore
+        );
+        assert_eq!(state.attempts(), 2);
+    }
+
+    #[test]
+    fn test_parse_and_match() -> anyhow::Result<()> {
+        let mut policies: RestartPolicies = r#"{
+            "default": {"mode": "on-failure", "max_retries": 1},
+            "rules": [
+                {"vm": "AppVM", "service": "App", "policy": {"mode": "never"}},
+                {"name": "chrome@0.service", "policy": {"mode": "always", "backoff_secs": 0, "probe_timeout_secs": 10}}
+            ]
+        }"#
+        .parse()?;
+
+        let app = RegistryEntry::dummy("foot@0.service".to_string());
+        let chrome = RegistryEntry {
+            r#type: UnitType {
+                vm: VmType::AppVM,
+                service: ServiceType::Svc,
+            },
+            ..RegistryEntry::dummy("chrome@0.service".to_string())
+        };
+        assert_eq!(policies.policy_for(&app).mode, RestartMode::Never);
+        assert_eq!(policies.policy_for(&chrome).mode, RestartMode::Always);
+
+        let default = Probe::default();
+        assert_eq!(policies.probe_for(&app, default), default);
+        assert_eq!(
+            policies.probe_for(&chrome, default),
+            Probe {
+                interval: default.interval,
+                timeout: Duration::from_secs(10),
+            }
+        );
+
+        let mgr = RegistryEntry {
+            r#type: UnitType {
+                vm: VmType::AppVM,
+                service: ServiceType::Mgr,
+            },
+            ..RegistryEntry::dummy("givc-foo-vm.service".to_string())
+        };
+        assert_eq!(policies.policy_for(&mgr).mode, RestartMode::Always);
+
+        let now = Instant::now();
+        assert_eq!(
+            policies.decide(&chrome, Failure::Exited, now),
+            Decision::Restart
+        );
+        assert_eq!(policies.attempts("chrome@0.service"), 1);
+        policies.reset("chrome@0.service");
+        assert_eq!(policies.attempts("chrome@0.service"), 0);
+
+        // Rule matches application by name, but it can't be restarted
+        let chrome_app = RegistryEntry {
+            r#type: UnitType {
+                vm: VmType::AppVM,
+                service: ServiceType::App,
+            },
+            ..chrome
+        };
+        assert_eq!(
+            policies.decide(&chrome_app, Failure::Failed, now),
+            Decision::Ignore
+        );
+
+        assert!(
+            r#"{"rules": [{"vm": "AppVM", "policy": {"mode": "never"}}]}"#
+                .parse::<RestartPolicies>()
+                .is_err()
+        );
+        Ok(())
+    }
+}
@@ -3,7 +3,12 @@
 
 #![allow(clippy::similar_names)]
 
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::{Context, Result, anyhow, bail, ensure};
 use async_stream::try_stream;
@@ -14,23 +19,29 @@ use tonic::{Code, Response, Status};
 use tracing::{debug, error, info, trace};
 
 use givc_client::endpoint::{EndpointConfig, TlsConfig};
-use givc_common::query::QueryResult;
+use givc_common::query::{Liveness, QueryResult};
 use givc_policyadmin::policy::run_policy_admin;
-use givc_policyadmin::policy_manager::{PolicyManager, Update, UpdateReceiver};
+use givc_policyadmin::policy_manager::{FileChange, PolicyManager, Update, UpdateReceiver};
 
-use crate::admin::entry::{Placement, RegistryEntry};
+use crate::admin::entry::{Placement, Probe, RegistryEntry};
+use crate::admin::journal::Journal;
 use crate::admin::policyclient::PolicyAdminClient;
 use crate::admin::registry::Registry;
+use crate::admin::restart::{Decision, Failure, RestartPolicies};
 use crate::pb::{
-    self, ApplicationRequest, ApplicationResponse, Empty, ListGenerationsResponse, LocaleRequest,
-    QueryListResponse, RegistryRequest, RegistryResponse, SetGenerationRequest,
-    SetGenerationResponse, StartResponse, StartVmRequest, TimezoneRequest, UnitStatusRequest,
-    WatchItem, ctap::CtapRequest, ctap::CtapResponse,
+    self, ApplicationRequest, ApplicationResponse, CollectGarbageRequest, CollectGarbageResponse,
+    Empty, ListGenerationsResponse, LocaleRequest, PolicyDeliveryStatus, PolicyStatusRequest,
+    PolicyStatusResponse, QueryListResponse, RegistryRequest, RegistryResponse,
+    RollbackGenerationResponse, SetGenerationRequest, SetGenerationResponse, StartResponse,
+    StartVmRequest, TimezoneRequest, UnitStatusRequest, WatchItem, ctap::CtapRequest,
+    ctap::CtapResponse,
 };
 use crate::systemd_api::client::SystemDClient;
 use crate::types::{ServiceType, UnitType, VmType};
+use crate::utils::access_control::EntityStore;
 use crate::utils::naming::VmName;
 use crate::utils::tonic::{Stream, escalate};
+use crate::utils::x509::SecurityInfo;
 
 pub use pb::admin_service_server::AdminServiceServer;
 
@@ -38,6 +49,11 @@ const VM_STARTUP_TIME: Duration = Duration::new(10, 0);
 const TIMEZONE_CONF: &str = "/etc/timezone.conf";
 const LOCALE_CONF: &str = "/etc/locale-givc.conf";
 
+/// Health checks of watched entries. Each entry is probed by its own task,
+/// so a hung agent delays only checks of its own units. This schedule is used
+/// for entries which restart policy doesn't give own one.
+pub type MonitorConfig = Probe;
+
 // FIXME: this is almost copy of sysfsm::Event.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum State {
@@ -54,6 +70,8 @@ struct AdminServiceImpl {
     locale_assigns: Mutex<Vec<pb::locale::LocaleAssignment>>,
     timezone: Mutex<String>,
     policy_manager: Option<Arc<PolicyManager>>,
+    restart_policies: std::sync::Mutex<RestartPolicies>,
+    monitor_config: MonitorConfig,
 }
 
 #[derive(Clone)]
@@ -79,18 +97,25 @@ impl Validator {
 
 impl AdminService {
     /**
-     * Create new `AdminService` instance
+     * Create new `AdminService` instance, `monitoring` is `None` if watched
+     * entries shouldn't be health checked periodically.
      *
      * # Errors
      * Fails if policy setup fails.
      */
     pub fn new(
         use_tls: Option<TlsConfig>,
-        monitoring: bool,
+        monitoring: Option<MonitorConfig>,
         enable_policy_admin: bool,
         policy_store: Option<PathBuf>,
         policy_config: Option<String>,
+        registry_journal: Option<PathBuf>,
+        restart_policies: RestartPolicies,
     ) -> anyhow::Result<Self> {
+        let (registry, restored) = match registry_journal {
+            Some(path) => Registry::with_journal(Journal::new(path))?,
+            None => (Registry::new(), Vec::new()),
+        };
         let (manager, updates) = if enable_policy_admin {
             Some(AdminServiceImpl::setup_policy_admin(
                 policy_store,
@@ -100,8 +125,20 @@ impl AdminService {
             None
         }
         .unzip();
-        let inner = Arc::new(AdminServiceImpl::new(use_tls, manager));
-        if monitoring {
+        let inner = Arc::new(AdminServiceImpl::new(
+            registry,
+            use_tls,
+            manager,
+            restart_policies,
+            monitoring.unwrap_or_default(),
+        ));
+        if !restored.is_empty() {
+            let clone = inner.clone();
+            tokio::task::spawn(async move {
+                clone.reconcile(restored).await;
+            });
+        }
+        if monitoring.is_some() {
             let clone = inner.clone();
             tokio::task::spawn(async move {
                 clone.monitor().await;
@@ -116,22 +153,39 @@ impl AdminService {
                         vm_name,
                         file,
                         policy,
+                        change,
                     },
                     tx,
                 )) = updates.recv().await
                 {
-                    let _ = tx.send(inner.push_policy_update(&vm_name, &file, &policy).await);
+                    let _ = tx.send(
+                        inner
+                            .push_policy_update(&vm_name, &file, &policy, change)
+                            .await,
+                    );
                 }
             });
         }
 
         Ok(Self { inner })
     }
+
+    /// Registry contents as cedar entities, for access control
+    #[must_use]
+    pub fn entity_store(&self) -> Arc<dyn EntityStore> {
+        Arc::new(self.inner.registry.clone())
+    }
 }
 
 impl AdminServiceImpl {
     #[must_use]
-    fn new(use_tls: Option<TlsConfig>, policy_manager: Option<Arc<PolicyManager>>) -> Self {
+    fn new(
+        registry: Registry,
+        use_tls: Option<TlsConfig>,
+        policy_manager: Option<Arc<PolicyManager>>,
+        restart_policies: RestartPolicies,
+        monitor_config: MonitorConfig,
+    ) -> Self {
         let timezone = std::fs::read_to_string(TIMEZONE_CONF)
             .ok()
             .and_then(|l| l.lines().next().map(ToOwned::to_owned))
@@ -152,12 +206,14 @@ impl AdminServiceImpl {
             })
             .unwrap_or_default();
         Self {
-            registry: Registry::new(),
+            registry,
             state: State::Init,
             tls_config: use_tls,
             timezone: Mutex::new(timezone),
             locale_assigns: Mutex::new(locale_assigns),
             policy_manager,
+            restart_policies: std::sync::Mutex::new(restart_policies),
+            monitor_config,
         }
     }
 
@@ -241,6 +297,7 @@ impl AdminServiceImpl {
         vm_name: &str,
         policy_file_path: &std::path::Path,
         policy_name: &str,
+        change: FileChange,
     ) -> anyhow::Result<()> {
         let agent_service_name = VmName::Vm(vm_name).agent_service();
         info!(
@@ -253,15 +310,33 @@ impl AdminServiceImpl {
         })?;
 
         let client = PolicyAdminClient::new(endpoint);
+        let file_name = policy_file_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
 
-        /* For safe streaming  */
-        let temp_path = policy_file_path.with_extension("tmp");
-        tokio::fs::copy(policy_file_path, &temp_path).await?;
-        let result = client
-            .upload_policy(policy_name.to_string(), temp_path.clone())
-            .await;
-        let _ = tokio::fs::remove_file(&temp_path).await;
-        result
+        match change {
+            FileChange::Update => {
+                /* For safe streaming  */
+                let temp_path = policy_file_path.with_extension("tmp");
+                tokio::fs::copy(policy_file_path, &temp_path).await?;
+                let result = client
+                    .upload_policy(policy_name.to_string(), file_name, temp_path.clone())
+                    .await;
+                let _ = tokio::fs::remove_file(&temp_path).await;
+                result
+            }
+            FileChange::Delete => {
+                client
+                    .delete_policy(policy_name.to_string(), file_name)
+                    .await
+            }
+            FileChange::Rename(new_name) => {
+                client
+                    .rename_policy(policy_name.to_string(), file_name, new_name)
+                    .await
+            }
+        }
     }
 
     async fn start_unit_on_vm(&self, unit: &str, vmname: &str) -> anyhow::Result<String> {
@@ -365,39 +440,74 @@ impl AdminServiceImpl {
         }
     }
 
-    async fn handle_error(&self, entry: RegistryEntry) -> anyhow::Result<()> {
+    /// Recover failed `entry`, according to its restart policy
+    async fn handle_error(&self, entry: RegistryEntry, failure: Failure) -> anyhow::Result<()> {
         info!(
-            "Handling error for {} vm type {} service type {}",
+            "Handling {failure:?} for {} vm type {} service type {}",
             entry.name, entry.r#type.vm, entry.r#type.service
         );
-        match (entry.r#type.vm, entry.r#type.service) {
-            (VmType::AppVM, ServiceType::App) => {
-                if entry.status.is_exitted() {
+        let decision = self.restart_policies.lock().unwrap().decide(
+            &entry,
+            failure,
+            std::time::Instant::now(),
+        );
+        match decision {
+            Decision::Restart => {
+                info!("Restarting {}", entry.name);
+                self.restart(&entry)
+                    .await
+                    .with_context(|| format!("handling error, by restart {}", entry.name))
+            }
+            Decision::Wait => {
+                debug!("Restart of {} postponed by backoff", entry.name);
+                Ok(())
+            }
+            Decision::GiveUp => {
+                error!("Restart policy gave up recovering {}", entry.name);
+                self.registry.report_failed(&entry.name)
+            }
+            Decision::Ignore => {
+                // Exited application is gone, nothing to monitor anymore
+                if entry.r#type.service == ServiceType::App && failure == Failure::Exited {
                     debug!("Deregister exitted {}", entry.name);
+                    self.restart_policies.lock().unwrap().reset(&entry.name);
                     self.registry.deregister(&entry.name)?;
                 }
                 Ok(())
             }
-            (VmType::AppVM | VmType::SysVM, ServiceType::Mgr) => {
-                if let Placement::Managed { vm: vm_name, .. } = entry.placement {
-                    self.start_vm(&vm_name)
-                        .await
-                        .with_context(|| format!("handing error, by restart VM {}", entry.name))?;
-                }
-                Ok(()) // FIXME: should use `?` from line above, why it didn't work?
+        }
+    }
+
+    async fn restart(&self, entry: &RegistryEntry) -> anyhow::Result<()> {
+        match (entry.r#type.service, &entry.placement) {
+            (ServiceType::VM, _) => self.start_vm(&entry.name).await,
+            (ServiceType::Mgr, Placement::Managed { vm, .. }) => self.start_vm(vm).await,
+            (ServiceType::Mgr, Placement::Endpoint { vm, .. }) if !vm.is_empty() => {
+                self.start_vm(&VmName::Vm(vm).vm_service()).await
             }
-            (x, y) => {
-                error!("Don't known how to handle_error for VM type: {x:?}:{y:?}");
+            (ServiceType::Svc, Placement::Managed { by, .. }) => {
+                let client = SystemDClient::new(self.agent_endpoint(by)?);
+                client.start_remote(entry.name.clone()).await?;
                 Ok(())
             }
+            (x, _) => bail!("Don't known how to restart {} of type {x:?}", entry.name),
         }
     }
 
     async fn monitor_routine(&self, entry: RegistryEntry) -> anyhow::Result<()> {
-        match self.get_remote_status(&entry).await {
+        let started = std::time::Instant::now();
+        let timeout = entry.probe.timeout;
+        let probe = tokio::time::timeout(timeout, self.get_remote_status(&entry))
+            .await
+            .unwrap_or_else(|_| Err(anyhow!("no answer in {timeout:?}")));
+        match probe {
             Err(err) => {
-                error!("could not get status of unit {}: {}", entry.name, err);
-                self.handle_error(entry)
+                let failures = self.registry.update_unreachable(&entry.name)?;
+                error!(
+                    "could not get status of unit {} ({failures} times in a row): {err}",
+                    entry.name
+                );
+                self.handle_error(entry, Failure::Failed)
                     .await
                     .context("during handle error")?;
             }
@@ -414,41 +524,106 @@ impl AdminServiceImpl {
                         entry.name, status.active_state
                     );
                 }
+                let failure = if invalid || status.active_state == "failed" {
+                    Some(Failure::Failed)
+                } else {
+                    inactive.then_some(Failure::Exited)
+                };
 
                 trace!("Status of {} is {:?} (updated)", entry.name, status);
                 // We have immutable copy of entry here, but need update _in registry_ copy
-                self.registry.update_state(&entry.name, status)?;
+                self.registry
+                    .update_state(&entry.name, status, started.elapsed())?;
 
-                if invalid || inactive {
-                    self.handle_error(entry)
+                if let Some(failure) = failure {
+                    self.handle_error(entry, failure)
                         .await
                         .context("during handle error")?;
+                } else {
+                    self.restart_policies.lock().unwrap().reset(&entry.name);
                 }
             }
         }
         Ok(())
     }
 
-    async fn monitor(&self) {
+    /// Put entries restored from journal back into registry, but only these which
+    /// agents still confirm. Agents are probed first, because managed entries
+    /// are reachable only via their (already restored) agent.
+    async fn reconcile(&self, restored: Vec<RegistryEntry>) {
+        let (agents, managed): (Vec<_>, Vec<_>) = restored
+            .into_iter()
+            .partition(|entry| matches!(entry.placement, Placement::Endpoint { .. }));
+        for entry in agents.into_iter().chain(managed) {
+            if self.registry.contains(&entry.name) {
+                debug!("{} re-registered before reconciliation", entry.name);
+                continue;
+            }
+            if entry.placement == Placement::Host {
+                debug!("Dropping restored host entry {}", entry.name);
+                continue;
+            }
+            match self.get_remote_status(&entry).await {
+                Ok(status) if !status.is_exitted() => {
+                    info!("Restored {} from registry journal", entry.name);
+                    self.register(RegistryEntry { status, ..entry });
+                }
+                Ok(_) => info!("Dropping restored {}, unit is not running", entry.name),
+                Err(err) => info!(
+                    "Dropping restored {}, agent not reachable: {err}",
+                    entry.name
+                ),
+            }
+        }
+    }
+
+    /// Spawn probe task for each newly watched entry, finished tasks are reaped
+    async fn monitor(self: Arc<Self>) {
         use tokio::time::{MissedTickBehavior, interval};
-        let mut watch = interval(Duration::from_secs(5));
-        watch.set_missed_tick_behavior(MissedTickBehavior::Delay);
-        watch.tick().await; // First tick fires instantly
+        let mut probes: HashMap<String, tokio::task::JoinHandle<()>> = HashMap::new();
+        let mut scan = interval(self.monitor_config.interval);
+        scan.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        scan.tick().await; // First tick fires instantly
         loop {
-            watch.tick().await;
-            let watch_list = self.registry.watch_list();
-            for entry in watch_list {
-                trace!("Monitoring {}", entry.name);
-                let name = entry.name.clone();
-                if let Err(err) = self.monitor_routine(entry).await {
-                    error!("Error during watch {}: {err}", name);
+            scan.tick().await;
+            probes.retain(|_, task| !task.is_finished());
+            for entry in self.registry.watch_list() {
+                if let Entry::Vacant(slot) = probes.entry(entry.name) {
+                    let this = self.clone();
+                    let name = slot.key().clone();
+                    slot.insert(tokio::spawn(async move { this.probe_loop(name).await }));
                 }
             }
         }
     }
 
-    // Refactoring kludge
-    pub fn register(&self, entry: RegistryEntry) {
+    /// Check `name` on its own schedule, until it is deregistered or not watched anymore.
+    /// Schedule is re-read on each check, so re-registration may change it.
+    async fn probe_loop(&self, name: String) {
+        loop {
+            let Ok(entry) = self.registry.by_name(&name) else {
+                break;
+            };
+            if !entry.watch {
+                break;
+            }
+            let next = tokio::time::Instant::now() + entry.probe.interval;
+            trace!("Monitoring {name}");
+            if let Err(err) = self.monitor_routine(entry).await {
+                error!("Error during watch {name}: {err}");
+            }
+            tokio::time::sleep_until(next).await;
+        }
+        debug!("Stopped monitoring {name}");
+    }
+
+    /// Put `entry` into registry, with health check schedule resolved from restart policies
+    pub fn register(&self, mut entry: RegistryEntry) {
+        entry.probe = self
+            .restart_policies
+            .lock()
+            .unwrap()
+            .probe_for(&entry, self.monitor_config);
         self.registry.register(entry);
     }
 
@@ -497,8 +672,11 @@ impl AdminServiceImpl {
                     by: systemd_agent_name,
                     vm: vm_name,
                 },
+                liveness: Liveness::default(),
+                probe: Probe::default(),
+                peer: None,
             };
-            self.registry.register(app_entry);
+            self.register(app_entry);
         }
         Ok(remote_name)
     }
@@ -533,11 +711,29 @@ impl pb::admin_service_server::AdminService for AdminService {
         &self,
         request: tonic::Request<RegistryRequest>,
     ) -> std::result::Result<tonic::Response<pb::RegistryResponse>, tonic::Status> {
+        let peer = request
+            .extensions()
+            .get::<SecurityInfo>()
+            .and_then(SecurityInfo::hostname)
+            .map(ToOwned::to_owned);
         let req = request.into_inner();
 
         info!("Registering service {:?}", req);
         let entry = RegistryEntry::try_from(req)
+            .map(|entry| RegistryEntry { peer, ..entry })
             .map_err(|e| Status::new(Code::InvalidArgument, format!("{e}")))?;
+        entry
+            .check_peer()
+            .map_err(|e| Status::new(Code::PermissionDenied, format!("{e}")))?;
+        if let Ok(known) = self.inner.registry.by_name(&entry.name)
+            && known.peer.is_some()
+            && known.peer != entry.peer
+        {
+            return Err(Status::new(
+                Code::PermissionDenied,
+                format!("{} is registered by other peer", entry.name),
+            ));
+        }
         let notify = matches!(
             entry.r#type,
             UnitType {
@@ -573,7 +769,7 @@ impl pb::admin_service_server::AdminService for AdminService {
                 if let Ok(conn) = endpoint.connect().await {
                     if let Some(policy_manager) = policy_manager {
                         debug!("policy-admin: sending policy updates to vm '{}'", vm_name);
-                        let _ = policy_manager.send_all_policies(&vm_name);
+                        policy_manager.resync_vm(&vm_name).await;
                     } else {
                         debug!("policy-admin: disabled");
                     }
@@ -981,12 +1177,40 @@ impl pb::admin_service_server::AdminService for AdminService {
                     let stream = ota.install_via_cachix(cachix_request).await?;
                     Ok(Box::pin(stream) as Self::SetGenerationStream)
                 }
-                _ => anyhow::bail!("unimplemented update method"),
+                Some(pb::set_generation_request::Update::Oci(oci_request)) => {
+                    let stream = ota.install_via_oci(oci_request).await?;
+                    Ok(Box::pin(stream) as Self::SetGenerationStream)
+                }
+                None => anyhow::bail!("unimplemented update method"),
             }
         })
         .await
     }
 
+    async fn rollback_generation(
+        &self,
+        request: tonic::Request<givc_common::pb::Empty>,
+    ) -> Result<tonic::Response<RollbackGenerationResponse>, tonic::Status> {
+        escalate(request, async |_| {
+            let endpoint = self.inner.host_endpoint()?;
+            let ota = super::OTA::OTA::new(endpoint);
+            ota.rollback().await
+        })
+        .await
+    }
+
+    async fn collect_garbage(
+        &self,
+        request: tonic::Request<CollectGarbageRequest>,
+    ) -> Result<tonic::Response<CollectGarbageResponse>, tonic::Status> {
+        escalate(request, async |req| {
+            let endpoint = self.inner.host_endpoint()?;
+            let ota = super::OTA::OTA::new(endpoint);
+            ota.collect_garbage(req.keep).await
+        })
+        .await
+    }
+
     async fn ctap(
         &self,
         request: tonic::Request<CtapRequest>,
@@ -1002,11 +1226,265 @@ impl pb::admin_service_server::AdminService for AdminService {
         })
         .await
     }
+
+    async fn policy_status(
+        &self,
+        request: tonic::Request<PolicyStatusRequest>,
+    ) -> Result<tonic::Response<PolicyStatusResponse>, tonic::Status> {
+        escalate(request, async |req| {
+            let policy_manager = self
+                .inner
+                .policy_manager
+                .as_ref()
+                .context("Policy admin is disabled")?;
+            let list = policy_manager
+                .status()
+                .list(req.vm_name.as_deref())
+                .into_iter()
+                .map(|(vm_name, policy, status)| PolicyDeliveryStatus {
+                    vm_name,
+                    policy,
+                    revision: status.revision,
+                    file: status.file,
+                    change: status.change.map(|change| change.to_string()),
+                    sha256: status.sha256,
+                    last_attempt: status.last_attempt,
+                    last_success: status.last_success,
+                    error: status.error,
+                })
+                .collect();
+            Ok(PolicyStatusResponse { list })
+        })
+        .await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::admin::fake_agent::{FakeAgent, unit_status};
+
+    const AGENT: &str = "givc-foo-vm.service";
+
+    fn admin_with(policies: &str) -> anyhow::Result<AdminServiceImpl> {
+        Ok(AdminServiceImpl::new(
+            Registry::new(),
+            None,
+            None,
+            policies.parse()?,
+            MonitorConfig {
+                interval: Duration::from_millis(50),
+                timeout: Duration::from_millis(200),
+            },
+        ))
+    }
+
+    /// Unit managed by `agent`, initially running
+    fn managed(name: &str, service: ServiceType, agent: &FakeAgent) -> RegistryEntry {
+        agent.set_status(name, "active", "running");
+        RegistryEntry {
+            name: name.to_owned(),
+            r#type: UnitType {
+                vm: VmType::AppVM,
+                service,
+            },
+            status: unit_status(name, "active", "running"),
+            placement: Placement::Managed {
+                by: AGENT.to_owned(),
+                vm: "microvm@foo-vm.service".to_owned(),
+            },
+            watch: true,
+            liveness: Liveness::default(),
+            probe: Probe::default(),
+            peer: None,
+        }
+    }
+
+    async fn monitor_ticks(
+        admin: &AdminServiceImpl,
+        name: &str,
+        ticks: usize,
+    ) -> anyhow::Result<()> {
+        for _ in 0..ticks {
+            let entry = admin.registry.by_name(name)?;
+            admin.monitor_routine(entry).await?;
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_restart_gives_up() -> anyhow::Result<()> {
+        let agent = FakeAgent::spawn()?;
+        let admin = admin_with(
+            r#"{"rules": [{"name": "foo.service", "policy": {"mode": "on-failure", "max_retries": 2, "backoff_secs": 0}}]}"#,
+        )?;
+        admin.register(agent.entry(AGENT, "foo-vm"));
+        admin.register(managed("foo.service", ServiceType::Svc, &agent));
+        agent.set_status("foo.service", "failed", "failed");
+        let (_, mut events) = admin.registry.subscribe();
+
+        monitor_ticks(&admin, "foo.service", 4).await?;
+        assert_eq!(agent.starts("foo.service"), 2);
+
+        let mut failed = 0;
+        while let Ok(event) = events.try_recv() {
+            if matches!(event, Event::UnitFailed(ref r) if r.name == "foo.service") {
+                failed += 1;
+            }
+        }
+        assert_eq!(failed, 1, "give up must be reported exactly once");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_restart_recovers() -> anyhow::Result<()> {
+        let agent = FakeAgent::spawn()?;
+        let admin = admin_with(
+            r#"{"rules": [{"vm": "AppVM", "service": "Svc", "policy": {"mode": "always", "max_retries": 1, "backoff_secs": 0}}]}"#,
+        )?;
+        admin.register(agent.entry(AGENT, "foo-vm"));
+        admin.register(managed("foo.service", ServiceType::Svc, &agent));
+        agent.set_heals(true);
+
+        // Clean exit is restarted too with `always`, and healthy unit resets attempts counter
+        agent.set_status("foo.service", "inactive", "dead");
+        monitor_ticks(&admin, "foo.service", 2).await?;
+        assert_eq!(agent.starts("foo.service"), 1);
+        assert_eq!(
+            admin
+                .restart_policies
+                .lock()
+                .unwrap()
+                .attempts("foo.service"),
+            0
+        );
+        assert!(admin.registry.by_name("foo.service")?.status.is_running());
+
+        agent.set_status("foo.service", "failed", "failed");
+        monitor_ticks(&admin, "foo.service", 1).await?;
+        assert_eq!(agent.starts("foo.service"), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_exited_app_deregistered() -> anyhow::Result<()> {
+        let agent = FakeAgent::spawn()?;
+        let admin = admin_with("{}")?;
+        admin.register(agent.entry(AGENT, "foo-vm"));
+        admin.register(managed("foot@0.service", ServiceType::App, &agent));
+
+        monitor_ticks(&admin, "foot@0.service", 1).await?;
+        assert!(admin.registry.contains("foot@0.service"));
+
+        agent.set_status("foot@0.service", "inactive", "dead");
+        monitor_ticks(&admin, "foot@0.service", 1).await?;
+        assert!(!admin.registry.contains("foot@0.service"));
+        assert_eq!(agent.starts("foot@0.service"), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_liveness() -> anyhow::Result<()> {
+        let agent = FakeAgent::spawn()?;
+        let admin = admin_with("{}")?;
+        admin.register(agent.entry(AGENT, "foo-vm"));
+        admin.register(managed("foo.service", ServiceType::Svc, &agent));
+
+        monitor_ticks(&admin, "foo.service", 1).await?;
+        let liveness = admin.registry.by_name("foo.service")?.liveness;
+        assert!(liveness.last_seen.is_some());
+        assert!(liveness.latency_ms.is_some());
+        assert_eq!(liveness.consecutive_failures, 0);
+
+        // Hung agent is accounted as failure after timeout
+        agent.set_hangs(true);
+        monitor_ticks(&admin, "foo.service", 2).await?;
+        let hung = admin.registry.by_name("foo.service")?.liveness;
+        assert_eq!(hung.consecutive_failures, 2);
+        assert_eq!(hung.last_seen, liveness.last_seen);
+        let listed = QueryResult::from(admin.registry.by_name("foo.service")?);
+        assert_eq!(listed.liveness, Some(hung));
+
+        agent.set_hangs(false);
+        monitor_ticks(&admin, "foo.service", 1).await?;
+        assert_eq!(
+            admin
+                .registry
+                .by_name("foo.service")?
+                .liveness
+                .consecutive_failures,
+            0
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_probe_schedule_per_entry() -> anyhow::Result<()> {
+        let agent = FakeAgent::spawn()?;
+        let admin = admin_with(
+            r#"{"rules": [{"name": "foo.service", "policy": {"mode": "never", "probe_interval_secs": 30}}]}"#,
+        )?;
+        admin.register(agent.entry(AGENT, "foo-vm"));
+        admin.register(managed("foo.service", ServiceType::Svc, &agent));
+
+        let probe = admin.registry.by_name("foo.service")?.probe;
+        assert_eq!(probe.interval, Duration::from_secs(30));
+        assert_eq!(probe.timeout, admin.monitor_config.timeout);
+        assert_eq!(admin.registry.by_name(AGENT)?.probe, admin.monitor_config);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hung_agent_does_not_block_others() -> anyhow::Result<()> {
+        let hung = FakeAgent::spawn()?;
+        let healthy = FakeAgent::spawn()?;
+        hung.set_hangs(true);
+        let admin = Arc::new(AdminServiceImpl {
+            monitor_config: MonitorConfig {
+                interval: Duration::from_millis(50),
+                timeout: Duration::from_secs(60),
+            },
+            ..admin_with("{}")?
+        });
+        admin.register(RegistryEntry {
+            watch: true,
+            ..hung.entry("givc-hung-vm.service", "hung-vm")
+        });
+        admin.register(RegistryEntry {
+            watch: true,
+            ..healthy.entry(AGENT, "foo-vm")
+        });
+        healthy.set_status(AGENT, "active", "running");
+
+        // Healthy agent keeps being probed, while probe of hung one is pending
+        let (_, mut events) = admin.registry.subscribe();
+        let monitor = tokio::spawn(admin.clone().monitor());
+        let probed = tokio::time::timeout(Duration::from_secs(30), async {
+            let mut seen = 0;
+            while seen < 3 {
+                if let Event::UnitStatusChanged(unit) = events.recv().await?
+                    && unit.name == AGENT
+                {
+                    seen += 1;
+                }
+            }
+            anyhow::Ok(())
+        })
+        .await;
+        monitor.abort();
+        probed.context("healthy agent not probed")??;
+
+        assert!(admin.registry.by_name(AGENT)?.liveness.last_seen.is_some());
+        assert!(
+            admin
+                .registry
+                .by_name("givc-hung-vm.service")?
+                .liveness
+                .last_seen
+                .is_none()
+        );
+        Ok(())
+    }
 
     #[test]
     fn test_locale_validator() -> anyhow::Result<()> {
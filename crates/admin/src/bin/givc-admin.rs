@@ -4,11 +4,17 @@
 use anyhow::Context;
 use clap::Parser;
 use givc::admin;
+use givc::admin::restart::RestartPolicies;
+use givc::admin::server::MonitorConfig;
 use givc::endpoint::TlsConfig;
 use givc::utils::access_control::Authorizer;
+use givc::utils::audit::AuditLog;
 use givc::utils::auth::Authenticator;
+use givc::utils::revocation::Revocation;
 use givc_common::pb::reflection::ADMIN_DESCRIPTOR;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use tonic::transport::Server;
 use tonic_middleware::RequestInterceptorLayer;
 use tracing::{debug, info};
@@ -35,9 +41,41 @@ struct Cli {
     #[arg(long, env = "HOST_KEY")]
     host_key: Option<PathBuf>,
 
+    #[arg(
+        long,
+        env = "CRL_FILE",
+        help = "Reject peer certificates revoked by this CRL (PEM or DER), issued by CA_CERT"
+    )]
+    crl_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        env = "CERT_DENYLIST",
+        help = "Reject peer certificates listed by serial or SHA-256 fingerprint in this file"
+    )]
+    cert_denylist: Option<PathBuf>,
+
     #[arg(long, env = "GIVC_MONITORING", default_value_t = true)]
     monitoring: bool,
 
+    #[arg(
+        long,
+        env = "GIVC_MONITOR_INTERVAL",
+        default_value_t = 5,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Seconds between health checks of each monitored unit, unless its restart policy sets `probe_interval_secs`"
+    )]
+    monitor_interval: u64,
+
+    #[arg(
+        long,
+        env = "GIVC_MONITOR_TIMEOUT",
+        default_value_t = 3,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Seconds to wait for health check answer, unless restart policy sets `probe_timeout_secs`"
+    )]
+    monitor_timeout: u64,
+
     #[arg(long, env = "POLICY_ADMIN", requires = "policy_config")]
     policy_admin: bool,
 
@@ -50,9 +88,46 @@ struct Cli {
     #[arg(long, env = "CEDAR_FILE")]
     cedar_file: Option<PathBuf>,
 
+    #[arg(
+        long,
+        env = "AUDIT_LOG",
+        help = "Append access control decisions to this JSON lines file"
+    )]
+    audit_log: Option<PathBuf>,
+
+    #[arg(
+        long,
+        env = "AUDIT_LOG_MAX_SIZE",
+        default_value_t = 10 << 20,
+        help = "Rotate audit log after this many bytes"
+    )]
+    audit_log_max_size: u64,
+
+    #[arg(
+        long,
+        env = "AUDIT_LOG_KEEP",
+        default_value_t = 5,
+        help = "Number of rotated audit logs to keep"
+    )]
+    audit_log_keep: usize,
+
     #[arg(long, env = "POLICY_STORE")]
     policy_store: Option<PathBuf>,
 
+    #[arg(
+        long,
+        env = "REGISTRY_JOURNAL",
+        help = "Persist registry to this file, and restore it on restart"
+    )]
+    registry_journal: Option<PathBuf>,
+
+    #[arg(
+        long,
+        env = "RESTART_POLICIES",
+        help = "JSON file with restart policies for monitored units"
+    )]
+    restart_policies: Option<PathBuf>,
+
     #[arg(
         long,
         env = "SERVICES",
@@ -73,7 +148,7 @@ async fn main() -> anyhow::Result<()> {
 
     let tls = if cli.use_tls {
         let tls_conf = TlsConfig {
-            ca_cert_file_path: cli.ca_cert.context("CA_CERT required")?,
+            ca_cert_file_path: cli.ca_cert.clone().context("CA_CERT required")?,
             cert_file_path: cli.host_cert.context("HOST_CERT required")?,
             key_file_path: cli.host_key.context("HOST_KEY required")?,
             tls_name: None,
@@ -88,18 +163,45 @@ async fn main() -> anyhow::Result<()> {
         .register_encoded_file_descriptor_set(ADMIN_DESCRIPTOR)
         .build_v1()?;
 
+    let restart_policies = cli
+        .restart_policies
+        .as_deref()
+        .map(RestartPolicies::from_file)
+        .transpose()?
+        .unwrap_or_default();
+
+    let monitoring = cli.monitoring.then_some(MonitorConfig {
+        interval: Duration::from_secs(cli.monitor_interval),
+        timeout: Duration::from_secs(cli.monitor_timeout),
+    });
+
     let admin_impl = admin::server::AdminService::new(
         tls,
-        cli.monitoring,
+        monitoring,
         cli.policy_admin,
         cli.policy_store,
         cli.policy_config,
+        cli.registry_journal,
+        restart_policies,
     )?;
 
+    let entity_store = admin_impl.entity_store();
     let admin_service_svc = admin::server::AdminServiceServer::new(admin_impl);
 
+    let revocation = if cli.crl_file.is_some() || cli.cert_denylist.is_some() {
+        let revocation = Arc::new(Revocation::load(
+            cli.crl_file,
+            cli.cert_denylist,
+            cli.ca_cert,
+        )?);
+        revocation.clone().watch()?;
+        Some(revocation)
+    } else {
+        None
+    };
     let authenticator = Authenticator {
         use_tls: cli.use_tls,
+        revocation,
     };
 
     let listener = tokio_listener::Listener::bind_multiple(
@@ -116,7 +218,12 @@ async fn main() -> anyhow::Result<()> {
             .cedar_file
             .as_deref()
             .context("Initialization failed: No Cedar policy file provided")?;
-        let authorizer = Authorizer::new(cedar_path)?;
+        let mut authorizer = Authorizer::new(cedar_path)?.with_entity_store(entity_store);
+        if let Some(path) = cli.audit_log {
+            let log = AuditLog::open(path, cli.audit_log_max_size, cli.audit_log_keep)?;
+            authorizer = authorizer.with_audit_log(Arc::new(log));
+        }
+        authorizer.watch(cedar_path.to_owned())?;
         builder
             .layer(RequestInterceptorLayer::new(authenticator))
             .layer(RequestInterceptorLayer::new(authorizer))
@@ -3,6 +3,8 @@
 
 use clap::Parser;
 use givc::endpoint::TlsConfig;
+use givc::systemd_api::applications::parse_applications;
+use givc::systemd_api::controller::SystemdController;
 use givc::systemd_api::server::SystemdService;
 use givc::types::{EndpointEntry, UnitStatus};
 use givc::utils::naming::VmName;
@@ -60,6 +62,13 @@ struct Cli {
         value_delimiter = ','
     )]
     services: Option<Vec<String>>,
+
+    #[arg(
+        long,
+        env = "APPLICATIONS",
+        help = "JSON list of application manifests ({name, command, args, directories})"
+    )]
+    applications: Option<String>,
 }
 
 #[tokio::main]
@@ -71,6 +80,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let addr = SocketAddr::new(cli.addr.parse()?, cli.port);
 
+    let applications = cli
+        .applications
+        .as_deref()
+        .map(parse_applications)
+        .transpose()?
+        .unwrap_or_default();
+    let controller =
+        SystemdController::connect(cli.services.unwrap_or_default(), applications).await?;
+
     // FIXME: Totally wrong,
     let agent_service_name = VmName::App(&cli.name).agent_service();
 
@@ -121,7 +139,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .unwrap();
 
     let agent_service_svc = pb::systemd::unit_control_service_server::UnitControlServiceServer::new(
-        SystemdService::new(),
+        SystemdService::new(controller),
     );
 
     builder
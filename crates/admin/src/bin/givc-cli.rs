@@ -1,17 +1,23 @@
 // SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::Context;
 use clap::{Parser, Subcommand};
 use givc::endpoint::TlsConfig;
 use givc::types::UnitType;
+use givc::utils::access_control::Authorizer;
+use givc::utils::acl_schema::{admin_schema, validate_policies};
+use givc::utils::audit::{AuditDecision, DEFAULT_AUDIT_LOG, read_audit_log};
 use givc::utils::vsock::parse_vsock_addr;
+use givc::utils::x509::SecurityInfo;
 use givc_client::client::AdminClient;
 use givc_common::address::EndpointAddress;
 use givc_common::pb;
 use lazy_regex::regex;
-use ota_update::cli::{CachixOptions, QueryUpdates, query_updates};
+use ota_update::cli::{CachixOptions, OciOptions, QueryUpdates, query_updates};
 use serde::ser::Serialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::time::interval;
@@ -101,11 +107,117 @@ impl StopSub {
     }
 }
 
+#[derive(Debug, Subcommand)]
+enum AclSub {
+    /// Parse policy and validate it against schema of admin API
+    Check { file: PathBuf },
+    /// Evaluate policy for single request offline. Registry entities are not
+    /// available, so only principal's own attributes can be matched.
+    Eval {
+        file: PathBuf,
+        #[arg(long)]
+        source: String,
+        /// Method name of `admin.AdminService`, or full path like `admin.AdminService/Poweroff`
+        #[arg(long)]
+        method: String,
+        /// Request message in protobuf JSON mapping
+        #[arg(long, default_value = "{}")]
+        json: String,
+        /// PEM certificate of source, to take principal attributes from
+        #[arg(long)]
+        peer_cert: Option<PathBuf>,
+    },
+}
+
+impl AclSub {
+    fn run(&self) -> anyhow::Result<()> {
+        match self {
+            AclSub::Check { file } => {
+                let text = std::fs::read_to_string(file)?;
+                let policies = cedar_policy::PolicySet::from_str(&text)
+                    .with_context(|| format!("parsing {}", file.display()))?;
+                let result = validate_policies(admin_schema()?, &policies)?;
+                for warning in result.validation_warnings() {
+                    println!("warning: {warning}");
+                }
+                for error in result.validation_errors() {
+                    println!("error: {error}");
+                }
+                anyhow::ensure!(
+                    result.validation_passed(),
+                    "{}: {} validation errors",
+                    file.display(),
+                    result.validation_errors().count()
+                );
+                println!(
+                    "{}: {} policies OK",
+                    file.display(),
+                    policies.policies().count()
+                );
+            }
+            AclSub::Eval {
+                file,
+                source,
+                method,
+                json,
+                peer_cert,
+            } => {
+                let security = match peer_cert {
+                    Some(path) => {
+                        let pem = std::fs::read(path)?;
+                        let (_, pem) = x509_parser::pem::parse_x509_pem(&pem)
+                            .map_err(|e| anyhow::anyhow!("reading PEM {}: {e}", path.display()))?;
+                        let security = SecurityInfo::try_from(pem.contents.as_slice())?;
+                        anyhow::ensure!(
+                            security.check_hostname(source),
+                            "certificate {} is not issued for {source}",
+                            path.display()
+                        );
+                        security
+                    }
+                    None => SecurityInfo::with_hostname(source),
+                };
+                let response = Authorizer::new(file)?.dry_run(&security, method, json)?;
+                println!("decision: {:?}", response.decision());
+                for policy in response.diagnostics().reason() {
+                    println!("policy: {policy}");
+                }
+                for error in response.diagnostics().errors() {
+                    println!("error: {error}");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Subcommand)]
 enum UpdateSub {
     Query(QueryUpdates),
     List,
     Cachix(CachixOptions),
+    /// Pull (and optionally install) update from OCI registry on host
+    Oci(OciOptions),
+    /// Roll back host to previous generation
+    Rollback,
+    /// Remove old generations on host, current and booted ones are always kept
+    Gc {
+        /// Number of newest generations to keep
+        #[arg(long, default_value_t = 3)]
+        keep: u32,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum PolicySub {
+    /// Show which policy revision each VM has applied
+    Status {
+        /// Show only policies delivered to this VM
+        #[arg(long)]
+        vm: Option<String>,
+        #[arg(long, default_value_t = false)]
+        as_json: bool,
+    },
 }
 
 #[derive(Debug, Parser)]
@@ -187,6 +299,10 @@ enum Commands {
         #[command(subcommand)]
         update: UpdateSub,
     },
+    Policy {
+        #[command(subcommand)]
+        policy: PolicySub,
+    },
     Test {
         #[command(subcommand)]
         test: Test,
@@ -194,6 +310,26 @@ enum Commands {
     Ctap {
         op: String,
     },
+    /// Show access control decisions, from local audit log of givc-admin
+    AuditLog {
+        #[arg(long, env = "GIVC_AUDIT_LOG", default_value = DEFAULT_AUDIT_LOG)]
+        file: PathBuf,
+        #[arg(long, default_value_t = false)]
+        denied: bool,
+        #[arg(long)]
+        principal: Option<String>,
+        #[arg(long)]
+        method: Option<String>,
+        #[arg(long)]
+        tail: Option<usize>,
+        #[arg(long, default_value_t = false)]
+        as_json: bool,
+    },
+    /// Check and dry-run access control policies
+    Acl {
+        #[command(subcommand)]
+        acl: AclSub,
+    },
 }
 
 fn unit_type_parse(s: &str) -> anyhow::Result<UnitType> {
@@ -306,6 +442,37 @@ impl Test {
     }
 }
 
+impl PolicySub {
+    async fn handle(self, admin: AdminClient) -> anyhow::Result<()> {
+        match self {
+            PolicySub::Status { vm, as_json } => {
+                let list = admin.policy_status(vm).await?;
+                if as_json {
+                    return dump(list, true);
+                }
+                for status in list {
+                    let outcome = match (&status.error, status.last_success) {
+                        (Some(error), _) => format!("failed: {error}"),
+                        (None, Some(_)) => "ok".to_string(),
+                        (None, None) => "pending".to_string(),
+                    };
+                    println!(
+                        "{} {} revision={} file={} sha256={} last-attempt={} last-success={} {outcome}",
+                        status.vm_name,
+                        status.policy,
+                        status.revision.as_deref().unwrap_or("-"),
+                        status.file.as_deref().unwrap_or("-"),
+                        status.sha256.as_deref().unwrap_or("-"),
+                        status.last_attempt.map_or("-".into(), |t| t.to_string()),
+                        status.last_success.map_or("-".into(), |t| t.to_string()),
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 impl UpdateSub {
     async fn handle(self, admin: AdminClient) -> anyhow::Result<()> {
         match self {
@@ -314,6 +481,23 @@ impl UpdateSub {
                 let response = admin.list_generations().await?;
                 println!("{response:?}");
             }
+            UpdateSub::Rollback => {
+                let response = admin.rollback_generation().await?;
+                match response.generation {
+                    Some(generation) => {
+                        println!(
+                            "Rolled back to generation {generation} ({})",
+                            response.target
+                        );
+                    }
+                    None => println!("Rolled back to {}", response.target),
+                }
+            }
+            UpdateSub::Gc { keep } => {
+                let response = admin.collect_garbage(keep).await?;
+                println!("Removed generations: {:?}", response.removed);
+                println!("Kept generations: {:?}", response.kept);
+            }
             UpdateSub::Cachix(CachixOptions {
                 pin_name,
                 cachix_host,
@@ -321,7 +505,44 @@ impl UpdateSub {
                 token,
             }) => {
                 admin
-                    .set_generation_cachix(pin_name, cachix_host, cache, token)
+                    .set_generation_cachix(pin_name, cachix_host, cache, token, print_progress)
+                    .await?;
+            }
+            UpdateSub::Oci(OciOptions {
+                reference,
+                username,
+                password,
+                token,
+                insecure,
+                validate,
+                install,
+                destination,
+            }) => {
+                let oci = pb::admin::Oci {
+                    reference: reference.to_string(),
+                    username,
+                    password,
+                    token,
+                    insecure,
+                    validate,
+                    install,
+                    destination,
+                };
+                admin
+                    .set_generation_oci(oci, |progress| {
+                        let mut line = progress.event;
+                        for value in [progress.reference, progress.digest, progress.path]
+                            .into_iter()
+                            .flatten()
+                        {
+                            line.push(' ');
+                            line.push_str(&value);
+                        }
+                        if let Some(current) = progress.current {
+                            line.push_str(&format!(" {current}/{}", progress.total.unwrap_or(0)));
+                        }
+                        println!("{line}");
+                    })
                     .await?;
             }
         }
@@ -329,6 +550,43 @@ impl UpdateSub {
     }
 }
 
+fn progress_bar(done: u64, total: u64) -> String {
+    const WIDTH: u64 = 30;
+    let filled = (done.saturating_mul(WIDTH))
+        .checked_div(total)
+        .unwrap_or(0)
+        .min(WIDTH);
+    format!(
+        "[{}{}]",
+        "#".repeat(usize::try_from(filled).unwrap_or_default()),
+        " ".repeat(usize::try_from(WIDTH - filled).unwrap_or_default())
+    )
+}
+
+fn print_progress(progress: pb::admin::UpdateProgress) {
+    const MIB: f64 = 1024.0 * 1024.0;
+    match progress.phase.as_str() {
+        "fetching" => {
+            #[allow(clippy::cast_precision_loss)]
+            let (done, total) = (
+                progress.bytes_done as f64 / MIB,
+                progress.bytes_total as f64 / MIB,
+            );
+            print!(
+                "\r{} {}/{} paths, {done:.1}/{total:.1} MiB {}\x1b[K",
+                progress_bar(progress.bytes_done, progress.bytes_total),
+                progress.paths_done,
+                progress.paths_total,
+                progress.store_path.unwrap_or_default()
+            );
+            let _ = std::io::Write::flush(&mut std::io::stdout());
+        }
+        "activating" => println!("\nactivating: {}", progress.step.unwrap_or_default()),
+        "resolved" => println!("update: {}", progress.store_path.unwrap_or_default()),
+        phase => println!("{phase}"),
+    }
+}
+
 async fn ctap(admin: AdminClient, operation: String) -> anyhow::Result<()> {
     let mut payload = vec![];
     tokio::io::stdin().read_to_end(&mut payload).await?;
@@ -358,6 +616,44 @@ async fn notify_user(admin: AdminClient, notification: Notification) -> anyhow::
     Ok(())
 }
 
+fn audit_log(
+    file: &Path,
+    denied: bool,
+    principal: Option<&str>,
+    method: Option<&str>,
+    tail: Option<usize>,
+    as_json: bool,
+) -> anyhow::Result<()> {
+    let records: Vec<_> = read_audit_log(file)?
+        .into_iter()
+        .filter(|r| !denied || r.decision == AuditDecision::Deny)
+        .filter(|r| principal.is_none_or(|p| r.principal == p))
+        .filter(|r| method.is_none_or(|m| r.method.ends_with(m)))
+        .collect();
+    let skip = tail.map_or(0, |n| records.len().saturating_sub(n));
+    for record in &records[skip..] {
+        if as_json {
+            println!("{}", serde_json::to_string(record)?);
+        } else {
+            let rejected = record
+                .rejected
+                .as_ref()
+                .map(|reason| format!(" rejected={reason:?}"))
+                .unwrap_or_default();
+            println!(
+                "{} {:?} {} {} policies={:?} context={}{rejected}",
+                record.timestamp_ms,
+                record.decision,
+                record.principal,
+                record.method,
+                record.policies,
+                record.context
+            );
+        }
+    }
+    Ok(())
+}
+
 async fn sysinfo(admin: AdminClient) -> anyhow::Result<()> {
     let status = admin.sysinfo().await?;
     println!("Ghaf Version: {}", status.ghaf_version);
@@ -379,6 +675,31 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
     info!("CLI is {:#?}", cli);
 
+    // Local command, doesn't need connection to admin
+    if let Commands::AuditLog {
+        file,
+        denied,
+        principal,
+        method,
+        tail,
+        as_json,
+    } = &cli.command
+    {
+        audit_log(
+            file,
+            *denied,
+            principal.as_deref(),
+            method.as_deref(),
+            *tail,
+            *as_json,
+        )?;
+        return Ok(());
+    }
+    if let Commands::Acl { acl } = &cli.command {
+        acl.run()?;
+        return Ok(());
+    }
+
     let tls = if cli.notls {
         None
     } else {
@@ -473,6 +794,10 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
         }
 
         Commands::Update { update } => update.handle(admin).await?,
+        Commands::Policy { policy } => policy.handle(admin).await?,
+        Commands::AuditLog { .. } | Commands::Acl { .. } => {
+            unreachable!("handled before connecting")
+        }
     }
 
     Ok(())
@@ -1,26 +1,124 @@
 // SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use clap::Parser;
-use givc::utils::x509::SecurityInfo;
+use clap::{Parser, Subcommand};
+use givc::utils::ca::{CertificateAuthority, HOST_CERT, HostIdentity, inspect, renew};
+use std::net::IpAddr;
 use std::path::PathBuf;
-use x509_parser::pem::parse_x509_pem;
+use std::time::Duration;
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
 
 #[derive(Debug, Parser)]
-#[command(name = "givc-x509")]
+#[command(name = "givc-x509", about = "Inspect and issue givc TLS certificates")]
 struct Cli {
-    cert: PathBuf,
+    #[command(subcommand)]
+    command: Commands,
 }
 
-fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
-    println!("CLI is {cli:#?}");
-
-    let cert_file = std::fs::read(cli.cert)?;
+#[derive(Debug, Subcommand)]
+enum Commands {
+    /// Show identity and validity of certificate
+    Inspect { cert: PathBuf },
+    /// Create new CA in `dir`, as `ca-cert.pem` and `ca-key.pem`
+    InitCa {
+        dir: PathBuf,
+        #[arg(long, default_value = "GIVC CA")]
+        name: String,
+        #[arg(long, default_value_t = 3650)]
+        days: u32,
+    },
+    /// Issue certificate for VM into `<out>/<name>/{ca-cert,cert,key}.pem`
+    Issue {
+        name: String,
+        #[arg(long, env = "GIVC_CA_DIR")]
+        ca_dir: PathBuf,
+        #[arg(long)]
+        out: PathBuf,
+        /// Additional DNS names, hostname itself is always first one
+        #[arg(long)]
+        dns: Vec<String>,
+        /// IP addresses, TCP peers are verified against them
+        #[arg(long)]
+        ip: Vec<IpAddr>,
+        #[arg(long, default_value_t = 3650)]
+        days: u32,
+    },
+    /// Re-issue certificates in `<out>/<name>`, which expire soon, keeping their names
+    Renew {
+        /// VMs to renew, all subdirectories of `out` if none given
+        names: Vec<String>,
+        #[arg(long, env = "GIVC_CA_DIR")]
+        ca_dir: PathBuf,
+        #[arg(long)]
+        out: PathBuf,
+        /// Renew certificates expiring within this many days
+        #[arg(long, default_value_t = 30)]
+        before: u32,
+        #[arg(long, default_value_t = 3650)]
+        days: u32,
+        #[arg(long, default_value_t = false)]
+        force: bool,
+    },
+}
 
-    let (_rem, pem) = parse_x509_pem(&cert_file)?;
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
 
-    let x509 = SecurityInfo::try_from(pem.contents.as_slice())?;
-    println!("SI is {x509:?}");
+    match cli.command {
+        Commands::Inspect { cert } => {
+            let (security, remaining) = inspect(&std::fs::read(cert)?)?;
+            println!("SI is {security:?}");
+            println!("Expires in {} days", remaining.as_secs() / DAY.as_secs());
+        }
+        Commands::InitCa { dir, name, days } => {
+            CertificateAuthority::create(&name, days)?.save(&dir)?;
+            println!("Created CA {name} in {}", dir.display());
+        }
+        Commands::Issue {
+            name,
+            ca_dir,
+            out,
+            dns,
+            ip,
+            days,
+        } => {
+            let ca = CertificateAuthority::load(&ca_dir)?;
+            let host = HostIdentity {
+                name,
+                dns_names: dns,
+                ip_addrs: ip,
+            };
+            let dir = out.join(&host.name);
+            ca.install(&ca.issue(&host, days)?, &dir)?;
+            println!("Issued certificate for {} in {}", host.name, dir.display());
+        }
+        Commands::Renew {
+            names,
+            ca_dir,
+            out,
+            before,
+            days,
+            force,
+        } => {
+            let ca = CertificateAuthority::load(&ca_dir)?;
+            let mut dirs: Vec<_> = names.iter().map(|name| out.join(name)).collect();
+            if dirs.is_empty() {
+                for entry in std::fs::read_dir(&out)? {
+                    let path = entry?.path();
+                    if path.join(HOST_CERT).exists() {
+                        dirs.push(path);
+                    }
+                }
+            }
+            for dir in dirs {
+                if renew(&ca, &dir, DAY * before, days, force)? {
+                    println!("Renewed {}", dir.display());
+                } else {
+                    println!("Still valid {}", dir.display());
+                }
+            }
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,198 @@
+// SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Port of modules/pkgs/applications from "go" agent
+use std::path::{Component, Path};
+
+use anyhow::{Context, bail, ensure};
+use serde::Deserialize;
+
+use crate::utils::naming::parse_application_name;
+
+const APP_ARG_FLAG: &str = "flag";
+const APP_ARG_URL: &str = "url";
+const APP_ARG_FILE: &str = "file";
+
+// Non-http schemes, which applications are allowed to receive
+const URL_EXTRA_SCHEMES: &[&str] = &["element", "io.element.desktop"];
+
+/// Application description, same JSON layout as `ApplicationManifest` in "go" agent
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ApplicationManifest {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub directories: Vec<String>,
+}
+
+impl ApplicationManifest {
+    /// Check manifest consistency
+    /// # Errors
+    /// Return `Err()` if manifest is malformed
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(!self.name.is_empty(), "application name is empty");
+        ensure!(!self.command.is_empty(), "application command is empty");
+        for arg in &self.args {
+            match arg.as_str() {
+                APP_ARG_FLAG | APP_ARG_URL => {}
+                APP_ARG_FILE => ensure!(
+                    !self.directories.is_empty(),
+                    "file argument given but no directories specified"
+                ),
+                other => bail!("application argument type {other} not supported"),
+            }
+        }
+        ensure!(
+            self.directories.is_empty() || self.allows(APP_ARG_FILE),
+            "directories specified but file argument type missing"
+        );
+        Ok(())
+    }
+
+    fn allows(&self, kind: &str) -> bool {
+        self.args.iter().any(|a| a == kind)
+    }
+
+    /// Full command line: manifest command followed by validated runtime `args`
+    /// # Errors
+    /// Return `Err()` if any of `args` isn't permitted by manifest
+    pub fn command_line(&self, args: &[String]) -> anyhow::Result<Vec<String>> {
+        for arg in args {
+            let valid = (self.allows(APP_ARG_FLAG) && is_flag(arg))
+                || (self.allows(APP_ARG_URL) && is_url(arg))
+                || (self.allows(APP_ARG_FILE) && is_file_in(arg, &self.directories));
+            ensure!(valid, "invalid application argument: {arg}");
+        }
+        let mut cmd: Vec<String> = self.command.split_whitespace().map(String::from).collect();
+        ensure!(!cmd.is_empty(), "incorrect application string format");
+        cmd.extend(args.iter().cloned());
+        Ok(cmd)
+    }
+}
+
+/// Parse JSON list of application manifests
+/// # Errors
+/// Return `Err()` if JSON malformed, or manifests are invalid
+pub fn parse_applications(json: &str) -> anyhow::Result<Vec<ApplicationManifest>> {
+    let apps: Vec<ApplicationManifest> =
+        serde_json::from_str(json).context("parsing applications JSON")?;
+    for (n, app) in apps.iter().enumerate() {
+        app.validate()
+            .with_context(|| format!("application {}", app.name))?;
+        ensure!(
+            !apps[..n].iter().any(|a| a.name == app.name),
+            "duplicate application name {}",
+            app.name
+        );
+    }
+    Ok(apps)
+}
+
+/// Find manifest for `unit_name` in `app@<number>.service` form
+/// # Errors
+/// Return `Err()` if name malformed or application is unknown
+pub fn find_application<'a>(
+    apps: &'a [ApplicationManifest],
+    unit_name: &str,
+) -> anyhow::Result<&'a ApplicationManifest> {
+    let (name, _) = parse_application_name(unit_name)?;
+    ensure!(
+        name.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+        "invalid application name {name}"
+    );
+    apps.iter()
+        .find(|app| app.name == name)
+        .with_context(|| format!("application {name} unknown"))
+}
+
+fn is_flag(arg: &str) -> bool {
+    let body = arg
+        .strip_prefix("--")
+        .or_else(|| arg.strip_prefix('-'))
+        .unwrap_or_default();
+    !body.is_empty()
+        && body
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn is_url(arg: &str) -> bool {
+    let Some((scheme, rest)) = arg.split_once(':') else {
+        return false;
+    };
+    let rest = rest.trim_start_matches('/');
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    (matches!(scheme, "http" | "https") || URL_EXTRA_SCHEMES.contains(&scheme))
+        && !authority.is_empty()
+        && !authority.contains('@')
+        && arg.chars().all(|c| c.is_ascii_graphic())
+}
+
+fn is_file_in(arg: &str, directories: &[String]) -> bool {
+    let path = Path::new(arg);
+    path.is_absolute()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::RootDir | Component::Normal(_)))
+        && directories.iter().any(|dir| path.starts_with(dir))
+        && path.exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn browser() -> ApplicationManifest {
+        ApplicationManifest {
+            name: "chrome".into(),
+            command: "run-waypipe chromium --enable-features=UseOzonePlatform".into(),
+            args: vec!["flag".into(), "url".into()],
+            directories: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_command_line() -> anyhow::Result<()> {
+        let app = browser();
+        let cmd = app.command_line(&["--incognito".into(), "https://ghaf.tii.ae".into()])?;
+        assert_eq!(
+            cmd,
+            [
+                "run-waypipe",
+                "chromium",
+                "--enable-features=UseOzonePlatform",
+                "--incognito",
+                "https://ghaf.tii.ae"
+            ]
+        );
+        assert!(app.command_line(&["; rm -rf /".into()]).is_err());
+        assert!(app.command_line(&["https://user@evil.com".into()]).is_err());
+        assert!(app.command_line(&["/etc/passwd".into()]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_applications() -> anyhow::Result<()> {
+        let apps = parse_applications(
+            r#"[{"name":"chrome","command":"chromium","args":["url"]},{"name":"foot","command":"foot"}]"#,
+        )?;
+        assert_eq!(apps.len(), 2);
+        assert_eq!(
+            find_application(&apps, "chrome@3.service")?.command,
+            "chromium"
+        );
+        assert!(find_application(&apps, "firefox@1.service").is_err());
+
+        assert!(
+            parse_applications(r#"[{"name":"a","command":"x"},{"name":"a","command":"y"}]"#)
+                .is_err()
+        );
+        assert!(
+            parse_applications(r#"[{"name":"a","command":"x","directories":["/tmp"]}]"#).is_err()
+        );
+        Ok(())
+    }
+}
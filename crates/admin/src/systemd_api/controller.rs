@@ -0,0 +1,597 @@
+// SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Systemd control via D-Bus, port of modules/pkgs/servicemanager from "go" agent
+use std::future::Future;
+use std::os::unix::fs::MetadataExt;
+use std::time::Duration;
+
+use anyhow::{Context, anyhow, bail, ensure};
+use tokio_stream::StreamExt;
+use tracing::{debug, info, warn};
+use zbus::Connection;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::{OwnedObjectPath, Value};
+
+use super::applications::{ApplicationManifest, find_application};
+use super::dbus::{ManagerProxy, ServiceProxy, UnitProxy};
+use crate::types::UnitStatus;
+
+const JOB_MODE: &str = "replace";
+const JOB_TIMEOUT: Duration = Duration::from_secs(90);
+const SIGKILL: i32 = 9;
+
+#[derive(Debug)]
+pub struct SystemdController {
+    conn: Connection,
+    manager: ManagerProxy<'static>,
+    whitelist: Vec<String>,
+    applications: Vec<ApplicationManifest>,
+}
+
+/// Resource usage of unit, as reported by `MonitorUnit`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitUsage {
+    pub cpu_nsec: u64,
+    pub memory_bytes: u64,
+}
+
+impl SystemdController {
+    /// Connect to system manager when running as root, or to user manager otherwise
+    /// # Errors
+    /// Return `Err()` if D-Bus connection fails
+    pub async fn connect(
+        whitelist: Vec<String>,
+        applications: Vec<ApplicationManifest>,
+    ) -> anyhow::Result<Self> {
+        // Effective UID of process is owner of /proc/self
+        let is_root = std::fs::metadata("/proc/self").is_ok_and(|m| m.uid() == 0);
+        let conn = if is_root {
+            Connection::system().await
+        } else {
+            Connection::session().await
+        }
+        .context("connecting to D-Bus")?;
+        Self::with_connection(conn, whitelist, applications).await
+    }
+
+    /// Use already established D-Bus connection
+    /// # Errors
+    /// Return `Err()` if manager proxy can't be created
+    pub async fn with_connection(
+        conn: Connection,
+        mut whitelist: Vec<String>,
+        applications: Vec<ApplicationManifest>,
+    ) -> anyhow::Result<Self> {
+        let manager = ManagerProxy::new(&conn).await?;
+        // Without subscription systemd don't send JobRemoved to us
+        if let Err(e) = manager.subscribe().await {
+            warn!("Failed to subscribe to systemd signals: {e}");
+        }
+        whitelist.extend(applications.iter().map(|app| app.name.clone()));
+        Ok(Self {
+            conn,
+            manager,
+            whitelist,
+            applications,
+        })
+    }
+
+    /// General units are whitelisted by their full name, applications instances
+    /// by their base name in `app@<number>.service` form
+    #[must_use]
+    pub fn is_whitelisted(&self, name: &str) -> bool {
+        self.whitelist.iter().any(|allowed| {
+            allowed == name
+                || name
+                    .strip_prefix(allowed.as_str())
+                    .and_then(|rest| rest.strip_prefix('@'))
+                    .and_then(|rest| rest.strip_suffix(".service"))
+                    .is_some_and(|num| !num.is_empty() && num.bytes().all(|b| b.is_ascii_digit()))
+        })
+    }
+
+    fn ensure_whitelisted(&self, name: &str) -> anyhow::Result<()> {
+        ensure!(!name.is_empty(), "incorrect input, must be unit name");
+        ensure!(self.is_whitelisted(name), "unit {name} is not whitelisted");
+        Ok(())
+    }
+
+    async fn unit_path(&self, name: &str) -> anyhow::Result<OwnedObjectPath> {
+        self.manager
+            .load_unit(name)
+            .await
+            .with_context(|| format!("cannot find unit with name {name}"))
+    }
+
+    async fn unit_proxy(&self, name: &str) -> anyhow::Result<UnitProxy<'static>> {
+        let path = self.unit_path(name).await?;
+        Ok(UnitProxy::builder(&self.conn)
+            .path(path)?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?)
+    }
+
+    /// Fetch status of whitelisted unit
+    /// # Errors
+    /// Return `Err()` if unit not whitelisted, or D-Bus call fails
+    pub async fn get_unit_status(&self, name: &str) -> anyhow::Result<UnitStatus> {
+        self.ensure_whitelisted(name)?;
+        let unit = self.unit_proxy(name).await?;
+        let status = UnitStatus {
+            name: unit.id().await?,
+            description: unit.description().await?,
+            load_state: unit.load_state().await?,
+            active_state: unit.active_state().await?,
+            sub_state: unit.sub_state().await?,
+            path: unit.inner().path().to_string(),
+            freezer_state: unit.freezer_state().await.unwrap_or_else(|e| {
+                debug!("Error fetching freezer state of {name}: {e}");
+                String::from("error")
+            }),
+        };
+        debug!("Status of {name} is {status:?}");
+        Ok(status)
+    }
+
+    /// Run job created by `job`, and wait for its completion
+    async fn run_job<F, Fut>(&self, name: &str, job: F) -> anyhow::Result<()>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = zbus::Result<OwnedObjectPath>>,
+    {
+        // Subscribe before job creation, otherwise we could miss fast jobs
+        let mut removed = self.manager.receive_job_removed().await?;
+        let job = job()
+            .await
+            .with_context(|| format!("queueing job for {name}"))?;
+        let wait = async {
+            while let Some(signal) = removed.next().await {
+                let args = signal.args()?;
+                if args.job() == &job {
+                    return anyhow::Ok(args.result().clone());
+                }
+            }
+            Err(anyhow!(
+                "D-Bus connection closed while waiting for job of {name}"
+            ))
+        };
+        let result = tokio::time::timeout(JOB_TIMEOUT, wait)
+            .await
+            .with_context(|| format!("timeout while waiting for job of {name}"))??;
+        ensure!(
+            result == "done",
+            "job for unit {name} finished with {result}"
+        );
+        Ok(())
+    }
+
+    /// (Re)start whitelisted unit
+    /// # Errors
+    /// Return `Err()` if unit not whitelisted, or start fails
+    pub async fn start_unit(&self, name: &str) -> anyhow::Result<()> {
+        self.ensure_whitelisted(name)?;
+        self.run_job(name, || self.manager.restart_unit(name, JOB_MODE))
+            .await?;
+        info!("unit {name} (re)start cmd successful");
+        Ok(())
+    }
+
+    /// Stop whitelisted unit
+    /// # Errors
+    /// Return `Err()` if unit not whitelisted, or stop fails
+    pub async fn stop_unit(&self, name: &str) -> anyhow::Result<()> {
+        self.ensure_whitelisted(name)?;
+        self.run_job(name, || self.manager.stop_unit(name, JOB_MODE))
+            .await?;
+        info!("unit {name} stop command successful");
+        Ok(())
+    }
+
+    /// Forcefully terminate all processes of whitelisted unit
+    /// # Errors
+    /// Return `Err()` if unit not whitelisted, or D-Bus call fails
+    pub async fn kill_unit(&self, name: &str) -> anyhow::Result<()> {
+        self.ensure_whitelisted(name)?;
+        self.manager.kill_unit(name, "all", SIGKILL).await?;
+        Ok(())
+    }
+
+    /// Freeze whitelisted unit
+    /// # Errors
+    /// Return `Err()` if unit not whitelisted, or D-Bus call fails
+    pub async fn freeze_unit(&self, name: &str) -> anyhow::Result<()> {
+        self.ensure_whitelisted(name)?;
+        self.manager.freeze_unit(name).await?;
+        Ok(())
+    }
+
+    /// Unfreeze (thaw) whitelisted unit
+    /// # Errors
+    /// Return `Err()` if unit not whitelisted, or D-Bus call fails
+    pub async fn unfreeze_unit(&self, name: &str) -> anyhow::Result<()> {
+        self.ensure_whitelisted(name)?;
+        self.manager.thaw_unit(name).await?;
+        Ok(())
+    }
+
+    /// Snapshot of resource usage of whitelisted service unit
+    /// # Errors
+    /// Return `Err()` if unit not whitelisted, or D-Bus call fails
+    pub async fn unit_usage(&self, name: &str) -> anyhow::Result<UnitUsage> {
+        self.ensure_whitelisted(name)?;
+        let path = self.unit_path(name).await?;
+        let service = ServiceProxy::builder(&self.conn)
+            .path(path)?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?;
+        Ok(UnitUsage {
+            cpu_nsec: service.cpu_usage_nsec().await?,
+            memory_bytes: service.memory_current().await?,
+        })
+    }
+
+    /// Start application `name` (in `app@<number>.service` form) as transient unit.
+    /// Dynamic arguments are validated against the application manifest.
+    /// # Errors
+    /// Return `Err()` if application is unknown, arguments invalid, or start fails
+    pub async fn start_application(
+        &self,
+        name: &str,
+        args: &[String],
+    ) -> anyhow::Result<UnitStatus> {
+        self.ensure_whitelisted(name)?;
+        let app = find_application(&self.applications, name)?;
+        let cmd = app.command_line(args)?;
+        let environment: Vec<String> = std::env::vars().map(|(k, v)| format!("{k}={v}")).collect();
+        let properties = [
+            (
+                "Description",
+                Value::from(format!("Application service for {}", app.name)),
+            ),
+            ("ExecStart", Value::from(vec![(cmd[0].clone(), cmd, false)])),
+            ("Type", Value::from("exec")),
+            ("Environment", Value::from(environment)),
+        ];
+        self.run_job(name, || {
+            self.manager
+                .start_transient_unit(name, JOB_MODE, &properties, &[])
+        })
+        .await
+        .with_context(|| format!("starting application {}", app.name))?;
+        info!("application {name} start cmd successful");
+
+        // Transient units are garbage collected as soon as they exit,
+        // so report short-living application as exited instead of failing
+        match self.get_unit_status(name).await {
+            Ok(status) if status.active_state == "failed" => {
+                bail!("application started but failed: {name}")
+            }
+            Ok(status) if status.load_state != "not-found" => Ok(status),
+            Ok(_) | Err(_) => Ok(exited_unit(name)),
+        }
+    }
+}
+
+/// Status reported for units, which gone after stop/kill (like transient units)
+#[must_use]
+pub fn exited_unit(name: &str) -> UnitStatus {
+    UnitStatus {
+        name: name.to_owned(),
+        description: format!("Exited application: {name}"),
+        load_state: String::from("not-found"),
+        active_state: String::from("inactive"),
+        sub_state: String::from("dead"),
+        path: String::new(),
+        freezer_state: String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use zbus::object_server::{ObjectServer, SignalEmitter};
+    use zbus::zvariant::OwnedValue;
+    use zbus::{fdo, interface};
+
+    #[derive(Debug, Clone)]
+    struct FakeUnitState {
+        path: OwnedObjectPath,
+        active: &'static str,
+        sub: &'static str,
+        freezer: &'static str,
+    }
+
+    type Units = Arc<Mutex<HashMap<String, FakeUnitState>>>;
+
+    /// Local stand-in for systemd manager, keeping unit states in memory
+    struct FakeManager {
+        units: Units,
+        jobs: u32,
+    }
+
+    impl FakeManager {
+        fn set(&self, name: &str, active: &'static str, sub: &'static str) -> fdo::Result<()> {
+            let mut units = self.units.lock().unwrap();
+            let unit = units
+                .get_mut(name)
+                .ok_or_else(|| fdo::Error::Failed(format!("unit {name} not loaded")))?;
+            unit.active = active;
+            unit.sub = sub;
+            Ok(())
+        }
+
+        async fn job_done(
+            &mut self,
+            emitter: &SignalEmitter<'_>,
+            unit: &str,
+        ) -> fdo::Result<OwnedObjectPath> {
+            self.jobs += 1;
+            let job =
+                OwnedObjectPath::try_from(format!("/org/freedesktop/systemd1/job/{}", self.jobs))
+                    .map_err(|e| fdo::Error::Failed(e.to_string()))?;
+            Self::job_removed(emitter, self.jobs, &job, unit, "done").await?;
+            Ok(job)
+        }
+    }
+
+    #[interface(name = "org.freedesktop.systemd1.Manager")]
+    impl FakeManager {
+        fn subscribe(&self) {}
+
+        fn load_unit(&self, name: &str) -> fdo::Result<OwnedObjectPath> {
+            self.units
+                .lock()
+                .unwrap()
+                .get(name)
+                .map(|u| u.path.clone())
+                .ok_or_else(|| fdo::Error::Failed(format!("unit {name} not loaded")))
+        }
+
+        async fn restart_unit(
+            &mut self,
+            #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+            name: &str,
+            _mode: &str,
+        ) -> fdo::Result<OwnedObjectPath> {
+            self.set(name, "active", "running")?;
+            self.job_done(&emitter, name).await
+        }
+
+        async fn stop_unit(
+            &mut self,
+            #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+            name: &str,
+            _mode: &str,
+        ) -> fdo::Result<OwnedObjectPath> {
+            self.set(name, "inactive", "dead")?;
+            self.job_done(&emitter, name).await
+        }
+
+        fn kill_unit(&self, name: &str, _whom: &str, _signal: i32) -> fdo::Result<()> {
+            self.set(name, "failed", "failed")
+        }
+
+        fn freeze_unit(&self, name: &str) -> fdo::Result<()> {
+            let mut units = self.units.lock().unwrap();
+            units.get_mut(name).map_or_else(
+                || Err(fdo::Error::Failed(format!("unit {name} not loaded"))),
+                |u| {
+                    u.freezer = "frozen";
+                    Ok(())
+                },
+            )
+        }
+
+        fn thaw_unit(&self, name: &str) -> fdo::Result<()> {
+            let mut units = self.units.lock().unwrap();
+            units.get_mut(name).map_or_else(
+                || Err(fdo::Error::Failed(format!("unit {name} not loaded"))),
+                |u| {
+                    u.freezer = "running";
+                    Ok(())
+                },
+            )
+        }
+
+        async fn start_transient_unit(
+            &mut self,
+            #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+            #[zbus(object_server)] server: &ObjectServer,
+            name: String,
+            _mode: String,
+            properties: Vec<(String, OwnedValue)>,
+            _aux: Vec<(String, Vec<(String, OwnedValue)>)>,
+        ) -> fdo::Result<OwnedObjectPath> {
+            if !properties.iter().any(|(key, _)| key == "ExecStart") {
+                return Err(fdo::Error::InvalidArgs("ExecStart missing".into()));
+            }
+            let path = add_unit(&self.units, &name, "active", "running");
+            server
+                .at(path.clone(), FakeUnit::new(&self.units, &name))
+                .await?;
+            self.job_done(&emitter, &name).await
+        }
+
+        #[zbus(signal)]
+        async fn job_removed(
+            emitter: &SignalEmitter<'_>,
+            id: u32,
+            job: &OwnedObjectPath,
+            unit: &str,
+            result: &str,
+        ) -> zbus::Result<()>;
+    }
+
+    struct FakeUnit {
+        name: String,
+        units: Units,
+    }
+
+    impl FakeUnit {
+        fn new(units: &Units, name: &str) -> Self {
+            Self {
+                name: name.to_owned(),
+                units: units.clone(),
+            }
+        }
+
+        fn state(&self) -> FakeUnitState {
+            self.units.lock().unwrap()[&self.name].clone()
+        }
+    }
+
+    #[interface(name = "org.freedesktop.systemd1.Unit")]
+    impl FakeUnit {
+        #[zbus(property)]
+        fn id(&self) -> String {
+            self.name.clone()
+        }
+
+        #[zbus(property)]
+        fn description(&self) -> String {
+            format!("Fake {}", self.name)
+        }
+
+        #[zbus(property)]
+        fn load_state(&self) -> String {
+            String::from("loaded")
+        }
+
+        #[zbus(property)]
+        fn active_state(&self) -> String {
+            self.state().active.to_owned()
+        }
+
+        #[zbus(property)]
+        fn sub_state(&self) -> String {
+            self.state().sub.to_owned()
+        }
+
+        #[zbus(property)]
+        fn freezer_state(&self) -> String {
+            self.state().freezer.to_owned()
+        }
+    }
+
+    fn add_unit(
+        units: &Units,
+        name: &str,
+        active: &'static str,
+        sub: &'static str,
+    ) -> OwnedObjectPath {
+        let mut units = units.lock().unwrap();
+        let path =
+            OwnedObjectPath::try_from(format!("/org/freedesktop/systemd1/unit/u{}", units.len()))
+                .unwrap();
+        units.insert(
+            name.to_owned(),
+            FakeUnitState {
+                path: path.clone(),
+                active,
+                sub,
+                freezer: "running",
+            },
+        );
+        path
+    }
+
+    async fn fake_systemd(
+        whitelist: &[&str],
+        applications: Vec<ApplicationManifest>,
+    ) -> anyhow::Result<(SystemdController, Connection)> {
+        let units = Units::default();
+        let (client, server) = tokio::net::UnixStream::pair()?;
+        let mut builder = zbus::connection::Builder::unix_stream(server)
+            .server(zbus::Guid::generate())?
+            .p2p()
+            .serve_at(
+                "/org/freedesktop/systemd1",
+                FakeManager {
+                    units: units.clone(),
+                    jobs: 0,
+                },
+            )?;
+        for name in ["foo.service", "secret.service"] {
+            let path = add_unit(&units, name, "inactive", "dead");
+            builder = builder.serve_at(path, FakeUnit::new(&units, name))?;
+        }
+        let (server, client) = tokio::try_join!(
+            builder.build(),
+            zbus::connection::Builder::unix_stream(client).p2p().build()
+        )?;
+        let whitelist = whitelist.iter().map(ToString::to_string).collect();
+        let controller =
+            SystemdController::with_connection(client, whitelist, applications).await?;
+        Ok((controller, server))
+    }
+
+    #[tokio::test]
+    async fn test_unit_lifecycle() -> anyhow::Result<()> {
+        let (ctl, _server) = fake_systemd(&["foo.service"], Vec::new()).await?;
+
+        let status = ctl.get_unit_status("foo.service").await?;
+        assert!(status.is_exitted());
+
+        ctl.start_unit("foo.service").await?;
+        let status = ctl.get_unit_status("foo.service").await?;
+        assert!(status.is_running());
+        assert!(status.path.starts_with("/org/freedesktop/systemd1/unit/"));
+
+        ctl.freeze_unit("foo.service").await?;
+        assert!(ctl.get_unit_status("foo.service").await?.is_paused());
+        ctl.unfreeze_unit("foo.service").await?;
+        assert!(ctl.get_unit_status("foo.service").await?.is_running());
+
+        ctl.stop_unit("foo.service").await?;
+        assert!(ctl.get_unit_status("foo.service").await?.is_exitted());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whitelist() -> anyhow::Result<()> {
+        let (ctl, _server) = fake_systemd(&["foo.service"], Vec::new()).await?;
+        assert!(ctl.get_unit_status("secret.service").await.is_err());
+        assert!(ctl.start_unit("secret.service").await.is_err());
+        assert!(ctl.kill_unit("secret.service").await.is_err());
+        assert!(ctl.start_unit("").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_start_application() -> anyhow::Result<()> {
+        let apps = vec![ApplicationManifest {
+            name: "foot".into(),
+            command: "foot --server".into(),
+            args: vec!["flag".into()],
+            directories: Vec::new(),
+        }];
+        let (ctl, _server) = fake_systemd(&[], apps).await?;
+
+        assert!(ctl.is_whitelisted("foot@0.service"));
+        assert!(!ctl.is_whitelisted("foot@x.service"));
+        assert!(!ctl.is_whitelisted("foot.service"));
+
+        let status = ctl
+            .start_application("foot@0.service", &["--hold".into()])
+            .await?;
+        assert_eq!(status.name, "foot@0.service");
+        assert!(status.is_running());
+
+        assert!(
+            ctl.start_application("foot@1.service", &["http://evil".into()])
+                .await
+                .is_err()
+        );
+        assert!(
+            ctl.start_application("chrome@0.service", &[])
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+}
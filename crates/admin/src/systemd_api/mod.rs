@@ -1,5 +1,8 @@
 // SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod applications;
 pub mod client;
+pub mod controller;
+pub mod dbus;
 pub mod server;
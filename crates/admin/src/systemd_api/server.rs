@@ -2,74 +2,187 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::pb;
-use std::pin::Pin;
+use crate::utils::tonic::{Stream, escalate};
+use anyhow::Context;
+use async_stream::try_stream;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tonic::{Request, Response, Status};
+use tracing::{debug, info};
+
+use super::controller::{SystemdController, exited_unit};
+use crate::types::UnitStatus;
 
 pub use pb::systemd::unit_control_service_server::UnitControlServiceServer;
 
-#[derive(Debug, Clone, Default)]
-pub struct SystemdService {}
+const RESOURCE_STREAM_INTERVAL: Duration = Duration::from_millis(400);
+const MEMINFO: &str = "/proc/meminfo";
+
+#[derive(Debug, Clone)]
+pub struct SystemdService {
+    controller: Arc<SystemdController>,
+}
 
 impl SystemdService {
     #[must_use]
-    pub fn new() -> Self {
-        SystemdService::default()
+    pub fn new(controller: SystemdController) -> Self {
+        Self {
+            controller: Arc::new(controller),
+        }
+    }
+
+    /// Status of unit after stop/kill, transient units could be already gone
+    async fn status_or_exited(&self, name: &str) -> UnitStatus {
+        self.controller
+            .get_unit_status(name)
+            .await
+            .unwrap_or_else(|e| {
+                debug!("Status of {name} unavailable, assume exited: {e}");
+                exited_unit(name)
+            })
     }
 }
 
-type Stream<T> = Pin<Box<dyn tokio_stream::Stream<Item = Result<T, Status>> + Send + 'static>>;
+fn unit_response(status: UnitStatus) -> pb::systemd::UnitResponse {
+    pb::systemd::UnitResponse {
+        unit_status: Some(status.into()),
+    }
+}
+
+fn mem_total() -> anyhow::Result<u64> {
+    let meminfo = std::fs::read_to_string(MEMINFO)?;
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))
+        .and_then(|rest| rest.trim().strip_suffix("kB"))
+        .context("MemTotal missing in /proc/meminfo")?
+        .trim()
+        .parse::<u64>()
+        .map(|kb| kb * 1024)
+        .context("parsing MemTotal")
+}
 
 #[tonic::async_trait]
 impl pb::systemd::unit_control_service_server::UnitControlService for SystemdService {
     async fn get_unit_status(
         &self,
-        _request: Request<pb::systemd::UnitRequest>,
+        request: Request<pb::systemd::UnitRequest>,
     ) -> Result<Response<pb::systemd::UnitResponse>, Status> {
-        unimplemented!()
+        escalate(request, async |req| {
+            info!("Incoming request to fetch unit status: {req:?}");
+            let status = self.controller.get_unit_status(&req.unit_name).await?;
+            Ok(unit_response(status))
+        })
+        .await
     }
     async fn start_unit(
         &self,
-        _request: Request<pb::systemd::UnitRequest>,
+        request: Request<pb::systemd::UnitRequest>,
     ) -> Result<Response<pb::systemd::UnitResponse>, Status> {
-        unimplemented!()
+        escalate(request, async |req| {
+            info!("Incoming request to (re)start {req:?}");
+            self.controller.start_unit(&req.unit_name).await?;
+            let status = self.controller.get_unit_status(&req.unit_name).await?;
+            Ok(unit_response(status))
+        })
+        .await
     }
     async fn stop_unit(
         &self,
-        _request: Request<pb::systemd::UnitRequest>,
+        request: Request<pb::systemd::UnitRequest>,
     ) -> Result<Response<pb::systemd::UnitResponse>, Status> {
-        unimplemented!()
+        escalate(request, async |req| {
+            info!("Incoming request to stop {req:?}");
+            self.controller.stop_unit(&req.unit_name).await?;
+            Ok(unit_response(self.status_or_exited(&req.unit_name).await))
+        })
+        .await
     }
     async fn kill_unit(
         &self,
-        _request: Request<pb::systemd::UnitRequest>,
+        request: Request<pb::systemd::UnitRequest>,
     ) -> Result<Response<pb::systemd::UnitResponse>, Status> {
-        unimplemented!()
+        escalate(request, async |req| {
+            info!("Incoming request to kill {req:?}");
+            self.controller.kill_unit(&req.unit_name).await?;
+            Ok(unit_response(self.status_or_exited(&req.unit_name).await))
+        })
+        .await
     }
     async fn freeze_unit(
         &self,
-        _request: Request<pb::systemd::UnitRequest>,
+        request: Request<pb::systemd::UnitRequest>,
     ) -> Result<Response<pb::systemd::UnitResponse>, Status> {
-        unimplemented!()
+        escalate(request, async |req| {
+            info!("Incoming request to freeze {req:?}");
+            self.controller.freeze_unit(&req.unit_name).await?;
+            let status = self.controller.get_unit_status(&req.unit_name).await?;
+            Ok(unit_response(status))
+        })
+        .await
     }
     async fn unfreeze_unit(
         &self,
-        _request: Request<pb::systemd::UnitRequest>,
+        request: Request<pb::systemd::UnitRequest>,
     ) -> Result<tonic::Response<pb::systemd::UnitResponse>, Status> {
-        unimplemented!()
+        escalate(request, async |req| {
+            info!("Incoming request to unfreeze {req:?}");
+            self.controller.unfreeze_unit(&req.unit_name).await?;
+            let status = self.controller.get_unit_status(&req.unit_name).await?;
+            Ok(unit_response(status))
+        })
+        .await
     }
     /// Server streaming response type for the `MonitorUnit` method.
     type MonitorUnitStream = Stream<pb::systemd::UnitResourceResponse>;
     async fn monitor_unit(
         &self,
-        _request: Request<pb::systemd::UnitResourceRequest>,
+        request: Request<pb::systemd::UnitResourceRequest>,
     ) -> Result<Response<Self::MonitorUnitStream>, Status> {
-        unimplemented!()
+        escalate(request, async |req| {
+            info!("Incoming request to monitor {req:?}");
+            let controller = self.controller.clone();
+            let name = req.unit_name;
+            let mut last = (Instant::now(), controller.unit_usage(&name).await?);
+            let total = mem_total()?;
+            let stream = try_stream! {
+                let mut tick = tokio::time::interval(RESOURCE_STREAM_INTERVAL);
+                tick.tick().await; // First tick fires instantly
+                loop {
+                    tick.tick().await;
+                    let now = Instant::now();
+                    let usage = controller
+                        .unit_usage(&name)
+                        .await
+                        .map_err(|e| Status::not_found(format!("{name}: {e}")))?;
+                    let elapsed = now.duration_since(last.0).as_nanos();
+                    let cpu = usage.cpu_nsec.saturating_sub(last.1.cpu_nsec);
+                    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+                    let response = pb::systemd::UnitResourceResponse {
+                        cpu_usage: cpu as f64 * 100.0 / elapsed.max(1) as f64,
+                        memory_usage: (usage.memory_bytes as f64 * 100.0 / total.max(1) as f64) as f32,
+                    };
+                    last = (now, usage);
+                    yield response;
+                }
+            };
+            Ok(Box::pin(stream) as Self::MonitorUnitStream)
+        })
+        .await
     }
 
     async fn start_application(
         &self,
-        _request: Request<pb::systemd::AppUnitRequest>,
+        request: Request<pb::systemd::AppUnitRequest>,
     ) -> Result<Response<pb::systemd::UnitResponse>, Status> {
-        unimplemented!()
+        escalate(request, async |req| {
+            info!("Incoming request to start application {req:?}");
+            let status = self
+                .controller
+                .start_application(&req.unit_name, &req.args)
+                .await?;
+            Ok(unit_response(status))
+        })
+        .await
     }
 }
@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use super::audit::{AuditDecision, AuditLog, AuditRecord, redact};
 use super::x509::SecurityInfo;
 use anyhow::Context;
 use bytes::Buf;
@@ -10,27 +11,74 @@ use cedar_policy::{
     Context as CedarContext,
     Decision,
     Entities,
+    Entity,
     EntityId,
     EntityTypeName,
     EntityUid,
     PolicySet,
     Request as CedarRequest, // rename to avoid clash with tonic::Request
+    RestrictedExpression,
 };
 use givc_common::pb::reflection::ADMIN_DESCRIPTOR;
 use http::Request as HttpRequest;
 use http_body_util::{BodyExt, Full};
 use prost_reflect::{DescriptorPool, DynamicMessage};
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 use std::{fs, str::FromStr, sync::Arc};
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::task::JoinHandle;
 use tonic::Status;
 use tonic::body::Body;
 use tonic_middleware::RequestInterceptor;
-use tracing::{debug, error, warn};
+use tracing::{debug, error, info, warn};
+
+const POLICY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Service assumed by dry run, when only method name is given
+pub const ADMIN_SERVICE: &str = "admin.AdminService";
+
+/// Entity types of request principal, action and resource
+pub const TYPE_SOURCE: &str = "Source";
+pub const TYPE_ACTION: &str = "Command";
+pub const TYPE_MODULE: &str = "Module";
+
+/// Live description of the system (VMs, agents, applications), which allows
+/// policies to use `in` and attribute conditions
+pub trait EntityStore: Send + Sync {
+    /// All entities known to store
+    ///
+    /// # Errors
+    /// Returns error if entities can't be built
+    fn entities(&self) -> anyhow::Result<Vec<Entity>>;
+
+    /// Entities, which request source with `hostname` is member of
+    ///
+    /// # Errors
+    /// Returns error if entity uids can't be built
+    fn memberships(&self, hostname: &str) -> anyhow::Result<HashSet<EntityUid>>;
+}
+
+/// Build uid of entity `id` with type `type_name`
+///
+/// # Errors
+/// Returns error if `type_name` isn't valid cedar type name
+pub fn entity_uid(type_name: &str, id: &str) -> anyhow::Result<EntityUid> {
+    let type_name = EntityTypeName::from_str(type_name)
+        .with_context(|| format!("invalid entity type name {type_name}"))?;
+    Ok(EntityUid::from_type_name_and_id(
+        type_name,
+        EntityId::new(id),
+    ))
+}
 
 #[derive(Clone)]
 pub struct Authorizer {
     pool: DescriptorPool,
     policy_state: Arc<std::sync::RwLock<(Arc<PolicySet>, Arc<CedarAuthorizer>)>>,
+    entity_store: Option<Arc<dyn EntityStore>>,
+    audit_log: Option<Arc<AuditLog>>,
     type_source: EntityTypeName,
     type_action: EntityTypeName,
     type_module: EntityTypeName,
@@ -45,11 +93,7 @@ impl Authorizer {
         let pool = DescriptorPool::decode(ADMIN_DESCRIPTOR)
             .context("Failed to decode ADMIN_DESCRIPTOR; check your reflection setup.")?;
 
-        // Direct execution without matching or unwrapping
-        let policy_text = fs::read_to_string(acl_file)
-            .with_context(|| format!("Failed to read cedar policy file: {}", acl_file.display()))?;
-
-        let policies = PolicySet::from_str(&policy_text).context("Failed to parse cedar policy")?;
+        let policies = load_policies(acl_file)?;
 
         let policy_state = Arc::new(std::sync::RwLock::new((
             Arc::new(policies),
@@ -59,19 +103,225 @@ impl Authorizer {
         Ok(Self {
             pool,
             policy_state,
-            type_source: EntityTypeName::from_str("Source").context("valid type name")?,
-            type_action: EntityTypeName::from_str("Command").context("valid type name")?,
-            type_module: EntityTypeName::from_str("Module").context("valid type name")?,
+            entity_store: None,
+            audit_log: None,
+            type_source: EntityTypeName::from_str(TYPE_SOURCE).context("valid type name")?,
+            type_action: EntityTypeName::from_str(TYPE_ACTION).context("valid type name")?,
+            type_module: EntityTypeName::from_str(TYPE_MODULE).context("valid type name")?,
         })
     }
 
-    fn authorize(
+    /// Evaluate policies against entities from `store`, instead of bare principal
+    #[must_use]
+    pub fn with_entity_store(self, store: Arc<dyn EntityStore>) -> Self {
+        Self {
+            entity_store: Some(store),
+            ..self
+        }
+    }
+
+    /// Record every decision to `log`
+    #[must_use]
+    pub fn with_audit_log(self, log: Arc<AuditLog>) -> Self {
+        Self {
+            audit_log: Some(log),
+            ..self
+        }
+    }
+
+    /// Record of request to `method`, as denied without policies evaluated
+    fn audit_record(
+        security: &SecurityInfo,
+        method: String,
+        mut context: serde_json::Value,
+    ) -> AuditRecord {
+        redact(&mut context);
+        AuditRecord {
+            timestamp_ms: AuditRecord::now_ms(),
+            principal: security.hostname().unwrap_or_default().to_owned(),
+            common_name: security.common_name().map(ToOwned::to_owned),
+            organizational_units: security.organizational_units().to_vec(),
+            method,
+            context,
+            decision: AuditDecision::Deny,
+            policies: Vec::new(),
+            errors: Vec::new(),
+            rejected: None,
+        }
+    }
+
+    /// Record decision on request
+    ///
+    /// # Errors
+    /// Returns `Err(Status::unavailable)` if decision can't be recorded, then
+    /// request is denied regardless of decision
+    fn audit(
         &self,
+        security: &SecurityInfo,
+        method: String,
+        context: serde_json::Value,
+        response: &cedar_policy::Response,
+    ) -> Result<(), Status> {
+        let Some(log) = &self.audit_log else {
+            return Ok(());
+        };
+        log.append(AuditRecord {
+            decision: match response.decision() {
+                Decision::Allow => AuditDecision::Allow,
+                Decision::Deny => AuditDecision::Deny,
+            },
+            policies: response
+                .diagnostics()
+                .reason()
+                .map(ToString::to_string)
+                .collect(),
+            errors: response
+                .diagnostics()
+                .errors()
+                .map(ToString::to_string)
+                .collect(),
+            ..Self::audit_record(security, method, context)
+        })
+        .map_err(|_| Status::unavailable("Audit log unavailable"))
+    }
+
+    /// Record request denied with `status` before policies were evaluated
+    fn audit_rejection(&self, security: &SecurityInfo, method: String, status: &Status) {
+        let Some(log) = &self.audit_log else {
+            return;
+        };
+        // Request is denied anyway, failure is already logged
+        let _ = log.append(AuditRecord {
+            rejected: Some(status.message().to_owned()),
+            ..Self::audit_record(security, method, serde_json::json!({}))
+        });
+    }
+
+    /// Decide on request offline, the same way as on incoming call, but without auditing.
+    /// `method` is either full path like `admin.AdminService/StartApplication`, or bare
+    /// method name of `admin.AdminService`. `request_json` is request message in protobuf
+    /// JSON mapping, it's ignored for methods not known to descriptor pool.
+    ///
+    /// # Errors
+    /// Returns error if method is unknown, request can't be decoded, or evaluation fails
+    pub fn dry_run(
+        &self,
+        security: &SecurityInfo,
+        method: &str,
+        request_json: &str,
+    ) -> anyhow::Result<cedar_policy::Response> {
+        let (service_name, method_name) =
+            parse_grpc_path(method).unwrap_or((ADMIN_SERVICE, method.trim_matches('/')));
+        let context_json = match self.pool.get_service_by_name(service_name) {
+            Some(service) => {
+                let method = service
+                    .methods()
+                    .find(|m| m.name() == method_name)
+                    .with_context(|| format!("unknown method {service_name}/{method_name}"))?;
+                if method.is_client_streaming() {
+                    // Streaming requests are authorized without body
+                    serde_json::json!({})
+                } else {
+                    let mut de = serde_json::Deserializer::from_str(request_json);
+                    let msg = DynamicMessage::deserialize(method.input(), &mut de)
+                        .with_context(|| format!("decoding {}", method.input().full_name()))?;
+                    de.end()?;
+                    serde_json::to_value(&msg)?
+                }
+            }
+            None => serde_json::json!({}),
+        };
+        let (response, _) = self
+            .evaluate(security, service_name, method_name, context_json)
+            .map_err(|status| anyhow::anyhow!("{}", status.message()))?;
+        Ok(response)
+    }
+
+    /// Replace active policy with content of `acl_file`
+    ///
+    /// # Errors
+    /// Returns error if file can't be read or policy is invalid, previous policy stays active then
+    pub fn reload(&self, acl_file: &Path) -> anyhow::Result<()> {
+        let policies = Arc::new(load_policies(acl_file)?);
+        let mut state = self.policy_state.write().unwrap();
+        state.0 = policies;
+        Ok(())
+    }
+
+    /// Reload policy when `acl_file` changes on disk, or SIGHUP is received
+    ///
+    /// # Errors
+    /// Returns error if SIGHUP handler can't be installed
+    pub fn watch(&self, acl_file: PathBuf) -> anyhow::Result<JoinHandle<()>> {
+        let mut hangup =
+            signal(SignalKind::hangup()).context("Failed to install SIGHUP handler")?;
+        let this = self.clone();
+        Ok(tokio::spawn(async move {
+            let mut seen = fingerprint(&acl_file);
+            let mut poll = tokio::time::interval(POLICY_POLL_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = hangup.recv() => info!("cedar: SIGHUP received, reloading policy"),
+                    _ = poll.tick() => {
+                        let current = fingerprint(&acl_file);
+                        if current == seen {
+                            continue;
+                        }
+                        seen = current;
+                        info!("cedar: {} changed, reloading policy", acl_file.display());
+                    }
+                }
+                match this.reload(&acl_file) {
+                    Ok(()) => info!("cedar: policy reloaded from {}", acl_file.display()),
+                    Err(e) => error!("cedar: keeping previous policy, reload failed: {e:#}"),
+                }
+            }
+        }))
+    }
+
+    /// Entities from store, plus principal with attributes from its certificate
+    fn entities(
+        &self,
+        principal: &EntityUid,
         source: &str,
+        security: &SecurityInfo,
+    ) -> anyhow::Result<Entities> {
+        let strings = |values: &[String]| {
+            RestrictedExpression::new_set(
+                values.iter().cloned().map(RestrictedExpression::new_string),
+            )
+        };
+        let mut attrs = HashMap::from([
+            ("dns_names".to_owned(), strings(security.dns_names())),
+            (
+                "organizational_units".to_owned(),
+                strings(security.organizational_units()),
+            ),
+        ]);
+        if let Some(cn) = security.common_name() {
+            attrs.insert(
+                "common_name".to_owned(),
+                RestrictedExpression::new_string(cn.to_owned()),
+            );
+        }
+
+        let (parents, mut entities) = match &self.entity_store {
+            Some(store) => (store.memberships(source)?, store.entities()?),
+            None => (HashSet::new(), Vec::new()),
+        };
+        entities.push(Entity::new(principal.clone(), attrs, parents)?);
+        Ok(Entities::from_entities(entities, None)?)
+    }
+
+    /// Evaluate policies for request, returns cedar response and context it was given
+    fn evaluate(
+        &self,
+        security: &SecurityInfo,
         full_service_name: &str,
         grpc_method_name: &str,
         mut context_json: serde_json::Value,
-    ) -> Result<(), Status> {
+    ) -> Result<(cedar_policy::Response, serde_json::Value), Status> {
+        let source = security.hostname().unwrap_or_default();
         let (module_name, service_name) = full_service_name.split_once('.').ok_or_else(|| {
             Status::internal(format!("Invalid service name format: {full_service_name}"))
         })?;
@@ -94,7 +344,7 @@ impl Authorizer {
             );
         }
 
-        let context = CedarContext::from_json_value(context_json, None)
+        let context = CedarContext::from_json_value(context_json.clone(), None)
             .map_err(|e| Status::internal(format!("Invalid Cedar context: {e}")))?;
 
         let principal =
@@ -106,25 +356,43 @@ impl Authorizer {
             EntityId::new(grpc_method_name),
         );
 
+        let entities = self.entities(&principal, source, security).map_err(|e| {
+            error!("cedar: failed to build entities: {e:#}");
+            Status::internal("Failed to build Cedar entities")
+        })?;
+
         let request = CedarRequest::new(principal, action, resource, context, None)
             .map_err(|e| Status::internal(format!("failed to build Cedar request: {e}")))?;
 
-        let entities = Entities::empty();
-
         let (policies, authorizer) = {
             let state = self.policy_state.read().unwrap();
             state.clone()
         };
 
         let response = authorizer.is_authorized(&request, &policies, &entities);
+        Ok((response, context_json))
+    }
+
+    pub(crate) fn authorize(
+        &self,
+        security: &SecurityInfo,
+        full_service_name: &str,
+        grpc_method_name: &str,
+        context_json: serde_json::Value,
+    ) -> Result<(), Status> {
+        let method = format!("{full_service_name}/{grpc_method_name}");
+        let (response, context) = self
+            .evaluate(security, full_service_name, grpc_method_name, context_json)
+            .inspect_err(|status| self.audit_rejection(security, method.clone(), status))?;
+        self.audit(security, method, context, &response)?;
 
         match response.decision() {
             Decision::Allow => Ok(()),
             Decision::Deny => {
                 warn!(
-                    "cedar: authorization denied for source vm = {source}, \
-                     module = {module_name}, and grpc service = {service_name}, \
-                     (rpc method: {grpc_method_name})"
+                    "cedar: authorization denied for source vm = {}, \
+                     grpc service = {full_service_name}, (rpc method: {grpc_method_name})",
+                    security.hostname().unwrap_or_default()
                 );
                 Err(Status::permission_denied(
                     "cedar: permission denied by admin access control policy",
@@ -134,66 +402,240 @@ impl Authorizer {
     }
 }
 
+impl Authorizer {
+    /// Buffer request body, and decode its message as cedar context. Context is empty
+    /// for unknown and client streaming methods, or if body doesn't hold whole message.
+    async fn decode_context(
+        &self,
+        service_name: &str,
+        method_name: &str,
+        body: Body,
+    ) -> Result<(Body, serde_json::Value), Status> {
+        let Some(method) = self
+            .pool
+            .get_service_by_name(service_name)
+            .and_then(|service| service.methods().find(|m| m.name() == method_name))
+            .filter(|method| !method.is_client_streaming())
+        else {
+            return Ok((body, serde_json::json!({})));
+        };
+        let body_bytes = body
+            .collect()
+            .await
+            .map_err(|e| Status::internal(format!("Failed to buffer body: {e}")))?
+            .to_bytes();
+
+        let mut buf = body_bytes.clone();
+        let mut context_json = serde_json::json!({});
+        if let Ok(_compressed) = buf.try_get_u8()
+            && let Ok(len) = buf.try_get_u32().map(|len| len as usize)
+            && let Some(payload) = buf.chunk().get(..len)
+        {
+            let msg = DynamicMessage::decode(method.input(), payload).map_err(|err| {
+                debug!("Authorizer: Failed to decode: {}", err);
+                Status::internal(format!("Failed to decode: {err}"))
+            })?;
+            context_json = serde_json::to_value(&msg)
+                .inspect_err(|e| error!("Failed to serialize to cedar context JSON: {e}"))
+                .unwrap_or(serde_json::json!({}));
+        }
+        Ok((Body::new(Full::new(body_bytes)), context_json))
+    }
+}
+
 #[tonic::async_trait]
 impl RequestInterceptor for Authorizer {
     async fn intercept(&self, req: HttpRequest<Body>) -> Result<HttpRequest<Body>, Status> {
-        let source = req
+        let path = req.uri().path().to_owned();
+        let Some(security) = req
             .extensions()
             .get::<SecurityInfo>()
-            .and_then(|sec_info| sec_info.hostname().map(String::from))
-            .ok_or_else(|| {
-                error!("SecurityInfo extension or hostname is missing");
-                Status::internal(
-                    "Cedar authorization denied: SecurityInfo extension or hostname is missing",
-                )
-            })?;
-
-        let (parts, mut body) = req.into_parts();
-        let path = parts.uri.path();
-
-        if let Some((service_name, method_name)) = parse_grpc_path(path) {
-            if let Some(service) = self.pool.get_service_by_name(service_name)
-                && let Some(method) = service.methods().find(|m| m.name() == method_name)
-                && !method.is_client_streaming()
-            {
-                let body_bytes = body
-                    .collect()
-                    .await
-                    .map_err(|e| Status::internal(format!("Failed to buffer body: {e}")))?
-                    .to_bytes();
-
-                let mut buf = body_bytes.clone();
-
-                if let Ok(_compressed) = buf.try_get_u8()
-                    && let Ok(len) = buf.try_get_u32().map(|len| len as usize)
-                    && let Some(payload) = buf.chunk().get(..len)
-                {
-                    let msg = DynamicMessage::decode(method.input(), payload).map_err(|err| {
-                        debug!("Authorizer: Failed to decode: {}", err);
-                        Status::internal(format!("Failed to decode: {err}"))
-                    })?;
-                    let cedar_context_json = serde_json::to_value(&msg)
-                        .inspect_err(|e| error!("Failed to serialize to cedar context JSON: {e}"))
-                        .unwrap_or(serde_json::json!({}));
-
-                    self.authorize(&source, service_name, method_name, cedar_context_json)?;
-
-                    let reconstructed_body = Body::new(Full::new(body_bytes));
-                    return Ok(HttpRequest::from_parts(parts, reconstructed_body));
-                }
-                body = Body::new(Full::new(body_bytes));
-            }
+            .filter(|sec_info| sec_info.hostname().is_some())
+            .cloned()
+        else {
+            error!("SecurityInfo extension or hostname is missing");
+            let status = Status::internal(
+                "Cedar authorization denied: SecurityInfo extension or hostname is missing",
+            );
+            self.audit_rejection(&SecurityInfo::disabled(), path, &status);
+            return Err(status);
+        };
 
-            let context_json = serde_json::json!({});
-            self.authorize(&source, service_name, method_name, context_json)?;
-            return Ok(HttpRequest::from_parts(parts, body));
-        }
+        let Some((service_name, method_name)) = parse_grpc_path(&path) else {
+            let status = Status::internal("Cedar authorization denied: Bad request");
+            self.audit_rejection(&security, path.clone(), &status);
+            return Err(status);
+        };
 
-        Err(Status::internal("Cedar authorization denied: Bad request"))
+        let (parts, body) = req.into_parts();
+        let (body, context_json) = self
+            .decode_context(service_name, method_name, body)
+            .await
+            .inspect_err(|status| {
+                self.audit_rejection(&security, format!("{service_name}/{method_name}"), status);
+            })?;
+        self.authorize(&security, service_name, method_name, context_json)?;
+        Ok(HttpRequest::from_parts(parts, body))
     }
 }
 
+fn load_policies(acl_file: &Path) -> anyhow::Result<PolicySet> {
+    let policy_text = fs::read_to_string(acl_file)
+        .with_context(|| format!("Failed to read cedar policy file: {}", acl_file.display()))?;
+    PolicySet::from_str(&policy_text).context("Failed to parse cedar policy")
+}
+
+pub(crate) fn fingerprint(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
 fn parse_grpc_path(path: &str) -> Option<(&str, &str)> {
     let mut parts = path.trim_matches('/').split('/');
     parts.next().zip(parts.next())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::audit::read_audit_log;
+
+    const PERMIT: &str = "permit(principal, action, resource);";
+
+    fn poweroff(authorizer: &Authorizer) -> Result<(), Status> {
+        authorizer.authorize(
+            &SecurityInfo::with_hostname("gui-vm"),
+            "admin.AdminService",
+            "Poweroff",
+            serde_json::json!({}),
+        )
+    }
+
+    #[test]
+    fn test_reload() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("givc-cedar-{}.cedar", std::process::id()));
+        fs::write(&path, PERMIT)?;
+        let authorizer = Authorizer::new(&path)?;
+        assert!(poweroff(&authorizer).is_ok());
+
+        // Invalid policy is rejected, old one stays active
+        fs::write(&path, "permit(principal, action")?;
+        assert!(authorizer.reload(&path).is_err());
+        assert!(poweroff(&authorizer).is_ok());
+
+        // Empty policy is valid, and denies everything
+        fs::write(&path, "")?;
+        authorizer.reload(&path)?;
+        assert!(poweroff(&authorizer).is_err());
+
+        fs::write(
+            &path,
+            format!(
+                r#"{PERMIT} forbid(principal, action, resource) when {{ action == Command::"Poweroff" }};"#
+            ),
+        )?;
+        authorizer.reload(&path)?;
+        assert!(poweroff(&authorizer).is_err());
+
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_dry_run() -> anyhow::Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("givc-cedar-dry-{}.cedar", std::process::id()));
+        fs::write(
+            &path,
+            r#"permit(principal == Source::"gui-vm", action, resource)
+               when {
+                 action == Command::"StartApplication" &&
+                 context has AppName && context.AppName == "chrome"
+               };"#,
+        )?;
+        let authorizer = Authorizer::new(&path)?;
+        fs::remove_file(&path)?;
+        let gui = SecurityInfo::with_hostname("gui-vm");
+
+        let start = |method, json| authorizer.dry_run(&gui, method, json);
+        let allowed = start("StartApplication", r#"{"AppName": "chrome"}"#)?;
+        assert_eq!(allowed.decision(), Decision::Allow);
+        assert_eq!(allowed.diagnostics().reason().count(), 1);
+        let denied = start(
+            "/admin.AdminService/StartApplication",
+            r#"{"AppName": "foot"}"#,
+        )?;
+        assert_eq!(denied.decision(), Decision::Deny);
+
+        assert!(start("Explode", "{}").is_err());
+        assert!(start("StartApplication", r#"{"NoSuchField": 1}"#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_audit() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("givc-cedar-audit-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        let policy = dir.join("policy.cedar");
+        let log = dir.join("audit.jsonl");
+        fs::write(
+            &policy,
+            r#"permit(principal == Source::"gui-vm", action, resource);"#,
+        )?;
+        let audit_log = Arc::new(AuditLog::open(log.clone(), 1 << 20, 1)?);
+        let authorizer = Authorizer::new(&policy)?.with_audit_log(audit_log.clone());
+
+        assert!(poweroff(&authorizer).is_ok());
+        assert!(
+            authorizer
+                .authorize(
+                    &SecurityInfo::with_hostname("net-vm"),
+                    "admin.AdminService",
+                    "Reboot",
+                    serde_json::json!({}),
+                )
+                .is_err()
+        );
+
+        // Secrets are redacted, and requests rejected before evaluation are audited too
+        assert!(
+            authorizer
+                .authorize(
+                    &SecurityInfo::with_hostname("gui-vm"),
+                    "admin.AdminService",
+                    "SetGeneration",
+                    serde_json::json!({"Oci": {"Username": "user", "Password": "secret"}}),
+                )
+                .is_ok()
+        );
+        assert!(
+            authorizer
+                .authorize(
+                    &SecurityInfo::with_hostname("gui-vm"),
+                    "NoModule",
+                    "Poweroff",
+                    serde_json::json!({}),
+                )
+                .is_err()
+        );
+
+        audit_log.flush();
+        let records = read_audit_log(&log)?;
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].principal, "gui-vm");
+        assert_eq!(records[0].method, "admin.AdminService/Poweroff");
+        assert_eq!(records[0].decision, AuditDecision::Allow);
+        assert_eq!(records[0].policies, ["policy0"]);
+        assert_eq!(records[0].context["service"], "AdminService");
+        assert_eq!(records[1].decision, AuditDecision::Deny);
+        assert!(records[1].policies.is_empty());
+        assert_eq!(records[2].context["Oci"]["Username"], "user");
+        assert_ne!(records[2].context["Oci"]["Password"], "secret");
+        assert_eq!(records[3].decision, AuditDecision::Deny);
+        assert!(records[3].rejected.is_some());
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+// SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Cedar schema of admin API, generated from protobuf descriptors. Each RPC method
+// is an action, with request fields (as `Authorizer` serializes them) in its context.
+use anyhow::Context;
+use cedar_policy::{Policy, PolicySet, Schema, ValidationMode, ValidationResult, Validator};
+use givc_common::pb::reflection::ADMIN_DESCRIPTOR;
+use prost_reflect::{DescriptorPool, FieldDescriptor, Kind, MessageDescriptor};
+use serde_json::{Map, Value, json};
+
+use super::access_control::{TYPE_ACTION, TYPE_MODULE, TYPE_SOURCE};
+use crate::admin::entities::{
+    TYPE_SERVICE_TYPE, TYPE_TRUST_LEVEL, TYPE_UNIT, TYPE_VM, TYPE_VM_TYPE,
+};
+
+// Served by tonic-reflection, so absent in `ADMIN_DESCRIPTOR`
+const REFLECTION_METHOD: &str = "ServerReflectionInfo";
+
+// Guard against recursive messages
+const MAX_DEPTH: usize = 8;
+
+fn string() -> Value {
+    json!({"type": "String"})
+}
+
+fn string_set() -> Value {
+    json!({"type": "Set", "element": string()})
+}
+
+/// Cedar type of single value of `kind`, as serialized by `prost_reflect`.
+/// `None` for values which cedar can't represent, like floats.
+fn value_type(kind: &Kind, depth: usize) -> Option<Value> {
+    match kind {
+        Kind::Bool => Some(json!({"type": "Boolean"})),
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 | Kind::Uint32 | Kind::Fixed32 => {
+            Some(json!({"type": "Long"}))
+        }
+        // 64-bit integers and bytes are serialized as strings in proto3 JSON
+        Kind::Int64
+        | Kind::Sint64
+        | Kind::Sfixed64
+        | Kind::Uint64
+        | Kind::Fixed64
+        | Kind::String
+        | Kind::Bytes
+        | Kind::Enum(_) => Some(string()),
+        Kind::Float | Kind::Double => None,
+        Kind::Message(msg) => match msg.full_name() {
+            "google.protobuf.Timestamp" | "google.protobuf.Duration" => Some(string()),
+            name if name.starts_with("google.protobuf.") => None,
+            _ if depth >= MAX_DEPTH => None,
+            _ => Some(record(msg, depth + 1)),
+        },
+    }
+}
+
+fn field_type(field: &FieldDescriptor, depth: usize) -> Option<Value> {
+    if field.is_map() {
+        // Arbitrary keys, can't be described by record type
+        return None;
+    }
+    let ty = value_type(&field.kind(), depth)?;
+    Some(if field.is_list() {
+        json!({"type": "Set", "element": ty})
+    } else {
+        ty
+    })
+}
+
+fn attributes(msg: &MessageDescriptor, depth: usize) -> Map<String, Value> {
+    msg.fields()
+        .filter_map(|field| {
+            let mut ty = field_type(&field, depth)?;
+            // Default values are omitted on serialization
+            ty["required"] = json!(false);
+            Some((field.json_name().to_owned(), ty))
+        })
+        .collect()
+}
+
+fn record(msg: &MessageDescriptor, depth: usize) -> Value {
+    json!({"type": "Record", "attributes": attributes(msg, depth)})
+}
+
+fn action(context: Map<String, Value>) -> Value {
+    json!({
+        "appliesTo": {
+            "principalTypes": [TYPE_SOURCE],
+            "resourceTypes": [TYPE_MODULE],
+            "context": {"type": "Record", "attributes": context},
+        }
+    })
+}
+
+/// Schema in cedar JSON format, for services in `pool`
+#[must_use]
+pub fn schema_json(pool: &DescriptorPool) -> Value {
+    let mut actions = Map::new();
+    let service_attr = || (String::from("service"), string());
+    for service in pool.services() {
+        for method in service.methods() {
+            let context = actions
+                .entry(method.name().to_owned())
+                .or_insert_with(|| action(Map::from_iter([service_attr()])))["appliesTo"]["context"]
+                ["attributes"]
+                .as_object_mut()
+                .expect("context is a record");
+            // Same method name in several services: all fields are optional anyway,
+            // first declaration wins on conflict
+            for (name, ty) in attributes(&method.input(), 0) {
+                context.entry(name).or_insert(ty);
+            }
+        }
+    }
+    actions.insert(
+        REFLECTION_METHOD.to_owned(),
+        action(Map::from_iter([service_attr()])),
+    );
+
+    json!({
+        "": {
+            "entityTypes": {
+                TYPE_SOURCE: {
+                    "memberOfTypes": [TYPE_UNIT],
+                    "shape": {
+                        "type": "Record",
+                        "attributes": {
+                            "dns_names": string_set(),
+                            "organizational_units": string_set(),
+                            "common_name": {"type": "String", "required": false},
+                        },
+                    },
+                },
+                TYPE_MODULE: {},
+                TYPE_UNIT: {
+                    "memberOfTypes": [TYPE_VM, TYPE_UNIT, TYPE_SERVICE_TYPE],
+                    "shape": {
+                        "type": "Record",
+                        "attributes": {
+                            "vm_type": string(),
+                            "service_type": string(),
+                            "trust_level": string(),
+                            "active_state": string(),
+                        },
+                    },
+                },
+                TYPE_VM: {
+                    "memberOfTypes": [TYPE_VM_TYPE, TYPE_TRUST_LEVEL],
+                    "shape": {
+                        "type": "Record",
+                        "attributes": {
+                            "vm_type": string(),
+                            "trust_level": string(),
+                        },
+                    },
+                },
+                TYPE_VM_TYPE: {},
+                TYPE_SERVICE_TYPE: {},
+                TYPE_TRUST_LEVEL: {},
+            },
+            "actions": actions,
+        }
+    })
+}
+
+/// Schema of admin API
+///
+/// # Errors
+/// Returns error if descriptors can't be decoded, or schema is rejected by cedar
+pub fn admin_schema() -> anyhow::Result<Schema> {
+    let pool = DescriptorPool::decode(ADMIN_DESCRIPTOR).context("decoding ADMIN_DESCRIPTOR")?;
+    Schema::from_json_value(schema_json(&pool)).context("building cedar schema")
+}
+
+// At runtime actions are `Command::"<Method>"` entities, but schemas know only `Action`
+fn rename_action_type(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if map.get("type").and_then(Value::as_str) == Some(TYPE_ACTION)
+                && map.contains_key("id")
+            {
+                map.insert("type".to_owned(), json!("Action"));
+            }
+            map.values_mut().for_each(rename_action_type);
+        }
+        Value::Array(items) => items.iter_mut().for_each(rename_action_type),
+        _ => (),
+    }
+}
+
+/// Validate `policies` against admin API schema
+///
+/// # Errors
+/// Returns error if policies can't be converted for validation
+pub fn validate_policies(schema: Schema, policies: &PolicySet) -> anyhow::Result<ValidationResult> {
+    let converted = policies
+        .policies()
+        .map(|policy| {
+            let mut est = policy.to_json()?;
+            rename_action_type(&mut est);
+            Ok(Policy::from_json(Some(policy.id().clone()), est)?)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let converted = PolicySet::from_policies(converted)?;
+    Ok(Validator::new(schema).validate(&converted, ValidationMode::Strict))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn validate(text: &str) -> anyhow::Result<ValidationResult> {
+        validate_policies(admin_schema()?, &PolicySet::from_str(text)?)
+    }
+
+    #[test]
+    fn test_schema() -> anyhow::Result<()> {
+        let pool = DescriptorPool::decode(ADMIN_DESCRIPTOR)?;
+        let schema = schema_json(&pool);
+        let start = &schema[""]["actions"]["StartApplication"]["appliesTo"]["context"];
+        assert_eq!(start["attributes"]["AppName"]["type"], "String");
+        assert_eq!(start["attributes"]["Args"]["type"], "Set");
+        assert!(schema[""]["actions"][REFLECTION_METHOD].is_object());
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate() -> anyhow::Result<()> {
+        let good = validate(
+            r#"permit(principal, action, resource)
+               when {
+                 principal == Source::"gui-vm" &&
+                 resource == Module::"admin" &&
+                 action == Command::"StartApplication" &&
+                 context has "VmName" && context.VmName == "chrome-vm"
+               };
+               permit(principal, action, resource)
+               when { action == Command::"ServerReflectionInfo" && resource == Module::"grpc" };"#,
+        )?;
+        assert!(good.validation_passed());
+
+        // Unknown RPC
+        assert!(
+            !validate(
+                r#"permit(principal, action, resource) when { action == Command::"Explode" };"#
+            )?
+            .validation_passed()
+        );
+        // Type error in context
+        assert!(
+            !validate(
+                r#"permit(principal, action, resource)
+                   when { action == Command::"StartApplication" && context.AppName == 1 };"#
+            )?
+            .validation_passed()
+        );
+        Ok(())
+    }
+}
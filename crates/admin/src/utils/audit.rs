@@ -0,0 +1,392 @@
+// SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Append-only trail of access control decisions, one JSON object per line.
+// When file grows over limit, it's rotated to `<path>.1`, older ones shifted to `<path>.2`, etc.
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::SystemTime;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tracing::{error, warn};
+
+pub const DEFAULT_AUDIT_LOG: &str = "/var/log/givc-admin/audit.jsonl";
+
+/// Records queued to writer thread, before new ones are refused
+const AUDIT_QUEUE_SIZE: usize = 1024;
+
+/// Request fields (compared case-insensitively), which values never reach audit log
+const SECRET_FIELDS: &[&str] = &["password", "token"];
+const REDACTED: &str = "<redacted>";
+
+/// Replace values of secret fields in `context`, at any depth
+pub fn redact(context: &mut serde_json::Value) {
+    match context {
+        serde_json::Value::Object(fields) => {
+            for (name, value) in fields.iter_mut() {
+                if SECRET_FIELDS.iter().any(|s| name.eq_ignore_ascii_case(s)) {
+                    *value = serde_json::Value::String(REDACTED.to_owned());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => (),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditDecision {
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Unix time, milliseconds
+    pub timestamp_ms: u64,
+    /// Hostname from peer certificate, used as cedar principal
+    pub principal: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub common_name: Option<String>,
+    #[serde(default)]
+    pub organizational_units: Vec<String>,
+    /// Full gRPC method path, like `admin.AdminService/StartApplication`
+    pub method: String,
+    /// Decoded request fields, passed to cedar as context, with secrets redacted
+    pub context: serde_json::Value,
+    pub decision: AuditDecision,
+    /// Policies which determined decision
+    #[serde(default)]
+    pub policies: Vec<String>,
+    /// Policies failed to evaluate
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+    /// Why request was denied before policies were evaluated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejected: Option<String>,
+}
+
+impl AuditRecord {
+    #[must_use]
+    pub fn now_ms() -> u64 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |t| t.as_millis().try_into().unwrap_or(u64::MAX))
+    }
+}
+
+#[derive(Debug)]
+struct AuditFile {
+    path: PathBuf,
+    max_size: u64,
+    keep: usize,
+    file: File,
+}
+
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
+fn open_append(path: &Path) -> anyhow::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(0o600)
+        .open(path)
+        .with_context(|| format!("opening audit log {}", path.display()))
+}
+
+impl AuditFile {
+    /// Append `record`, rotate file if it grew too big. Record reaches disk
+    /// only with `sync()`, or rotation
+    fn append(&mut self, record: &AuditRecord) -> anyhow::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        if self.file.metadata()?.len() >= self.max_size {
+            self.sync()?;
+            self.rotate()?;
+            self.file = open_append(&self.path)?;
+        }
+        Ok(())
+    }
+
+    fn sync(&self) -> anyhow::Result<()> {
+        self.file
+            .sync_data()
+            .with_context(|| format!("syncing audit log {}", self.path.display()))
+    }
+
+    fn handle(&mut self, message: Message) {
+        match message {
+            Message::Append(record) => {
+                if let Err(e) = self.append(&record) {
+                    error!("Failed to write audit record: {e:#}");
+                }
+            }
+            #[cfg(test)]
+            Message::Flush(done) => {
+                let _ = self.sync();
+                let _ = done.send(());
+            }
+            #[cfg(test)]
+            Message::Pause { paused, resume } => {
+                let _ = paused.send(());
+                let _ = resume.recv();
+            }
+        }
+    }
+
+    fn rotate(&self) -> anyhow::Result<()> {
+        if self.keep == 0 {
+            return Ok(std::fs::remove_file(&self.path)?);
+        }
+        let _ = std::fs::remove_file(rotated(&self.path, self.keep));
+        for n in (1..self.keep).rev() {
+            let from = rotated(&self.path, n);
+            if from.exists() {
+                std::fs::rename(&from, rotated(&self.path, n + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, rotated(&self.path, 1))
+            .with_context(|| format!("rotating audit log {}", self.path.display()))
+    }
+}
+
+#[derive(Debug)]
+enum Message {
+    Append(Box<AuditRecord>),
+    #[cfg(test)]
+    Flush(mpsc::Sender<()>),
+    #[cfg(test)]
+    Pause {
+        paused: mpsc::Sender<()>,
+        resume: mpsc::Receiver<()>,
+    },
+}
+
+/// Audit log, written by background thread, so access control decisions
+/// never wait for disk. Records are written in order they were queued, and
+/// synced to disk once queue is drained, not one by one.
+///
+/// Queue is bounded by `AUDIT_QUEUE_SIZE`. When writer falls behind and queue
+/// is full, new records are refused, and access control fails closed: request
+/// is denied rather than allowed without trace.
+#[derive(Debug)]
+pub struct AuditLog {
+    tx: mpsc::SyncSender<Message>,
+}
+
+impl AuditLog {
+    /// Open (or create) audit log at `path`, rotated after `max_size` bytes,
+    /// keeping `keep` rotated files, and start its writer thread
+    ///
+    /// # Errors
+    /// Returns error if file can't be opened, or thread can't be spawned
+    pub fn open(path: PathBuf, max_size: u64, keep: usize) -> anyhow::Result<Self> {
+        let mut file = AuditFile {
+            file: open_append(&path)?,
+            path,
+            max_size,
+            keep,
+        };
+        let (tx, rx) = mpsc::sync_channel(AUDIT_QUEUE_SIZE);
+        std::thread::Builder::new()
+            .name("audit-log".into())
+            .spawn(move || {
+                while let Ok(message) = rx.recv() {
+                    file.handle(message);
+                    while let Ok(message) = rx.try_recv() {
+                        file.handle(message);
+                    }
+                    if let Err(e) = file.sync() {
+                        error!("Failed to write audit records: {e:#}");
+                    }
+                }
+            })
+            .context("spawning audit log writer")?;
+        Ok(Self { tx })
+    }
+
+    /// Queue `record` for writing, without waiting
+    ///
+    /// # Errors
+    /// Returns error if queue is full, or writer is gone; record is dropped then
+    pub fn append(&self, record: AuditRecord) -> anyhow::Result<()> {
+        let result = match self.tx.try_send(Message::Append(Box::new(record))) {
+            Ok(()) => Ok(()),
+            Err(mpsc::TrySendError::Full(_)) => {
+                Err(anyhow::anyhow!("Audit log queue is full, record dropped"))
+            }
+            Err(mpsc::TrySendError::Disconnected(_)) => {
+                Err(anyhow::anyhow!("Audit log writer is gone, record dropped"))
+            }
+        };
+        result.inspect_err(|e| error!("{e}"))
+    }
+
+    /// Wait until all queued records are written
+    #[cfg(test)]
+    pub(crate) fn flush(&self) {
+        let (done, wait) = mpsc::channel();
+        if self.tx.send(Message::Flush(done)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
+
+    /// Stop writer until returned sender is dropped
+    #[cfg(test)]
+    fn pause(&self) -> mpsc::Sender<()> {
+        let (paused, wait) = mpsc::channel();
+        let (resume, resumed) = mpsc::channel();
+        let _ = self.tx.send(Message::Pause {
+            paused,
+            resume: resumed,
+        });
+        let _ = wait.recv();
+        resume
+    }
+}
+
+/// Read records from `path` and its rotated files, oldest first.
+/// Malformed lines are skipped.
+///
+/// # Errors
+/// Returns error if neither log nor its rotated files can be read
+pub fn read_audit_log(path: &Path) -> anyhow::Result<Vec<AuditRecord>> {
+    let mut files: Vec<PathBuf> = (1..)
+        .map(|n| rotated(path, n))
+        .take_while(|p| p.exists())
+        .collect();
+    files.reverse();
+    if path.exists() || files.is_empty() {
+        files.push(path.to_owned());
+    }
+    let mut records = Vec::new();
+    for file in files {
+        let reader = BufReader::new(
+            File::open(&file).with_context(|| format!("reading audit log {}", file.display()))?,
+        );
+        for (n, line) in reader.lines().enumerate() {
+            match serde_json::from_str(&line?) {
+                Ok(record) => records.push(record),
+                Err(e) => warn!(
+                    "{}:{}: skipping malformed record: {e}",
+                    file.display(),
+                    n + 1
+                ),
+            }
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn record(principal: &str, decision: AuditDecision) -> AuditRecord {
+        AuditRecord {
+            timestamp_ms: AuditRecord::now_ms(),
+            principal: principal.to_owned(),
+            common_name: None,
+            organizational_units: Vec::new(),
+            method: "admin.AdminService/Poweroff".to_owned(),
+            context: serde_json::json!({}),
+            decision,
+            policies: vec!["policy0".to_owned()],
+            errors: Vec::new(),
+            rejected: None,
+        }
+    }
+
+    #[test]
+    fn test_redact() {
+        let mut context = serde_json::json!({
+            "service": "AdminService",
+            "Cachix": {"Url": "https://cache", "Token": "secret"},
+            "Oci": {"Username": "user", "Password": "secret", "Token": "secret"},
+            "Items": [{"token": "secret"}],
+        });
+        redact(&mut context);
+        assert_eq!(
+            context,
+            serde_json::json!({
+                "service": "AdminService",
+                "Cachix": {"Url": "https://cache", "Token": REDACTED},
+                "Oci": {"Username": "user", "Password": REDACTED, "Token": REDACTED},
+                "Items": [{"token": REDACTED}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_append_and_rotate() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("givc-audit-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("audit.jsonl");
+
+        // Each record is 150..300 bytes, so every second one triggers rotation
+        let log = AuditLog::open(path.clone(), 300, 2)?;
+        let records: Vec<_> = (0..7)
+            .map(|n| record(&format!("vm-{n}"), AuditDecision::Allow))
+            .collect();
+        for r in &records {
+            log.append(r.clone())?;
+        }
+        log.flush();
+        assert!(rotated(&path, 2).exists());
+        assert!(!rotated(&path, 3).exists());
+        let mode = std::fs::metadata(&path)?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // Oldest ones dropped, rest are in order
+        assert_eq!(read_audit_log(&path)?, records[2..]);
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_full_queue() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("givc-audit-full-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("audit.jsonl");
+
+        // Writer stuck, queue fills up and further records are refused
+        let log = AuditLog::open(path.clone(), 1 << 20, 1)?;
+        let resume = log.pause();
+        for n in 0..AUDIT_QUEUE_SIZE {
+            log.append(record(&format!("vm-{n}"), AuditDecision::Allow))?;
+        }
+        assert!(
+            log.append(record("overflow", AuditDecision::Allow))
+                .is_err()
+        );
+
+        // Queued ones are written once writer resumes, and queue accepts again
+        drop(resume);
+        log.flush();
+        log.append(record("after", AuditDecision::Deny))?;
+        log.flush();
+        let records = read_audit_log(&path)?;
+        assert_eq!(records.len(), AUDIT_QUEUE_SIZE + 1);
+        assert!(records.iter().all(|r| r.principal != "overflow"));
+        assert_eq!(records.last().map(|r| r.principal.as_str()), Some("after"));
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
 // SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use super::revocation::Revocation;
 use super::x509::SecurityInfo;
+use std::sync::Arc;
 use tonic::transport::server::{Connected, TlsConnectInfo};
 use tonic::{Request, Status};
 use tracing::debug;
@@ -34,6 +36,8 @@ fn transport_info_from_request<T>(req: &HttpRequest<T>) -> Option<&ListenerConne
 #[derive(Clone)]
 pub struct Authenticator {
     pub use_tls: bool,
+    /// Revoked certificates, rejected on any transport
+    pub revocation: Option<Arc<Revocation>>,
 }
 
 /// Authentication interceptor that verifies the peer's identity.
@@ -41,11 +45,15 @@ pub struct Authenticator {
 /// **TCP**: Verifies peer IP matches an IP in their certificate's SAN.
 /// **Vsock/Unix/Other**: Certificate validity only (TLS handshake). No IP check -
 /// security relies on hypervisor isolation (vsock) or filesystem permissions (unix).
+/// On all transports, revoked certificates are rejected if revocation data is configured.
 #[tonic::async_trait]
 impl RequestInterceptor for Authenticator {
     async fn intercept(&self, mut req: HttpRequest<Body>) -> Result<HttpRequest<Body>, Status> {
         if self.use_tls {
             let security_info = security_info_from_request(&req)?;
+            if let Some(revocation) = &self.revocation {
+                revocation.check(&security_info)?;
+            }
 
             match transport_info_from_request(&req) {
                 Some(ListenerConnectInfo::Tcp(tcp_info)) => {
@@ -0,0 +1,310 @@
+// SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Small local CA, issuing per-VM certificates in layout read by `givc-admin` and
+// `givc-agent` (`CA_CERT`, `HOST_CERT`, `HOST_KEY`):
+//   <ca-dir>/ca-cert.pem, <ca-dir>/ca-key.pem
+//   <out-dir>/<name>/ca-cert.pem, <out-dir>/<name>/cert.pem, <out-dir>/<name>/key.pem
+use std::io::Write;
+use std::net::IpAddr;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Context;
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DistinguishedName, DnType,
+    ExtendedKeyUsagePurpose, IsCa, KeyPair, KeyUsagePurpose, SanType,
+};
+use time::OffsetDateTime;
+use x509_parser::parse_x509_certificate;
+use x509_parser::pem::parse_x509_pem;
+
+use super::x509::SecurityInfo;
+
+pub const CA_CERT: &str = "ca-cert.pem";
+pub const CA_KEY: &str = "ca-key.pem";
+pub const HOST_CERT: &str = "cert.pem";
+pub const HOST_KEY: &str = "key.pem";
+
+// Tolerate clock skew between VMs
+const BACKDATE: Duration = Duration::from_secs(60 * 60);
+
+fn validity(params: &mut CertificateParams, days: u32) {
+    let now = OffsetDateTime::now_utc();
+    params.not_before = now - BACKDATE;
+    params.not_after = now + Duration::from_secs(u64::from(days) * 24 * 60 * 60);
+}
+
+fn common_name(name: &str) -> DistinguishedName {
+    let mut dn = DistinguishedName::new();
+    dn.push(DnType::CommonName, name);
+    dn
+}
+
+/// Write `contents` to `path` atomically, readable only by owner if `private`
+fn write_file(path: &Path, contents: &str, private: bool) -> anyhow::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let mode = if private { 0o600 } else { 0o644 };
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(mode)
+        .open(&tmp)
+        .with_context(|| format!("writing {}", path.display()))?;
+    // Mode is applied only to new file, leftover from interrupted write keeps its own
+    file.set_permissions(std::fs::Permissions::from_mode(mode))?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path).with_context(|| format!("writing {}", path.display()))
+}
+
+/// Names, which peer will be verified against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostIdentity {
+    /// Used as CN and first DNS SAN, so it becomes `SecurityInfo::hostname`
+    pub name: String,
+    pub dns_names: Vec<String>,
+    pub ip_addrs: Vec<IpAddr>,
+}
+
+impl From<&SecurityInfo> for HostIdentity {
+    fn from(security: &SecurityInfo) -> Self {
+        let dns_names = security.dns_names();
+        Self {
+            name: security.hostname().unwrap_or_default().to_owned(),
+            dns_names: dns_names.iter().skip(1).cloned().collect(),
+            ip_addrs: security.ip_addrs().to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IssuedCert {
+    pub cert_pem: String,
+    pub key_pem: String,
+}
+
+pub struct CertificateAuthority {
+    cert: Certificate,
+    key: KeyPair,
+    // Original certificate, `cert` is re-created from it on load
+    cert_pem: String,
+}
+
+impl CertificateAuthority {
+    /// Create new self-signed CA
+    ///
+    /// # Errors
+    /// Returns error if key generation or signing fails
+    pub fn create(name: &str, days: u32) -> anyhow::Result<Self> {
+        let mut params = CertificateParams::default();
+        params.distinguished_name = common_name(name);
+        params.is_ca = IsCa::Ca(BasicConstraints::Constrained(1));
+        params.key_usages = vec![
+            KeyUsagePurpose::DigitalSignature,
+            KeyUsagePurpose::KeyCertSign,
+            KeyUsagePurpose::CrlSign,
+        ];
+        validity(&mut params, days);
+        let key = KeyPair::generate()?;
+        let cert = params.self_signed(&key)?;
+        Ok(Self {
+            cert_pem: cert.pem(),
+            cert,
+            key,
+        })
+    }
+
+    /// Load CA from `dir`
+    ///
+    /// # Errors
+    /// Returns error if CA files can't be read or parsed
+    pub fn load(dir: &Path) -> anyhow::Result<Self> {
+        let read = |name| {
+            let path = dir.join(name);
+            std::fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))
+        };
+        let cert_pem = read(CA_CERT)?;
+        let key = KeyPair::from_pem(&read(CA_KEY)?).context("parsing CA key")?;
+        let params = CertificateParams::from_ca_cert_pem(&cert_pem).context("parsing CA cert")?;
+        let cert = params.self_signed(&key)?;
+        Ok(Self {
+            cert,
+            key,
+            cert_pem,
+        })
+    }
+
+    /// Save CA to `dir`, refusing to overwrite existing one
+    ///
+    /// # Errors
+    /// Returns error if CA already exists in `dir`, or files can't be written
+    pub fn save(&self, dir: &Path) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !dir.join(CA_KEY).exists(),
+            "CA already exists in {}",
+            dir.display()
+        );
+        std::fs::create_dir_all(dir)?;
+        write_file(&dir.join(CA_KEY), &self.key.serialize_pem(), true)?;
+        write_file(&dir.join(CA_CERT), &self.cert_pem, false)
+    }
+
+    #[must_use]
+    pub fn cert_pem(&self) -> &str {
+        &self.cert_pem
+    }
+
+    /// Issue certificate for `host`, with fresh key, usable both as TLS client and server
+    ///
+    /// # Errors
+    /// Returns error if names are invalid, or signing fails
+    pub fn issue(&self, host: &HostIdentity, days: u32) -> anyhow::Result<IssuedCert> {
+        anyhow::ensure!(!host.name.is_empty(), "host name is empty");
+        let mut params = CertificateParams::default();
+        params.distinguished_name = common_name(&host.name);
+        params.subject_alt_names = std::iter::once(&host.name)
+            .chain(&host.dns_names)
+            .map(|dns| Ok(SanType::DnsName(dns.clone().try_into()?)))
+            .chain(host.ip_addrs.iter().map(|ip| Ok(SanType::IpAddress(*ip))))
+            .collect::<anyhow::Result<_>>()
+            .with_context(|| format!("invalid names of {}", host.name))?;
+        params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+        params.extended_key_usages = vec![
+            ExtendedKeyUsagePurpose::ServerAuth,
+            ExtendedKeyUsagePurpose::ClientAuth,
+        ];
+        params.use_authority_key_identifier_extension = true;
+        validity(&mut params, days);
+        let key = KeyPair::generate()?;
+        let cert = params.signed_by(&key, &self.cert, &self.key)?;
+        Ok(IssuedCert {
+            cert_pem: cert.pem(),
+            key_pem: key.serialize_pem(),
+        })
+    }
+
+    /// Write `issued` certificate with key and CA certificate into `dir`
+    ///
+    /// # Errors
+    /// Returns error if files can't be written
+    pub fn install(&self, issued: &IssuedCert, dir: &Path) -> anyhow::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        write_file(&dir.join(CA_CERT), &self.cert_pem, false)?;
+        write_file(&dir.join(HOST_KEY), &issued.key_pem, true)?;
+        write_file(&dir.join(HOST_CERT), &issued.cert_pem, false)
+    }
+}
+
+/// Identity and remaining validity of PEM certificate, zero if already expired
+///
+/// # Errors
+/// Returns error if certificate can't be parsed
+pub fn inspect(cert_pem: &[u8]) -> anyhow::Result<(SecurityInfo, Duration)> {
+    let (_, pem) = parse_x509_pem(cert_pem).map_err(|e| anyhow::anyhow!("invalid PEM: {e}"))?;
+    let (_, x509) =
+        parse_x509_certificate(&pem.contents).map_err(|e| anyhow::anyhow!("invalid cert: {e}"))?;
+    let remaining = x509
+        .validity()
+        .time_to_expiration()
+        .and_then(|left| left.try_into().ok())
+        .unwrap_or_default();
+    Ok((SecurityInfo::try_from(pem.contents.as_slice())?, remaining))
+}
+
+/// Re-issue certificate in `dir`, keeping its names, if it expires within `before`
+/// (or `force` is set). Returns whether certificate was renewed.
+///
+/// # Errors
+/// Returns error if certificate can't be read, or new one can't be issued
+pub fn renew(
+    ca: &CertificateAuthority,
+    dir: &Path,
+    before: Duration,
+    days: u32,
+    force: bool,
+) -> anyhow::Result<bool> {
+    let path = dir.join(HOST_CERT);
+    let pem = std::fs::read(&path).with_context(|| format!("reading {}", path.display()))?;
+    let (security, remaining) = inspect(&pem).with_context(|| format!("{}", path.display()))?;
+    if !force && remaining > before {
+        return Ok(false);
+    }
+    let issued = ca.issue(&HostIdentity::from(&security), days)?;
+    ca.install(&issued, dir)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_and_renew() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("givc-ca-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let ca_dir = dir.join("ca");
+        CertificateAuthority::create("GIVC test CA", 365)?.save(&ca_dir)?;
+        assert!(
+            CertificateAuthority::create("again", 365)?
+                .save(&ca_dir)
+                .is_err()
+        );
+        let ca = CertificateAuthority::load(&ca_dir)?;
+
+        let host = HostIdentity {
+            name: "chrome-vm".into(),
+            dns_names: vec!["chrome-vm.ghaf".into()],
+            ip_addrs: vec!["192.168.100.5".parse()?],
+        };
+        let host_dir = dir.join("chrome-vm");
+        ca.install(&ca.issue(&host, 10)?, &host_dir)?;
+        assert_eq!(
+            std::fs::read_to_string(host_dir.join(CA_CERT))?,
+            ca.cert_pem()
+        );
+
+        let (security, remaining) = inspect(&std::fs::read(host_dir.join(HOST_CERT))?)?;
+        assert_eq!(security.hostname(), Some("chrome-vm"));
+        assert!(security.check_hostname("chrome-vm.ghaf"));
+        assert!(security.check_address(&"192.168.100.5".parse()?));
+        assert!(!security.check_address(&"192.168.100.6".parse()?));
+        assert!(remaining > Duration::from_secs(9 * 24 * 60 * 60));
+
+        let week = Duration::from_secs(7 * 24 * 60 * 60);
+        assert!(!renew(&ca, &host_dir, week, 365, false)?);
+        assert!(renew(
+            &ca,
+            &host_dir,
+            Duration::from_secs(30 * 24 * 60 * 60),
+            365,
+            false
+        )?);
+        let (renewed, remaining) = inspect(&std::fs::read(host_dir.join(HOST_CERT))?)?;
+        assert_eq!(HostIdentity::from(&renewed), host);
+        assert!(remaining > Duration::from_secs(300 * 24 * 60 * 60));
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+    #[test]
+    fn test_write_file_private() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("givc-ca-key-{}.pem", std::process::id()));
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        // Leftover of interrupted write, readable by everyone
+        std::fs::write(&tmp, "stale")?;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o644))?;
+
+        write_file(&path, "secret", true)?;
+        assert_eq!(std::fs::read_to_string(&path)?, "secret");
+        let mode = std::fs::metadata(&path)?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
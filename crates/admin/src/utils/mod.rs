@@ -2,8 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod access_control;
+pub mod acl_schema;
+pub mod audit;
 pub mod auth;
+pub mod ca;
 pub mod naming;
+pub mod revocation;
 pub mod tonic;
 pub mod vsock;
 pub mod x509;
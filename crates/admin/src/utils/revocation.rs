@@ -0,0 +1,387 @@
+// SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Revoked peer certificates: serials from CRL (PEM or DER), plus local denylist of
+// serials and SHA-256 fingerprints. Denylist is text file, one entry per line:
+//   serial 01:a3:5f
+//   sha256 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08
+// Empty lines and `#` comments are ignored. CRL is accepted only if issued and
+// signed by CA certificate, which peers are verified against.
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use anyhow::Context;
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::task::JoinHandle;
+use tonic::Status;
+use tracing::{error, info, warn};
+use x509_parser::prelude::*;
+
+use super::access_control::fingerprint;
+use super::x509::SecurityInfo;
+
+const REVOCATION_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Leading zeroes are insignificant, but DER may keep one to make value positive
+fn normalize_serial(serial: &[u8]) -> Vec<u8> {
+    let start = serial.iter().position(|b| *b != 0).unwrap_or(serial.len());
+    serial[start..].to_vec()
+}
+
+fn parse_hex(text: &str) -> anyhow::Result<Vec<u8>> {
+    let digits: String = text.chars().filter(|c| *c != ':').collect();
+    anyhow::ensure!(
+        digits.bytes().all(|b| b.is_ascii_hexdigit()),
+        "invalid hex string {text}"
+    );
+    anyhow::ensure!(
+        !digits.is_empty() && digits.len().is_multiple_of(2),
+        "odd or empty hex string {text}"
+    );
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .with_context(|| format!("invalid hex string {text}"))
+        })
+        .collect()
+}
+
+fn verify_issuer(crl: &CertificateRevocationList, ca: &[u8]) -> anyhow::Result<()> {
+    for pem in Pem::iter_from_buffer(ca) {
+        let pem = pem.context("invalid CA certificate PEM")?;
+        let cert = pem
+            .parse_x509()
+            .map_err(|e| anyhow::anyhow!("invalid CA certificate: {e}"))?;
+        if cert.subject() == crl.issuer() {
+            return crl
+                .verify_signature(cert.public_key())
+                .map_err(|e| anyhow::anyhow!("CRL signature of {} is invalid: {e}", crl.issuer()));
+        }
+    }
+    anyhow::bail!("CRL issuer {} is not configured CA", crl.issuer())
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RevokedSet {
+    serials: HashSet<Vec<u8>>,
+    fingerprints: HashSet<[u8; 32]>,
+}
+
+impl RevokedSet {
+    /// Add serials revoked by CRL in `data`, which must be issued by one of PEM
+    /// encoded certificates in `ca`
+    ///
+    /// # Errors
+    /// Returns error if `data` is neither PEM nor DER encoded CRL, or it isn't
+    /// signed by its issuer from `ca`
+    pub fn add_crl(&mut self, data: &[u8], ca: &[u8]) -> anyhow::Result<()> {
+        let der = if data.starts_with(b"-----BEGIN") {
+            let (_, pem) = x509_parser::pem::parse_x509_pem(data)
+                .map_err(|e| anyhow::anyhow!("invalid PEM: {e}"))?;
+            pem.contents
+        } else {
+            data.to_vec()
+        };
+        let (_, crl) = parse_x509_crl(&der).map_err(|e| anyhow::anyhow!("invalid CRL: {e}"))?;
+        verify_issuer(&crl, ca)?;
+        if crl.next_update().is_some_and(|next| next < ASN1Time::now()) {
+            warn!("CRL issued by {} is outdated", crl.issuer());
+        }
+        self.serials.extend(
+            crl.iter_revoked_certificates()
+                .map(|revoked| normalize_serial(revoked.raw_serial())),
+        );
+        Ok(())
+    }
+
+    /// Add entries from denylist `text`
+    ///
+    /// # Errors
+    /// Returns error on malformed line
+    pub fn add_denylist(&mut self, text: &str) -> anyhow::Result<()> {
+        for (n, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let entry = match line.split_once(char::is_whitespace) {
+                Some(("serial", hex)) => parse_hex(hex.trim()).map(|serial| {
+                    self.serials.insert(normalize_serial(&serial));
+                }),
+                Some(("sha256", hex)) => parse_hex(hex.trim()).and_then(|digest| {
+                    let digest = <[u8; 32]>::try_from(digest.as_slice())
+                        .context("SHA-256 fingerprint must be 32 bytes")?;
+                    self.fingerprints.insert(digest);
+                    Ok(())
+                }),
+                _ => Err(anyhow::anyhow!("expected `serial <hex>` or `sha256 <hex>`")),
+            };
+            entry.with_context(|| format!("denylist line {}", n + 1))?;
+        }
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn is_revoked(&self, security: &SecurityInfo) -> bool {
+        self.serials.contains(&normalize_serial(security.serial()))
+            || self.fingerprints.contains(security.fingerprint())
+    }
+}
+
+/// Revocation data, reloaded from files on change
+#[derive(Debug)]
+pub struct Revocation {
+    crl_file: Option<PathBuf>,
+    ca_file: Option<PathBuf>,
+    denylist_file: Option<PathBuf>,
+    revoked: RwLock<RevokedSet>,
+}
+
+impl Revocation {
+    /// Load CRL and denylist, any of them is optional. CRL is verified against
+    /// CA certificate from `ca_file`
+    ///
+    /// # Errors
+    /// Returns error if files can't be read or parsed, or CRL is given without CA
+    pub fn load(
+        crl_file: Option<PathBuf>,
+        denylist_file: Option<PathBuf>,
+        ca_file: Option<PathBuf>,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            crl_file.is_none() || ca_file.is_some(),
+            "CRL requires CA certificate to verify it"
+        );
+        let this = Self {
+            crl_file,
+            ca_file,
+            denylist_file,
+            revoked: RwLock::default(),
+        };
+        this.reload()?;
+        Ok(this)
+    }
+
+    fn read(&self) -> anyhow::Result<RevokedSet> {
+        let mut revoked = RevokedSet::default();
+        if let (Some(path), Some(ca_path)) = (&self.crl_file, &self.ca_file) {
+            let data =
+                std::fs::read(path).with_context(|| format!("reading CRL {}", path.display()))?;
+            let ca = std::fs::read(ca_path)
+                .with_context(|| format!("reading CA certificate {}", ca_path.display()))?;
+            revoked
+                .add_crl(&data, &ca)
+                .with_context(|| format!("parsing CRL {}", path.display()))?;
+        }
+        if let Some(path) = &self.denylist_file {
+            let text = std::fs::read_to_string(path)
+                .with_context(|| format!("reading denylist {}", path.display()))?;
+            revoked
+                .add_denylist(&text)
+                .with_context(|| format!("parsing denylist {}", path.display()))?;
+        }
+        Ok(revoked)
+    }
+
+    /// Re-read files
+    ///
+    /// # Errors
+    /// Returns error if files can't be read or parsed, previous data stays active then
+    pub fn reload(&self) -> anyhow::Result<()> {
+        let revoked = self.read()?;
+        *self.revoked.write().unwrap() = revoked;
+        Ok(())
+    }
+
+    /// Reject revoked peer
+    ///
+    /// # Errors
+    /// Returns `Err(Status::unauthenticated)` if peer certificate is revoked
+    pub fn check(&self, security: &SecurityInfo) -> Result<(), Status> {
+        if self.revoked.read().unwrap().is_revoked(security) {
+            warn!(
+                "Rejected revoked certificate of {}",
+                security.hostname().unwrap_or("<unknown>")
+            );
+            return Err(Status::unauthenticated("Certificate revoked"));
+        }
+        Ok(())
+    }
+
+    fn fingerprints(&self) -> Vec<Option<(std::time::SystemTime, u64)>> {
+        [&self.crl_file, &self.ca_file, &self.denylist_file]
+            .into_iter()
+            .flatten()
+            .map(|path| fingerprint(path))
+            .collect()
+    }
+
+    /// Reload when files change on disk, or SIGHUP is received
+    ///
+    /// # Errors
+    /// Returns error if SIGHUP handler can't be installed
+    pub fn watch(self: Arc<Self>) -> anyhow::Result<JoinHandle<()>> {
+        let mut hangup =
+            signal(SignalKind::hangup()).context("Failed to install SIGHUP handler")?;
+        Ok(tokio::spawn(async move {
+            let mut seen = self.fingerprints();
+            let mut poll = tokio::time::interval(REVOCATION_POLL_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = hangup.recv() => (),
+                    _ = poll.tick() => {
+                        let current = self.fingerprints();
+                        if current == seen {
+                            continue;
+                        }
+                        seen = current;
+                    }
+                }
+                match self.reload() {
+                    Ok(()) => info!("Certificate revocation data reloaded"),
+                    Err(e) => error!("Keeping previous revocation data, reload failed: {e:#}"),
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rcgen::{
+        BasicConstraints, CertificateParams, CertificateRevocationListParams, IsCa, KeyIdMethod,
+        KeyPair, KeyUsagePurpose, RevocationReason, RevokedCertParams, SerialNumber, date_time_ymd,
+    };
+
+    use super::*;
+
+    struct Ca {
+        cert: rcgen::Certificate,
+        key: KeyPair,
+    }
+
+    impl Ca {
+        fn new() -> anyhow::Result<Self> {
+            let mut params = CertificateParams::new(Vec::<String>::new())?;
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+            let key = KeyPair::generate()?;
+            let cert = params.self_signed(&key)?;
+            Ok(Self { cert, key })
+        }
+
+        fn issue(&self, hostname: &str, serial: u64) -> anyhow::Result<SecurityInfo> {
+            let mut params = CertificateParams::new(vec![hostname.to_owned()])?;
+            params.serial_number = Some(SerialNumber::from(serial));
+            let cert = params.signed_by(&KeyPair::generate()?, &self.cert, &self.key)?;
+            Ok(SecurityInfo::try_from(cert.der().as_ref())?)
+        }
+
+        fn temp_pem(&self, name: &str) -> anyhow::Result<PathBuf> {
+            temp_file(name, self.cert.pem().as_bytes())
+        }
+
+        fn crl(&self, serials: &[u64]) -> anyhow::Result<String> {
+            let params = CertificateRevocationListParams {
+                this_update: date_time_ymd(2025, 1, 1),
+                next_update: date_time_ymd(2100, 1, 1),
+                crl_number: SerialNumber::from(1u64),
+                issuing_distribution_point: None,
+                revoked_certs: serials
+                    .iter()
+                    .map(|serial| RevokedCertParams {
+                        serial_number: SerialNumber::from(*serial),
+                        revocation_time: date_time_ymd(2025, 1, 1),
+                        reason_code: Some(RevocationReason::Superseded),
+                        invalidity_date: None,
+                    })
+                    .collect(),
+                key_identifier_method: KeyIdMethod::Sha256,
+            };
+            Ok(params.signed_by(&self.cert, &self.key)?.pem()?)
+        }
+    }
+
+    fn temp_file(name: &str, data: &[u8]) -> anyhow::Result<PathBuf> {
+        let path =
+            std::env::temp_dir().join(format!("givc-revocation-{}-{name}", std::process::id()));
+        std::fs::write(&path, data)?;
+        Ok(path)
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn test_crl() -> anyhow::Result<()> {
+        let ca = Ca::new()?;
+        let chrome = ca.issue("chrome-vm", 1001)?;
+        let gui = ca.issue("gui-vm", 1002)?;
+        let crl = temp_file("crl.pem", ca.crl(&[1001])?.as_bytes())?;
+        let ca_file = ca.temp_pem("ca.pem")?;
+
+        assert!(Revocation::load(Some(crl.clone()), None, None).is_err());
+        let revocation = Revocation::load(Some(crl.clone()), None, Some(ca_file.clone()))?;
+        assert!(revocation.check(&chrome).is_err());
+        assert!(revocation.check(&gui).is_ok());
+
+        // Broken file doesn't lift revocation
+        std::fs::write(&crl, "garbage")?;
+        assert!(revocation.reload().is_err());
+        assert!(revocation.check(&chrome).is_err());
+
+        std::fs::write(&crl, ca.crl(&[1002])?)?;
+        revocation.reload()?;
+        assert!(revocation.check(&chrome).is_ok());
+        assert!(revocation.check(&gui).is_err());
+
+        // CRL of other CA, or forged with its issuer name, is refused
+        let other = Ca::new()?;
+        std::fs::write(&crl, other.crl(&[1001])?)?;
+        assert!(revocation.reload().is_err());
+        let forged = Ca {
+            cert: CertificateParams::from_ca_cert_der(ca.cert.der())?.self_signed(&other.key)?,
+            key: other.key,
+        };
+        std::fs::write(&crl, forged.crl(&[1001])?)?;
+        assert!(revocation.reload().is_err());
+        assert!(revocation.check(&chrome).is_ok());
+
+        std::fs::remove_file(&crl)?;
+        std::fs::remove_file(&ca_file)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_denylist() -> anyhow::Result<()> {
+        let ca = Ca::new()?;
+        let chrome = ca.issue("chrome-vm", 0x0102)?;
+        let gui = ca.issue("gui-vm", 0x0203)?;
+        let net = ca.issue("net-vm", 0x0304)?;
+        let denylist = temp_file(
+            "denylist",
+            format!(
+                "# decommissioned\nserial 00:01:02\nsha256 {}  # gui-vm\n",
+                hex(gui.fingerprint())
+            )
+            .as_bytes(),
+        )?;
+
+        let revocation = Revocation::load(None, Some(denylist.clone()), None)?;
+        assert!(revocation.check(&chrome).is_err());
+        assert!(revocation.check(&gui).is_err());
+        assert!(revocation.check(&net).is_ok());
+
+        std::fs::write(&denylist, "serial xyz\n")?;
+        assert!(Revocation::load(None, Some(denylist.clone()), None).is_err());
+        std::fs::write(&denylist, "serial €a\n")?;
+        assert!(Revocation::load(None, Some(denylist.clone()), None).is_err());
+
+        std::fs::remove_file(&denylist)?;
+        Ok(())
+    }
+}
@@ -3,6 +3,8 @@
 
 use std::convert::TryFrom;
 use std::net::IpAddr;
+
+use sha2::{Digest, Sha256};
 use x509_parser::prelude::*;
 
 #[derive(Clone, Debug)]
@@ -10,6 +12,10 @@ pub struct SecurityInfo {
     enabled: bool,
     dns_names: Vec<String>,
     ip_addrs: Vec<IpAddr>,
+    common_name: Option<String>,
+    organizational_units: Vec<String>,
+    serial: Vec<u8>,
+    fingerprint: [u8; 32],
 }
 
 impl SecurityInfo {
@@ -18,6 +24,10 @@ impl SecurityInfo {
             enabled: true,
             dns_names: Vec::new(),
             ip_addrs: Vec::new(),
+            common_name: None,
+            organizational_units: Vec::new(),
+            serial: Vec::new(),
+            fingerprint: [0; 32],
         }
     }
 
@@ -29,6 +39,16 @@ impl SecurityInfo {
         }
     }
 
+    /// Identity of peer with certificate issued for `hostname`, without one at hand
+    #[must_use]
+    pub fn with_hostname(hostname: &str) -> Self {
+        Self {
+            dns_names: vec![hostname.to_owned()],
+            common_name: Some(hostname.to_owned()),
+            ..Self::new()
+        }
+    }
+
     #[must_use]
     pub fn check_address(&self, ia: &IpAddr) -> bool {
         !self.enabled || self.ip_addrs.iter().any(|a| a == ia)
@@ -43,6 +63,40 @@ impl SecurityInfo {
     pub fn hostname(&self) -> Option<&str> {
         self.dns_names.first().map(String::as_str)
     }
+
+    #[must_use]
+    pub fn dns_names(&self) -> &[String] {
+        &self.dns_names
+    }
+
+    #[must_use]
+    pub fn ip_addrs(&self) -> &[IpAddr] {
+        &self.ip_addrs
+    }
+
+    /// Subject CN of peer certificate
+    #[must_use]
+    pub fn common_name(&self) -> Option<&str> {
+        self.common_name.as_deref()
+    }
+
+    /// Subject OUs of peer certificate
+    #[must_use]
+    pub fn organizational_units(&self) -> &[String] {
+        &self.organizational_units
+    }
+
+    /// Serial number of peer certificate, big-endian
+    #[must_use]
+    pub fn serial(&self) -> &[u8] {
+        &self.serial
+    }
+
+    /// SHA-256 digest of peer certificate DER
+    #[must_use]
+    pub fn fingerprint(&self) -> &[u8; 32] {
+        &self.fingerprint
+    }
 }
 
 impl TryFrom<&[u8]> for SecurityInfo {
@@ -50,6 +104,18 @@ impl TryFrom<&[u8]> for SecurityInfo {
     fn try_from(cert: &[u8]) -> Result<Self, Self::Error> {
         let mut this = Self::new();
         let (_, x509) = parse_x509_certificate(cert)?;
+        this.serial = x509.raw_serial().to_vec();
+        this.fingerprint = Sha256::digest(cert).into();
+        let subject = x509.subject();
+        this.common_name = subject
+            .iter_common_name()
+            .find_map(|cn| cn.as_str().ok())
+            .map(ToOwned::to_owned);
+        this.organizational_units = subject
+            .iter_organizational_unit()
+            .filter_map(|ou| ou.as_str().ok())
+            .map(ToOwned::to_owned)
+            .collect();
         for ext in x509.extensions() {
             if let ParsedExtension::SubjectAlternativeName(san) = ext.parsed_extension() {
                 for name in &san.general_names {
@@ -7,7 +7,7 @@ use gethostname::gethostname;
 use tokio::sync::mpsc;
 use tokio_stream::StreamExt;
 use tonic::transport::Channel;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use givc_common::address::EndpointAddress;
 use givc_common::pb;
@@ -500,6 +500,22 @@ impl AdminClient {
         Ok(response.into_inner())
     }
 
+    /// Delivery status of policies to VMs, or only to `vm_name`
+    /// # Errors
+    /// Fails if policy admin is disabled on admin server, or on network IO errors
+    pub async fn policy_status(
+        &self,
+        vm_name: Option<String>,
+    ) -> anyhow::Result<Vec<pb::admin::PolicyDeliveryStatus>> {
+        let response = self
+            .connect_to()
+            .await?
+            .policy_status(pb::admin::PolicyStatusRequest { vm_name })
+            .await
+            .rewrap_err()?;
+        Ok(response.into_inner().list)
+    }
+
     /// List installed generations (updates)
     /// # Errors
     /// Fails if remote execution of `ota-update` tool failed, or on network IO errors
@@ -514,16 +530,52 @@ impl AdminClient {
         Ok(gens.list)
     }
 
+    /// Roll back to previous generation (or image slot)
+    /// # Errors
+    /// Fails if remote execution of `ota-update` tool failed, or on network IO errors
+    pub async fn rollback_generation(
+        &self,
+    ) -> anyhow::Result<pb::admin::RollbackGenerationResponse> {
+        let response = self
+            .connect_to()
+            .await?
+            .rollback_generation(pb::admin::Empty {})
+            .await
+            .rewrap_err()?;
+        Ok(response.into_inner())
+    }
+
+    /// Remove old generations, keeping `keep` newest ones
+    /// # Errors
+    /// Fails if remote execution of `ota-update` tool failed, or on network IO errors
+    pub async fn collect_garbage(
+        &self,
+        keep: u32,
+    ) -> anyhow::Result<pb::admin::CollectGarbageResponse> {
+        let response = self
+            .connect_to()
+            .await?
+            .collect_garbage(pb::admin::CollectGarbageRequest { keep })
+            .await
+            .rewrap_err()?;
+        Ok(response.into_inner())
+    }
+
     /// Install choosed pinned release from cachix.
+    /// Update progress is passed to `progress` as it arrives.
     /// # Errors
     /// Fails if remote execution of `ota-update` tool failed, or on network IO errors
-    pub async fn set_generation_cachix(
+    pub async fn set_generation_cachix<F>(
         &self,
         pin: String,
         server: Option<String>,
         cache: String,
         token: Option<String>,
-    ) -> anyhow::Result<()> {
+        mut progress: F,
+    ) -> anyhow::Result<()>
+    where
+        F: FnMut(pb::admin::UpdateProgress),
+    {
         let cachix = pb::admin::Cachix {
             pin,
             cachix_host: server,
@@ -540,10 +592,51 @@ impl AdminClient {
             .await
             .rewrap_err()?;
         let stream = response.into_inner();
-        drain_stream_with_callback(stream, async move |next| {
+        drain_stream_with_callback(stream, async |next| {
+            if let Some(update) = next.progress {
+                progress(update);
+            }
+            if let Some(out) = next.output {
+                info!("set_generation: {out}");
+            }
+            Ok(())
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Pull release from OCI registry, and optionally install it.
+    /// Registry progress is passed to `progress` as it arrives.
+    /// # Errors
+    /// Fails if remote execution of `ota-update` tool failed, or on network IO errors
+    pub async fn set_generation_oci<F>(
+        &self,
+        oci: pb::admin::Oci,
+        mut progress: F,
+    ) -> anyhow::Result<()>
+    where
+        F: FnMut(pb::admin::RegistryProgress),
+    {
+        let req = pb::admin::SetGenerationRequest {
+            update: Some(pb::set_generation_request::Update::Oci(oci)),
+        };
+        let response = self
+            .connect_to()
+            .await?
+            .set_generation(req)
+            .await
+            .rewrap_err()?;
+        let stream = response.into_inner();
+        drain_stream_with_callback(stream, async |next| {
+            if let Some(registry) = next.registry {
+                progress(registry);
+            }
             if let Some(out) = next.output {
                 info!("set_generation: {out}");
             }
+            if let Some(err) = next.error {
+                warn!("set_generation: {err}");
+            }
             Ok(())
         })
         .await?;
@@ -22,6 +22,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 ".admin.Generation",
                 "#[derive(Deserialize, Serialize)] #[serde(rename_all = \"camelCase\")]",
             )
+            .type_attribute(
+                ".admin.PolicyDeliveryStatus",
+                "#[derive(Deserialize, Serialize)] #[serde(rename_all = \"camelCase\")]",
+            )
             .compile_protos(&[format!("api/{pkg}/{pkg}.proto").as_str()], &["api"])?;
     }
 
@@ -3,6 +3,8 @@
 
 pub mod address;
 pub mod query;
+#[cfg(feature = "signature")]
+pub mod signature;
 pub mod types;
 
 pub mod pb {
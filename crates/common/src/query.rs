@@ -35,6 +35,34 @@ pub enum TrustLevel {
     NotSecure = 2,
 }
 
+/// Health check statistics, collected by admin monitor
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Liveness {
+    pub last_seen: Option<u64>, // Unix time, seconds
+    pub consecutive_failures: u32,
+    pub latency_ms: Option<u64>,
+}
+
+impl From<pb::Liveness> for Liveness {
+    fn from(item: pb::Liveness) -> Self {
+        Self {
+            last_seen: item.last_seen,
+            consecutive_failures: item.consecutive_failures,
+            latency_ms: item.latency_ms,
+        }
+    }
+}
+
+impl From<Liveness> for pb::Liveness {
+    fn from(val: Liveness) -> Self {
+        Self {
+            last_seen: val.last_seen,
+            consecutive_failures: val.consecutive_failures,
+            latency_ms: val.latency_ms,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[cfg_attr(feature = "glib", derive(glib::Boxed))]
 #[cfg_attr(feature = "glib", boxed_type(name = "GivcQueryResult"))]
@@ -47,6 +75,7 @@ pub struct QueryResult {
     pub service_type: ServiceType,
     pub vm_name: Option<String>,
     pub agent_name: Option<String>,
+    pub liveness: Option<Liveness>, // Only for monitored units
 }
 
 impl QueryResult {
@@ -73,6 +102,7 @@ impl TryFrom<pb::QueryListItem> for QueryResult {
                 .with_context(|| format!("While parsing service_type {}", item.service_type))?,
             agent_name: item.agent_name,
             vm_name: item.vm_name,
+            liveness: item.liveness.map(Liveness::from),
         })
     }
 }
@@ -88,6 +118,7 @@ impl From<QueryResult> for pb::QueryListItem {
             service_type: val.service_type.to_string(),
             agent_name: val.agent_name,
             vm_name: val.vm_name,
+            liveness: val.liveness.map(Into::into),
         }
     }
 }
@@ -97,6 +128,7 @@ pub enum Event {
     UnitStatusChanged(QueryResult), // When unit updated/added
     UnitRegistered(QueryResult),
     UnitShutdown(QueryResult),
+    UnitFailed(QueryResult), // Restart policy gave up on unit
 }
 
 impl Event {
@@ -125,6 +157,7 @@ impl TryFrom<pb::WatchItem> for Event {
                 Status::Added(value) => Event::UnitRegistered(QueryResult::try_from(value)?),
                 Status::Updated(value) => Event::UnitStatusChanged(QueryResult::try_from(value)?),
                 Status::Removed(value) => Event::UnitShutdown(QueryResult::try_from(value)?),
+                Status::Failed(value) => Event::UnitFailed(QueryResult::try_from(value)?),
             })
         } else {
             bail!("WatchItem missing")
@@ -138,6 +171,7 @@ impl From<Event> for pb::WatchItem {
             Event::UnitRegistered(value) => Event::watch_item(Status::Added(value.into())),
             Event::UnitStatusChanged(value) => Event::watch_item(Status::Updated(value.into())),
             Event::UnitShutdown(value) => Event::watch_item(Status::Removed(value.into())),
+            Event::UnitFailed(value) => Event::watch_item(Status::Failed(value.into())),
         }
     }
 }
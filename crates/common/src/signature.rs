@@ -0,0 +1,194 @@
+// SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Detached ed25519 signatures, shared by update manifests and policy artifacts.
+// Keys are in nix format `<name>:<base64>`, as generated by
+// `nix-store --generate-binary-cache-key`. Signatures are lines
+// `<key name>:<base64 signature>`, so artifact can be signed by several keys.
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{Context, ensure};
+use aws_lc_rs::signature::{ED25519, UnparsedPublicKey};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use tracing::warn;
+
+pub const PUBLIC_KEY_LEN: usize = 32;
+
+/// Split `<name>:<base64>`, format of both keys and signatures
+///
+/// # Errors
+/// Fails if name is missing or data isn't valid base64
+pub fn split_key(text: &str) -> anyhow::Result<(&str, Vec<u8>)> {
+    let (name, data) = text
+        .trim()
+        .split_once(':')
+        .context("expected `<name>:<base64>`")?;
+    ensure!(!name.is_empty(), "key name is empty");
+    let data = STANDARD.decode(data).context("invalid base64")?;
+    Ok((name, data))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey {
+    name: String,
+    key: Vec<u8>,
+}
+
+impl FromStr for PublicKey {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> anyhow::Result<Self> {
+        let (name, key) = split_key(text)?;
+        Self::new(name.to_owned(), key)
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.name, STANDARD.encode(&self.key))
+    }
+}
+
+impl PublicKey {
+    /// # Errors
+    /// Fails if key isn't `PUBLIC_KEY_LEN` bytes
+    pub fn new(name: String, key: Vec<u8>) -> anyhow::Result<Self> {
+        ensure!(
+            key.len() == PUBLIC_KEY_LEN,
+            "public key {name} must be {PUBLIC_KEY_LEN} bytes"
+        );
+        Ok(Self { name, key })
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        UnparsedPublicKey::new(&ED25519, &self.key)
+            .verify(message, signature)
+            .is_ok()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TrustedKeys(Vec<PublicKey>);
+
+impl FromIterator<PublicKey> for TrustedKeys {
+    fn from_iter<I: IntoIterator<Item = PublicKey>>(keys: I) -> Self {
+        Self(keys.into_iter().collect())
+    }
+}
+
+impl TrustedKeys {
+    /// Parse keys, one per line. Empty lines and `#` comments are ignored.
+    ///
+    /// # Errors
+    /// Fails on malformed key
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        text.lines()
+            .enumerate()
+            .map(|(n, line)| (n, line.split('#').next().unwrap_or_default().trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(n, line)| {
+                line.parse()
+                    .with_context(|| format!("key at line {}", n + 1))
+            })
+            .collect()
+    }
+
+    /// # Errors
+    /// Fails if file can't be read or contains malformed keys
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading trusted keys {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("parsing trusted keys {}", path.display()))
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Check that one of `signatures` lines is made over `message` by trusted key.
+    /// Signatures by other keys are skipped. Returns name of the key.
+    ///
+    /// # Errors
+    /// Fails if no signature was made by trusted key, or it doesn't match
+    pub fn verify(&self, message: &[u8], signatures: &str) -> anyhow::Result<&str> {
+        for line in signatures.lines().filter(|line| !line.trim().is_empty()) {
+            let (name, signature) = split_key(line).context("malformed signature")?;
+            let Some(key) = self.0.iter().find(|key| key.name == name) else {
+                warn!("Skipping signature by untrusted key {name}");
+                continue;
+            };
+            ensure!(
+                key.verify(message, &signature),
+                "signature by {name} doesn't match"
+            );
+            return Ok(&key.name);
+        }
+        anyhow::bail!("not signed by trusted key")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_lc_rs::signature::{Ed25519KeyPair, KeyPair};
+
+    fn key_pair(seed: u8) -> Ed25519KeyPair {
+        Ed25519KeyPair::from_seed_unchecked(&[seed; 32]).unwrap()
+    }
+
+    fn public_key(name: &str, pair: &Ed25519KeyPair) -> PublicKey {
+        PublicKey::new(name.to_owned(), pair.public_key().as_ref().to_vec()).unwrap()
+    }
+
+    fn sign(name: &str, pair: &Ed25519KeyPair, message: &[u8]) -> String {
+        format!("{name}:{}", STANDARD.encode(pair.sign(message).as_ref()))
+    }
+
+    #[test]
+    fn verify_signatures() -> anyhow::Result<()> {
+        let (release, other) = (key_pair(1), key_pair(2));
+        let key = public_key("release-1", &release);
+        let trusted = TrustedKeys::parse(&format!("# release keys\n{key}\n"))?;
+        assert_eq!(trusted, TrustedKeys::from_iter([key]));
+
+        let signature = sign("release-1", &release, b"message");
+        assert_eq!(trusted.verify(b"message", &signature)?, "release-1");
+
+        // Untrusted key is skipped, while trusted one is found
+        let both = format!("{}\n{signature}\n", sign("other-1", &other, b"message"));
+        assert_eq!(trusted.verify(b"message", &both)?, "release-1");
+        assert!(
+            trusted
+                .verify(b"message", &sign("other-1", &other, b"message"))
+                .is_err()
+        );
+
+        // Tampered message, or key name forged
+        assert!(trusted.verify(b"tampered", &signature).is_err());
+        assert!(
+            trusted
+                .verify(b"message", &sign("release-1", &other, b"message"))
+                .is_err()
+        );
+        assert!(trusted.verify(b"message", "").is_err());
+        assert!(TrustedKeys::default().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn reject_malformed_keys() {
+        assert!(PublicKey::from_str("no-colon").is_err());
+        assert!(PublicKey::from_str(":AAAA").is_err());
+        assert!(PublicKey::from_str("short:AAAA").is_err());
+        assert!(TrustedKeys::parse("good:not base64!").is_err());
+    }
+}
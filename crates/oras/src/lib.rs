@@ -0,0 +1,268 @@
+// SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// ORAS client: pushes and pulls artifacts of OCI registries, shared by OTA
+// updates and policy artifacts.
+pub mod client;
+#[cfg(any(test, feature = "test-registry"))]
+pub mod test_registry;
+pub mod types;
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use tokio::time::{Duration, timeout};
+
+pub use oci_client::annotations::ORG_OPENCONTAINERS_IMAGE_TITLE;
+pub use oci_client::client::ClientProtocol;
+pub use types::{TaggedReference, UntaggedReference};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistryCredentials {
+    Anonymous,
+    Basic { username: String, password: String },
+    Bearer { token: String },
+}
+
+/// Generic artifact, like policy, pulled with `pull_artifact()`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArtifactOptions {
+    pub reference: TaggedReference,
+    pub credentials: RegistryCredentials,
+    pub client_protocol: ClientProtocol,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PulledArtifact {
+    /// Manifest digest, which artifact was pulled by
+    pub digest: String,
+    /// Pulled files, named by titles of their layers
+    pub files: Vec<PathBuf>,
+}
+
+/// Digest of manifest, which tag (or digest) of `options.reference` points to
+///
+/// # Errors
+/// Fails on network errors, or if reference doesn't exist
+pub async fn resolve_artifact_digest(options: &ArtifactOptions) -> anyhow::Result<String> {
+    let client = client::build_client(options.client_protocol.clone());
+    timeout(
+        Duration::from_secs(30),
+        client::fetch_manifest_digest(&client, &options.reference, &options.credentials, None),
+    )
+    .await
+    .context("timeout while resolving artifact digest")?
+}
+
+/// Pull layers of artifact pinned to manifest `digest` into `destination` directory.
+/// Layers are named by their `org.opencontainers.image.title` annotation, untitled
+/// ones are skipped. Every blob is verified against its digest before it gets its
+/// name, so `destination` never holds unverified files.
+///
+/// # Errors
+/// Fails on network or IO errors, on digest mismatch, or if layer title isn't
+/// plain file name
+pub async fn pull_artifact(
+    options: &ArtifactOptions,
+    digest: &str,
+    destination: &Path,
+) -> anyhow::Result<PulledArtifact> {
+    let pinned: TaggedReference = format!("{}@{digest}", options.reference.repository_path())
+        .parse()
+        .context("pinning artifact reference to digest")?;
+    let client = client::build_client(options.client_protocol.clone());
+    let remote = timeout(
+        Duration::from_secs(30),
+        client::fetch_manifest_and_config(&client, &pinned, &options.credentials, None),
+    )
+    .await
+    .context("artifact timeout while fetching manifest")??;
+    anyhow::ensure!(
+        remote.manifest_digest == digest,
+        "manifest digest mismatch: expected {digest}, got {}",
+        remote.manifest_digest
+    );
+
+    tokio::fs::create_dir_all(destination)
+        .await
+        .with_context(|| format!("creating destination {}", destination.display()))?;
+    let mut files = Vec::new();
+    for layer in &remote.layers {
+        let Some(title) = layer
+            .annotations
+            .as_ref()
+            .and_then(|a| a.get(ORG_OPENCONTAINERS_IMAGE_TITLE))
+        else {
+            continue;
+        };
+        let name = sanitize_relative_file_path(title)?;
+        anyhow::ensure!(
+            !name.contains('/') && !name.starts_with('.'),
+            "artifact layer title must be plain file name: {title}"
+        );
+        let part = destination.join(format!(".{}.part", sanitize_path_component(&layer.digest)));
+        client::download_blob_to_file(
+            &client,
+            &pinned,
+            layer,
+            &options.credentials,
+            &part,
+            |_, _| {},
+            None,
+        )
+        .await
+        .with_context(|| format!("downloading blob {}", layer.digest))?;
+        let local = destination.join(name);
+        tokio::fs::rename(&part, &local)
+            .await
+            .with_context(|| format!("renaming {} to {}", part.display(), local.display()))?;
+        files.push(local);
+    }
+
+    Ok(PulledArtifact {
+        digest: remote.manifest_digest,
+        files,
+    })
+}
+
+/// Replace `:` of tag or digest, so it can be path component
+#[must_use]
+pub fn sanitize_path_component(value: &str) -> String {
+    value.replace(':', "_")
+}
+
+/// Normalize relative path, refusing absolute ones and ones leaving base directory
+///
+/// # Errors
+/// Fails if path is absolute, empty, or has `..` components
+pub fn sanitize_relative_file_path(value: &str) -> anyhow::Result<String> {
+    let mut out = PathBuf::new();
+    let path = Path::new(value);
+    if path.is_absolute() {
+        anyhow::bail!("absolute path is not allowed: {value}");
+    }
+    for component in path.components() {
+        match component {
+            std::path::Component::Normal(part) => out.push(part),
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                anyhow::bail!("parent dir is not allowed: {value}");
+            }
+            std::path::Component::RootDir | std::path::Component::Prefix(_) => {
+                anyhow::bail!("non-relative path is not allowed: {value}");
+            }
+        }
+    }
+    if out.as_os_str().is_empty() {
+        anyhow::bail!("empty path is not allowed");
+    }
+    Ok(out.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_registry::TestRegistry;
+
+    fn options(reference: &str, credentials: RegistryCredentials) -> ArtifactOptions {
+        ArtifactOptions {
+            reference: reference.parse().expect("valid reference"),
+            credentials,
+            client_protocol: ClientProtocol::Http,
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("givc-oras-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn sanitize_relative_file_path_rejects_parent_dir() {
+        let err = sanitize_relative_file_path("../../etc/passwd").expect_err("must fail");
+        assert!(err.to_string().contains("parent dir"));
+    }
+
+    #[tokio::test]
+    async fn pull_artifact_by_tag_digest() -> anyhow::Result<()> {
+        let registry = TestRegistry::start().await?;
+        let digest = registry.push_artifact(
+            "policies/proxy",
+            "stable",
+            &[("rules.json", b"{}"), ("rules.json.sig", b"key:sig")],
+        );
+        let options = options(
+            &registry.reference("policies/proxy", "stable"),
+            RegistryCredentials::Anonymous,
+        );
+        assert_eq!(resolve_artifact_digest(&options).await?, digest);
+
+        let destination = temp_dir("pull");
+        let pulled = pull_artifact(&options, &digest, &destination).await?;
+        assert_eq!(pulled.digest, digest);
+        assert_eq!(
+            pulled.files,
+            vec![
+                destination.join("rules.json"),
+                destination.join("rules.json.sig")
+            ]
+        );
+        assert_eq!(std::fs::read(destination.join("rules.json"))?, b"{}");
+        // Only named files are left, no partial downloads
+        assert_eq!(std::fs::read_dir(&destination)?.count(), 2);
+
+        // Tag moved, while pull is pinned to old digest
+        let moved = registry.push_artifact("policies/proxy", "stable", &[("rules.json", b"[]")]);
+        assert_eq!(resolve_artifact_digest(&options).await?, moved);
+        let pinned = pull_artifact(&options, &digest, &destination).await?;
+        assert_eq!(pinned.files.len(), 2);
+        assert_eq!(std::fs::read(destination.join("rules.json"))?, b"{}");
+
+        std::fs::remove_dir_all(&destination)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pull_artifact_rejects_unsafe_titles() -> anyhow::Result<()> {
+        let registry = TestRegistry::start().await?;
+        let reference = registry.reference("policies/proxy", "stable");
+        for title in ["../escape", "nested/file", ".hidden"] {
+            let digest = registry.push_artifact("policies/proxy", "stable", &[(title, b"{}")]);
+            let options = options(&reference, RegistryCredentials::Anonymous);
+            let destination = temp_dir("unsafe");
+            assert!(
+                pull_artifact(&options, &digest, &destination)
+                    .await
+                    .is_err(),
+                "{title} must be rejected"
+            );
+            let _ = std::fs::remove_dir_all(&destination);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pull_artifact_with_credentials() -> anyhow::Result<()> {
+        let registry = TestRegistry::start_with_basic_auth("reader", "secret").await?;
+        let digest = registry.push_artifact("policies/proxy", "v1", &[("rules.json", b"{}")]);
+        let reference = registry.reference("policies/proxy", "v1");
+
+        let anonymous = options(&reference, RegistryCredentials::Anonymous);
+        assert!(resolve_artifact_digest(&anonymous).await.is_err());
+
+        let basic = options(
+            &reference,
+            RegistryCredentials::Basic {
+                username: "reader".to_string(),
+                password: "secret".to_string(),
+            },
+        );
+        assert_eq!(resolve_artifact_digest(&basic).await?, digest);
+        let destination = temp_dir("credentials");
+        pull_artifact(&basic, &digest, &destination).await?;
+        assert_eq!(std::fs::read(destination.join("rules.json"))?, b"{}");
+        std::fs::remove_dir_all(&destination)?;
+        Ok(())
+    }
+}
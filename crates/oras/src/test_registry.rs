@@ -0,0 +1,198 @@
+// SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// In-memory OCI registry, for tests of code pulling artifacts. Serves only
+// reads of manifests and blobs, artifacts are put into it with `push_artifact()`.
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use axum::body::Bytes;
+use axum::http::{HeaderMap, StatusCode, Uri, header};
+use axum::response::{IntoResponse, Response};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use oci_client::manifest::{OCI_IMAGE_MEDIA_TYPE, OciDescriptor, OciImageManifest};
+use sha2::{Digest, Sha256};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use crate::ORG_OPENCONTAINERS_IMAGE_TITLE;
+
+const EMPTY_CONFIG_MEDIA_TYPE: &str = "application/vnd.oci.empty.v1+json";
+const LAYER_MEDIA_TYPE: &str = "application/octet-stream";
+
+#[derive(Debug, Default)]
+struct Storage {
+    /// Manifests by `<repository>:<tag or digest>`
+    manifests: HashMap<String, Bytes>,
+    blobs: HashMap<String, Bytes>,
+}
+
+#[derive(Debug)]
+struct State {
+    storage: Mutex<Storage>,
+    /// Expected `Authorization` header, if registry requires basic auth
+    authorization: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct TestRegistry {
+    address: SocketAddr,
+    state: Arc<State>,
+    server: JoinHandle<()>,
+}
+
+fn digest(data: &[u8]) -> String {
+    format!("sha256:{}", hex::encode(Sha256::digest(data)))
+}
+
+impl TestRegistry {
+    /// Start anonymous registry on random local port
+    ///
+    /// # Errors
+    /// Fails if listener can't be bound
+    pub async fn start() -> anyhow::Result<Self> {
+        Self::serve(None).await
+    }
+
+    /// Start registry, which requires HTTP basic auth
+    ///
+    /// # Errors
+    /// Fails if listener can't be bound
+    pub async fn start_with_basic_auth(username: &str, password: &str) -> anyhow::Result<Self> {
+        let credentials = STANDARD.encode(format!("{username}:{password}"));
+        Self::serve(Some(format!("Basic {credentials}"))).await
+    }
+
+    async fn serve(authorization: Option<String>) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        let state = Arc::new(State {
+            storage: Mutex::default(),
+            authorization,
+        });
+        let app = axum::Router::new().fallback({
+            let state = state.clone();
+            move |uri: Uri, headers: HeaderMap| async move { handle(&state, &uri, &headers) }
+        });
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        Ok(Self {
+            address,
+            state,
+            server,
+        })
+    }
+
+    /// Reference of `repository:tag` at this registry
+    #[must_use]
+    pub fn reference(&self, repository: &str, tag: &str) -> String {
+        format!("{}/{repository}:{tag}", self.address)
+    }
+
+    /// Store artifact with one titled layer per file, and point `tag` to it.
+    /// Returns manifest digest.
+    ///
+    /// # Panics
+    /// Panics if storage lock is poisoned
+    #[must_use]
+    pub fn push_artifact(&self, repository: &str, tag: &str, files: &[(&str, &[u8])]) -> String {
+        let mut storage = self.state.storage.lock().unwrap();
+        let mut blob = |data: &[u8]| {
+            let digest = digest(data);
+            storage
+                .blobs
+                .insert(digest.clone(), Bytes::copy_from_slice(data));
+            digest
+        };
+        let config = OciDescriptor {
+            media_type: EMPTY_CONFIG_MEDIA_TYPE.to_string(),
+            digest: blob(b"{}"),
+            size: 2,
+            ..Default::default()
+        };
+        let layers = files
+            .iter()
+            .map(|(name, data)| OciDescriptor {
+                media_type: LAYER_MEDIA_TYPE.to_string(),
+                digest: blob(data),
+                size: i64::try_from(data.len()).unwrap_or(i64::MAX),
+                annotations: Some(BTreeMap::from([(
+                    ORG_OPENCONTAINERS_IMAGE_TITLE.to_string(),
+                    (*name).to_string(),
+                )])),
+                ..Default::default()
+            })
+            .collect();
+        let manifest = OciImageManifest {
+            media_type: Some(OCI_IMAGE_MEDIA_TYPE.to_string()),
+            config,
+            layers,
+            artifact_type: Some(LAYER_MEDIA_TYPE.to_string()),
+            ..Default::default()
+        };
+        let body = Bytes::from(serde_json::to_vec(&manifest).unwrap_or_default());
+        let digest = digest(&body);
+        storage
+            .manifests
+            .insert(format!("{repository}:{digest}"), body.clone());
+        storage
+            .manifests
+            .insert(format!("{repository}:{tag}"), body);
+        digest
+    }
+}
+
+impl Drop for TestRegistry {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+fn handle(state: &State, uri: &Uri, headers: &HeaderMap) -> Response {
+    if let Some(expected) = &state.authorization
+        && headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            != Some(expected.as_str())
+    {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Basic realm=\"test-registry\"")],
+        )
+            .into_response();
+    }
+    let Some(path) = uri.path().strip_prefix("/v2/") else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if path.is_empty() {
+        return StatusCode::OK.into_response();
+    }
+
+    let storage = state.storage.lock().unwrap();
+    if let Some((repository, reference)) = path.rsplit_once("/manifests/") {
+        match storage.manifests.get(&format!("{repository}:{reference}")) {
+            Some(body) => (
+                [
+                    (header::CONTENT_TYPE, OCI_IMAGE_MEDIA_TYPE.to_string()),
+                    (
+                        header::HeaderName::from_static("docker-content-digest"),
+                        digest(body),
+                    ),
+                ],
+                body.clone(),
+            )
+                .into_response(),
+            None => StatusCode::NOT_FOUND.into_response(),
+        }
+    } else if let Some((_, blob)) = path.rsplit_once("/blobs/") {
+        match storage.blobs.get(blob) {
+            Some(body) => body.clone().into_response(),
+            None => StatusCode::NOT_FOUND.into_response(),
+        }
+    } else {
+        StatusCode::NOT_FOUND.into_response()
+    }
+}
@@ -25,7 +25,9 @@ impl UntaggedReference {
         format!("{}/{}", self.0.resolve_registry(), self.0.repository())
     }
 
-    pub(crate) fn for_tag(&self, tag: &str) -> anyhow::Result<TaggedReference> {
+    /// # Errors
+    /// Fails if `tag` isn't valid tag
+    pub fn for_tag(&self, tag: &str) -> anyhow::Result<TaggedReference> {
         let value = format!("{}:{}", self.repository_path(), tag);
         value
             .parse()
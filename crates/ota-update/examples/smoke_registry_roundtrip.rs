@@ -5,10 +5,11 @@ use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use clap::Parser;
-use oci_client::client::ClientProtocol;
 use ota_update::image::install::validate_manifest_path;
+use ota_update::image::signature::Verification;
 use ota_update::registry::{
-    PullOptions, PushOptions, RegistryCredentials, TaggedReference, pull_update, push_update,
+    ClientProtocol, PullOptions, PushOptions, RegistryCredentials, TaggedReference, pull_update,
+    push_update,
 };
 
 #[derive(Debug, Parser)]
@@ -39,6 +40,10 @@ struct Args {
 
     #[arg(long)]
     insecure: bool,
+
+    /// Require manifest signed by one of these keys, unsigned manifests are accepted otherwise
+    #[arg(long)]
+    trusted_keys: Option<PathBuf>,
 }
 
 fn validate_reference_host(reference: &str, allow_nonlocal: bool) -> anyhow::Result<()> {
@@ -73,7 +78,11 @@ fn credentials(args: &Args) -> anyhow::Result<RegistryCredentials> {
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
     validate_reference_host(&args.reference, args.allow_nonlocal)?;
-    validate_manifest_path(&args.manifest).await?;
+    let verification = match &args.trusted_keys {
+        Some(path) => Verification::load(path, false)?,
+        None => Verification::Unsigned,
+    };
+    validate_manifest_path(&args.manifest, &verification).await?;
 
     let creds = credentials(&args)?;
     let client_protocol = if args.insecure {
@@ -100,14 +109,16 @@ async fn main() -> anyhow::Result<()> {
             credentials: creds,
             client_protocol,
             install: false,
+            boot_tries: None,
             validate: true,
+            verification: verification.clone(),
         },
         None,
         None,
     )
     .await?;
 
-    validate_manifest_path(&pull.manifest_path).await?;
+    validate_manifest_path(&pull.manifest_path, &verification).await?;
     println!(
         "PASS pushed={} pulled={}",
         push.manifest_url,
@@ -3,16 +3,22 @@
 
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 
 use anyhow::Context;
 use cachix_client::{CachixClientConfig, nixos::filter_valid_systems};
 use clap::{ArgAction, Parser, Subcommand};
 use ota_update::cli::{CachixOptions, QueryUpdates, query_updates};
+use ota_update::generation::{RollbackTarget, collect_garbage, rollback};
+use ota_update::image::Version;
 use ota_update::image::cli::ImageUpdate;
 use ota_update::profile;
+use ota_update::progress::{ActivationStep, NixProgress, UpdateEvent};
 use ota_update::query::query_available_updates;
-use ota_update::registry::cli::RegistryCommand;
+use ota_update::registry::cli::{OutputFormat, RegistryCommand};
 use regex::Regex;
 use tracing::info;
 
@@ -50,9 +56,48 @@ enum Commands {
     /// Query updates list
     Query(QueryUpdates),
 
-    Cachix(CachixOptions),
+    Cachix {
+        #[command(flatten)]
+        options: CachixOptions,
+
+        /// Output format for progress
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        output: OutputFormat,
+    },
     Image(ImageUpdate),
     Registry(RegistryCommand),
+
+    /// Switch to previous generation, or to other image slot on image based systems
+    Rollback {
+        /// Image slot version to roll back to, if there are several
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Optional hash fragment of image slot
+        #[arg(long, requires = "version")]
+        hash: Option<String>,
+
+        /// Do not change anything, only show rollback target
+        #[arg(long)]
+        dry_run: bool,
+
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        output: OutputFormat,
+    },
+
+    /// Remove old generations, current and booted ones are always kept
+    Gc {
+        /// Number of newest generations to keep
+        #[arg(long, default_value_t = 3)]
+        keep: usize,
+
+        /// Do not remove anything, only show what would be removed
+        #[arg(long)]
+        dry_run: bool,
+
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        output: OutputFormat,
+    },
 }
 
 async fn get_generations() -> anyhow::Result<()> {
@@ -76,15 +121,42 @@ fn is_valid_nix_path(path: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Run `nix build`, reporting fetch progress parsed from its JSON log
+async fn nix_build_with_progress(nix: &mut Command) -> anyhow::Result<std::process::ExitStatus> {
+    let mut child = nix
+        .arg("--log-format")
+        .arg("internal-json")
+        .arg("-v")
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to execute 'nix build'")?;
+    let stderr = child.stderr.take().context("nix build stderr")?;
+    let mut lines = BufReader::new(stderr).lines();
+    let mut progress = NixProgress::default();
+    while let Some(line) = lines.next_line().await? {
+        if let Some(event) = progress.feed(&line, Instant::now()) {
+            event.report(OutputFormat::Jsonl);
+        } else if !line.starts_with("@nix ") {
+            eprintln!("{line}");
+        }
+    }
+    Ok(child.wait().await?)
+}
+
 async fn set_generation(
     path: &Path,
     sources: &[String],
     pub_keys: &[String],
     no_check_signs: bool,
+    output: OutputFormat,
 ) -> anyhow::Result<()> {
     const GCROOT: &str = "/nix/var/nix/gcroots/auto/ota-update";
 
     is_valid_nix_path(path)?;
+    UpdateEvent::Resolved {
+        store_path: path.display().to_string(),
+    }
+    .report(output);
 
     let mut nix = Command::new("nix");
     nix.arg("--extra-experimental-features")
@@ -106,14 +178,21 @@ async fn set_generation(
     if no_check_signs {
         nix.arg("--no-check-sigs");
     }
-    let nix = nix
-        .status()
-        .await
-        .context("Failed to execute 'nix build'")?;
+    let nix = match output {
+        OutputFormat::Jsonl => nix_build_with_progress(&mut nix).await?,
+        OutputFormat::Text => nix
+            .status()
+            .await
+            .context("Failed to execute 'nix build'")?,
+    };
     if !nix.success() {
         anyhow::bail!("nix build failed");
     }
 
+    UpdateEvent::Activating {
+        step: ActivationStep::SetProfile,
+    }
+    .report(output);
     profile::set(
         Path::new("/nix/var/nix/profiles/"),
         OsStr::new("system"),
@@ -125,12 +204,12 @@ async fn set_generation(
         info!("Fail to unlink {GCROOT}: {e}");
     }
 
-    let boot_path = path.join("bin/switch-to-configuration");
-    Command::new(&boot_path)
-        .arg("boot")
-        .status()
-        .await
-        .with_context(|| format!("Fail to execute {}", boot_path.display()))?;
+    UpdateEvent::Activating {
+        step: ActivationStep::InstallBootloader,
+    }
+    .report(output);
+    profile::install_bootloader(path).await?;
+    UpdateEvent::Done.report(output);
     Ok(())
 }
 
@@ -145,6 +224,7 @@ async fn perform_cachix_update(
     token: Option<String>,
     host: Option<String>,
     cache: String,
+    output: OutputFormat,
 ) -> anyhow::Result<()> {
     let system = read_system_boot_json().await?;
     let mut client_config = CachixClientConfig::new(cache);
@@ -161,7 +241,14 @@ async fn perform_cachix_update(
         .find_map(|(pin, _)| (pin.name == pin_name).then_some(pin.last_revision.store_path))
         .context("no valid systems")?;
     let info = client.cache_info().await?;
-    set_generation(&candidate, &[info.uri], &info.public_signing_keys, false).await?;
+    set_generation(
+        &candidate,
+        &[info.uri],
+        &info.public_signing_keys,
+        false,
+        output,
+    )
+    .await?;
     Ok(())
 }
 
@@ -184,6 +271,7 @@ async fn perform_local_update(
         &[source],
         &[candidate.pub_key],
         no_check_signs,
+        OutputFormat::Text,
     )
     .await?;
     Ok(())
@@ -204,14 +292,54 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Commands::Query(query) => {
             query_updates(query).await?;
         }
-        Commands::Cachix(CachixOptions {
-            pin_name,
-            token,
-            cachix_host,
-            cache,
-        }) => perform_cachix_update(&pin_name, token, cachix_host, cache).await?,
+        Commands::Cachix {
+            options:
+                CachixOptions {
+                    pin_name,
+                    token,
+                    cachix_host,
+                    cache,
+                },
+            output,
+        } => perform_cachix_update(&pin_name, token, cachix_host, cache, output).await?,
         Commands::Image(image) => image.handle().await?,
         Commands::Registry(registry) => registry.handle().await?,
+        Commands::Rollback {
+            version,
+            hash,
+            dry_run,
+            output,
+        } => {
+            let version = version.map(|version| Version::new(version, hash));
+            let target = rollback(version, dry_run).await?;
+            match output {
+                OutputFormat::Jsonl => println!("{}", serde_json::to_string(&target)?),
+                OutputFormat::Text => match target {
+                    RollbackTarget::Generation {
+                        generation,
+                        store_path,
+                    } => println!(
+                        "rollback to generation {generation} ({})",
+                        store_path.display()
+                    ),
+                    RollbackTarget::Slot { version } => println!("rollback to slot {version}"),
+                },
+            }
+        }
+        Commands::Gc {
+            keep,
+            dry_run,
+            output,
+        } => {
+            let report = collect_garbage(keep, dry_run).await?;
+            match output {
+                OutputFormat::Jsonl => println!("{}", serde_json::to_string(&report)?),
+                OutputFormat::Text => {
+                    println!("removed generations: {:?}", report.removed);
+                    println!("kept generations: {:?}", report.kept);
+                }
+            }
+        }
     }
     Ok(())
 }
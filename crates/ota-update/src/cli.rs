@@ -2,9 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::query::query_available_updates;
+use crate::registry::TaggedReference;
 use crate::types::UpdateInfo;
 use clap::Parser;
 use serde_json;
+use std::fmt;
+
+const REDACTED: &str = "<redacted>";
+
+/// Secret shown only as present or absent, so options could be logged
+fn redacted(secret: Option<&String>) -> Option<&str> {
+    secret.map(|_| REDACTED)
+}
 
 #[derive(Parser, Clone, Debug)]
 pub struct QueryUpdates {
@@ -21,7 +30,7 @@ pub struct QueryUpdates {
     pin_name: String,
 }
 
-#[derive(Parser, Clone, Debug)]
+#[derive(Parser, Clone)]
 pub struct CachixOptions {
     pub pin_name: String,
 
@@ -35,6 +44,66 @@ pub struct CachixOptions {
     pub cachix_host: Option<String>,
 }
 
+#[derive(Parser, Clone)]
+pub struct OciOptions {
+    /// OCI reference with tag (registry/repo[:tag])
+    pub reference: TaggedReference,
+
+    /// Registry username (basic auth)
+    #[arg(long, env = "GIVC_REGISTRY_USERNAME", requires = "password")]
+    pub username: Option<String>,
+
+    /// Registry password (basic auth)
+    #[arg(long, env = "GIVC_REGISTRY_PASSWORD", requires = "username")]
+    pub password: Option<String>,
+
+    /// Registry API token (bearer auth)
+    #[arg(long, env = "GIVC_REGISTRY_TOKEN", conflicts_with_all = ["username", "password"])]
+    pub token: Option<String>,
+
+    /// Use HTTP instead of HTTPS for registry access
+    #[arg(long)]
+    pub insecure: bool,
+
+    /// Validate pulled artifacts
+    #[arg(long)]
+    pub validate: bool,
+
+    /// Install image right after successful pull
+    #[arg(long)]
+    pub install: bool,
+
+    /// Download root on host
+    #[arg(long)]
+    pub destination: Option<String>,
+}
+
+impl fmt::Debug for CachixOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachixOptions")
+            .field("pin_name", &self.pin_name)
+            .field("token", &redacted(self.token.as_ref()))
+            .field("cache", &self.cache)
+            .field("cachix_host", &self.cachix_host)
+            .finish()
+    }
+}
+
+impl fmt::Debug for OciOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OciOptions")
+            .field("reference", &self.reference)
+            .field("username", &self.username)
+            .field("password", &redacted(self.password.as_ref()))
+            .field("token", &redacted(self.token.as_ref()))
+            .field("insecure", &self.insecure)
+            .field("validate", &self.validate)
+            .field("install", &self.install)
+            .field("destination", &self.destination)
+            .finish()
+    }
+}
+
 /// # Errors
 /// Fails if fetch/parse raise failure
 pub async fn query_updates(query: QueryUpdates) -> anyhow::Result<()> {
@@ -53,3 +122,32 @@ pub async fn query_updates(query: QueryUpdates) -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let oci = OciOptions::parse_from([
+            "oci",
+            "registry.example/ghaf:1.0",
+            "--username",
+            "user",
+            "--password",
+            "hunter2",
+        ]);
+        let dump = format!("{oci:#?}");
+        assert!(dump.contains("user"));
+        assert!(dump.contains(REDACTED));
+        assert!(!dump.contains("hunter2"));
+
+        let oci = OciOptions::parse_from(["oci", "registry.example/ghaf:1.0", "--token", "s3cr3t"]);
+        assert!(!format!("{oci:?}").contains("s3cr3t"));
+
+        let cachix = CachixOptions::parse_from(["cachix", "ghaf-updates", "--token", "s3cr3t"]);
+        let dump = format!("{cachix:?}");
+        assert!(dump.contains("ghaf-updates"));
+        assert!(!dump.contains("s3cr3t"));
+    }
+}
@@ -0,0 +1,206 @@
+// SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Rollback and garbage collection of system generations. Systems without `system`
+// profile are image based, there rollback switches default boot entry to other slot.
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tracing::info;
+
+use crate::image::Version;
+use crate::image::install::rollback_image;
+use crate::lock::UpdateLock;
+use crate::profile::{
+    format_profile_link, install_bootloader, read_profile_links, switch_generation,
+};
+use crate::types::ProfileElement;
+
+const PROFILES_DIR: &str = "/nix/var/nix/profiles";
+const SYSTEM_PROFILE: &str = "system";
+const LOCK_PATH: &str = "/run/ota-update.lock";
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RollbackTarget {
+    Generation {
+        generation: i32,
+        store_path: PathBuf,
+    },
+    Slot {
+        version: String,
+    },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GcReport {
+    pub removed: Vec<i32>,
+    pub kept: Vec<i32>,
+}
+
+/// Newest generation older than `default`, same as `nix-env --rollback` picks
+#[must_use]
+pub fn previous_generation(profiles: &[ProfileElement], default: i32) -> Option<&ProfileElement> {
+    profiles
+        .iter()
+        .filter(|profile| profile.num < default)
+        .max_by_key(|profile| profile.num)
+}
+
+/// Split `profiles` into kept and removed ones. `keep` newest generations are kept,
+/// as well as default one and ones pointing to `protected` store paths (current
+/// and booted systems) regardless of their age.
+#[must_use]
+pub fn select_garbage<'a>(
+    profiles: &'a [ProfileElement],
+    keep: usize,
+    protected: &[PathBuf],
+) -> (Vec<&'a ProfileElement>, Vec<&'a ProfileElement>) {
+    let mut sorted: Vec<_> = profiles.iter().collect();
+    sorted.sort_by_key(|profile| std::cmp::Reverse(profile.num));
+    let (mut kept, mut removed): (Vec<_>, Vec<_>) =
+        sorted.into_iter().enumerate().partition(|(n, profile)| {
+            *n < keep || profile.current || protected.contains(&profile.store_path)
+        });
+    kept.reverse();
+    removed.reverse();
+    (
+        kept.into_iter().map(|(_, profile)| profile).collect(),
+        removed.into_iter().map(|(_, profile)| profile).collect(),
+    )
+}
+
+async fn has_system_profile() -> bool {
+    fs::symlink_metadata(Path::new(PROFILES_DIR).join(SYSTEM_PROFILE))
+        .await
+        .is_ok()
+}
+
+/// Switch to generation preceding default one, or on image based systems to
+/// slot of `version` (the only inactive slot if not given)
+///
+/// # Errors
+/// Fails if there is nothing to roll back to, or activation fails
+pub async fn rollback(version: Option<Version>, dry_run: bool) -> anyhow::Result<RollbackTarget> {
+    if !has_system_profile().await {
+        let version = rollback_image(version, dry_run).await?;
+        return Ok(RollbackTarget::Slot {
+            version: format!("{version:#}"),
+        });
+    }
+    anyhow::ensure!(
+        version.is_none(),
+        "version can be given only on image based systems"
+    );
+
+    let _lock = UpdateLock::acquire(LOCK_PATH, "rollback")?;
+    let (default, profiles) = read_profile_links(PROFILES_DIR, SYSTEM_PROFILE).await?;
+    let target = previous_generation(&profiles, default)
+        .with_context(|| format!("no generation older than {default}"))?;
+    if !dry_run {
+        switch_generation(
+            Path::new(PROFILES_DIR),
+            OsStr::new(SYSTEM_PROFILE),
+            target.num,
+        )
+        .await?;
+        install_bootloader(&target.store_path).await?;
+    }
+    Ok(RollbackTarget::Generation {
+        generation: target.num,
+        store_path: target.store_path.clone(),
+    })
+}
+
+/// Remove profile links of old generations, keeping `keep` newest ones. Default,
+/// current and booted generations are never removed. Store paths are freed by
+/// next run of nix garbage collector.
+///
+/// # Errors
+/// Fails if profiles can't be read, or links can't be removed
+pub async fn collect_garbage(keep: usize, dry_run: bool) -> anyhow::Result<GcReport> {
+    let _lock = UpdateLock::acquire(LOCK_PATH, "gc")?;
+    let (default, profiles) = read_profile_links(PROFILES_DIR, SYSTEM_PROFILE).await?;
+    let mut protected = Vec::new();
+    for link in ["/run/current-system", "/run/booted-system"] {
+        protected.push(
+            fs::read_link(link)
+                .await
+                .with_context(|| format!("while reading {link}"))?,
+        );
+    }
+
+    let (kept, removed) = select_garbage(&profiles, keep, &protected);
+    let report = GcReport {
+        removed: removed.iter().map(|profile| profile.num).collect(),
+        kept: kept.iter().map(|profile| profile.num).collect(),
+    };
+    if dry_run || removed.is_empty() {
+        return Ok(report);
+    }
+
+    for profile in removed {
+        let link = Path::new(PROFILES_DIR).join(format_profile_link(SYSTEM_PROFILE, profile.num));
+        info!("Removing {}", link.display());
+        fs::remove_file(&link)
+            .await
+            .with_context(|| format!("while removing {}", link.display()))?;
+    }
+    // Drop boot entries of removed generations
+    let default = kept
+        .iter()
+        .find(|profile| profile.num == default)
+        .context("default generation not found")?;
+    install_bootloader(&default.store_path).await?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(num: i32, current: bool) -> ProfileElement {
+        ProfileElement {
+            num,
+            store_path: PathBuf::from(format!("/nix/store/{num}-nixos-system")),
+            current,
+            created: None,
+        }
+    }
+
+    fn nums(profiles: &[&ProfileElement]) -> Vec<i32> {
+        profiles.iter().map(|profile| profile.num).collect()
+    }
+
+    #[test]
+    fn test_previous_generation() {
+        let profiles = [profile(3, false), profile(7, true), profile(5, false)];
+        assert_eq!(previous_generation(&profiles, 7).map(|p| p.num), Some(5));
+        assert_eq!(previous_generation(&profiles, 5).map(|p| p.num), Some(3));
+        assert!(previous_generation(&profiles, 3).is_none());
+    }
+
+    #[test]
+    fn test_select_garbage() {
+        let profiles = [
+            profile(1, false),
+            profile(2, false),
+            profile(3, true),
+            profile(4, false),
+            profile(5, false),
+            profile(6, false),
+        ];
+        let (kept, removed) = select_garbage(&profiles, 2, &[]);
+        assert_eq!(nums(&kept), [3, 5, 6]);
+        assert_eq!(nums(&removed), [1, 2, 4]);
+
+        // Booted generation survives, even with nothing else kept
+        let booted = [profiles[0].store_path.clone()];
+        let (kept, removed) = select_garbage(&profiles, 0, &booted);
+        assert_eq!(nums(&kept), [1, 3]);
+        assert_eq!(nums(&removed), [2, 4, 5, 6]);
+    }
+}
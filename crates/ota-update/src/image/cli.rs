@@ -2,13 +2,46 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::Version;
+use super::checksum::read_sha256;
+use super::delta::{DEFAULT_BLOCK_SIZE, apply_file, create_file};
 use super::install::{
-    execute_plan, install_from_manifest_path, populate_runtime, validate_manifest_path,
+    apply_plan_file, execute_plan, install_from_manifest_path, populate_runtime,
+    print_install_plan, validate_manifest_path,
 };
+use super::manifest::{DeltaFile, File, Manifest};
 use super::plan::Plan;
+use super::signature::{DEFAULT_TRUSTED_KEYS, SecretKey, Verification, signature_path};
+use anyhow::Context;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
+fn parse_sha256(value: &str) -> Result<[u8; 32], String> {
+    let bytes = hex::decode(value).map_err(|e| e.to_string())?;
+    bytes
+        .try_into()
+        .map_err(|_| "expected 32 bytes SHA-256 checksum".to_string())
+}
+
+/// Keys, which manifests must be signed with
+#[derive(Debug, Clone, Parser)]
+pub struct TrustOptions {
+    /// File with trusted public keys, `<name>:<base64>` per line
+    #[arg(long, env = "OTA_TRUSTED_KEYS", default_value = DEFAULT_TRUSTED_KEYS)]
+    pub trusted_keys: PathBuf,
+
+    /// Accept manifests without valid signature
+    #[arg(long)]
+    pub allow_unsigned: bool,
+}
+
+impl TrustOptions {
+    /// # Errors
+    /// Fails if trusted keys can't be loaded
+    pub fn load(&self) -> anyhow::Result<Verification> {
+        Verification::load(&self.trusted_keys, self.allow_unsigned)
+    }
+}
+
 #[derive(Debug, Parser)]
 pub struct ImageUpdate {
     #[command(subcommand)]
@@ -24,8 +57,17 @@ pub enum ImageAction {
     /// Install image from manifest
     Install {
         /// Path to manifest.json
-        #[arg(long)]
-        manifest: PathBuf,
+        #[arg(long, required_unless_present = "apply_plan")]
+        manifest: Option<PathBuf>,
+
+        /// Print install plan as JSON instead of executing it, for review
+        #[arg(long, conflicts_with = "apply_plan")]
+        plan_json: bool,
+
+        /// Execute plan previously printed with `--plan-json`. Plan is made again from
+        /// its manifest (with its boot tries), and executed only if it is unchanged
+        #[arg(long, conflicts_with_all = ["manifest", "validate", "no_validate"])]
+        apply_plan: Option<PathBuf>,
 
         /// Validate manifest checksums before install
         #[arg(long, conflicts_with = "no_validate")]
@@ -34,13 +76,76 @@ pub enum ImageAction {
         /// Skip manifest checksum validation (default)
         #[arg(long, conflicts_with = "validate")]
         no_validate: bool,
+
+        /// Boot attempts before new slot is rolled back, 0 disables boot assessment
+        #[arg(long, env = "OTA_BOOT_TRIES", default_value_t = 0)]
+        boot_tries: u32,
+
+        #[command(flatten)]
+        trust: TrustOptions,
     },
 
-    /// Validate image manifest content only
+    /// Validate image manifest signature and content only
     Validate {
         /// Path to manifest.json
         #[arg(long)]
         manifest: PathBuf,
+
+        #[command(flatten)]
+        trust: TrustOptions,
+    },
+
+    /// Sign manifest, writing detached signature next to it
+    Sign {
+        /// Path to manifest.json
+        #[arg(long)]
+        manifest: PathBuf,
+
+        /// File with secret key, as generated by `nix-store --generate-binary-cache-key`
+        #[arg(long, env = "OTA_SIGNING_KEY")]
+        key: PathBuf,
+    },
+
+    /// Create block-level delta of volume image against image of base version,
+    /// printing its manifest entry
+    Delta {
+        /// Volume image of base version (uncompressed)
+        #[arg(long)]
+        base: PathBuf,
+
+        /// Volume image of new version (uncompressed)
+        #[arg(long)]
+        target: PathBuf,
+
+        /// Delta file to write
+        #[arg(long)]
+        output: PathBuf,
+
+        #[arg(long, default_value_t = DEFAULT_BLOCK_SIZE)]
+        block_size: u32,
+    },
+
+    /// Reconstruct volume from base volume and delta, used by install plans
+    ApplyDelta {
+        /// Volume of base slot
+        #[arg(long)]
+        base: PathBuf,
+
+        /// Volume to write
+        #[arg(long)]
+        target: PathBuf,
+
+        /// Delta file, read from stdin if omitted
+        #[arg(long)]
+        delta: Option<PathBuf>,
+
+        /// Expected size of reconstructed volume image
+        #[arg(long)]
+        size: u64,
+
+        /// Expected SHA-256 of reconstructed volume image
+        #[arg(long, value_parser = parse_sha256)]
+        sha256: [u8; 32],
     },
 
     /// Remove installed image slot
@@ -54,26 +159,116 @@ pub enum ImageAction {
         hash: Option<String>,
     },
     Status,
+
+    /// Confirm active slot after successful boot, ending its boot assessment.
+    /// Same rename as `systemd-bless-boot good`, for systems without it
+    Confirm,
+
+    /// Demote slots which failed boot assessment, and restore active one as default
+    Assess,
 }
 
 impl ImageUpdate {
-    #[allow(clippy::missing_errors_doc)]
+    #[allow(clippy::missing_errors_doc, clippy::too_many_lines)]
     pub async fn handle(self) -> anyhow::Result<()> {
         match self.action {
             ImageAction::Install {
                 manifest,
+                plan_json,
+                apply_plan,
                 validate,
                 no_validate,
+                boot_tries,
+                trust,
             } => {
-                install_from_manifest_path(&manifest, validate && !no_validate, self.dry_run).await
+                if let Some(plan) = apply_plan {
+                    return apply_plan_file(&plan, &trust.load()?, self.dry_run).await;
+                }
+                let manifest = manifest.context("--manifest is required")?;
+                let validate = validate && !no_validate;
+                let boot_tries = (boot_tries > 0).then_some(boot_tries);
+                if plan_json {
+                    print_install_plan(&manifest, &trust.load()?, validate, boot_tries).await
+                } else {
+                    install_from_manifest_path(
+                        &manifest,
+                        &trust.load()?,
+                        validate,
+                        boot_tries,
+                        self.dry_run,
+                    )
+                    .await
+                }
             }
 
-            ImageAction::Validate { manifest } => {
-                validate_manifest_path(&manifest).await?;
+            ImageAction::Validate { manifest, trust } => {
+                validate_manifest_path(&manifest, &trust.load()?).await?;
                 println!("Manifest validation successful.");
                 Ok(())
             }
 
+            ImageAction::Sign { manifest, key } => {
+                let key: SecretKey = std::fs::read_to_string(&key)
+                    .with_context(|| format!("reading signing key {}", key.display()))?
+                    .parse()?;
+                let signature = key.sign(&Manifest::from_file(&manifest)?)?;
+                let path = signature_path(&manifest);
+                std::fs::write(&path, format!("{signature}\n"))
+                    .with_context(|| format!("writing signature {}", path.display()))?;
+                println!("Signed {} with {}", manifest.display(), key.public_key());
+                Ok(())
+            }
+
+            ImageAction::Delta {
+                base,
+                target,
+                output,
+                block_size,
+            } => {
+                let stats = create_file(base, target, output.clone(), block_size).await?;
+                let name = output
+                    .file_name()
+                    .context("delta output has no file name")?
+                    .to_string_lossy()
+                    .into_owned();
+                let entry = DeltaFile {
+                    file: File {
+                        name,
+                        sha256sum: read_sha256(&output).await?,
+                        unpacked_size: None,
+                    },
+                    target_sha256: stats.target_sha256,
+                    target_size: stats.target_size,
+                };
+                eprintln!(
+                    "{} blocks copied from base, {} blocks in delta",
+                    stats.copied_blocks, stats.data_blocks
+                );
+                println!("{}", serde_json::to_string_pretty(&entry)?);
+                Ok(())
+            }
+
+            ImageAction::ApplyDelta {
+                base,
+                target,
+                delta,
+                size,
+                sha256,
+            } => {
+                if self.dry_run {
+                    println!("DRY-RUN: apply delta to {}", target.display());
+                    return Ok(());
+                }
+                let stats = apply_file(base, delta, target.clone(), size, sha256).await?;
+                println!(
+                    "Reconstructed {}: {} blocks copied, {} blocks from delta",
+                    target.display(),
+                    stats.copied_blocks,
+                    stats.data_blocks
+                );
+                Ok(())
+            }
+
             ImageAction::Remove { version, hash } => {
                 let rt = populate_runtime().await?;
                 let version = Version::new(version, hash);
@@ -81,6 +276,14 @@ impl ImageUpdate {
 
                 execute_plan(plan, self.dry_run).await
             }
+            ImageAction::Confirm => {
+                let rt = populate_runtime().await?;
+                execute_plan(Plan::confirm(&rt)?, self.dry_run).await
+            }
+            ImageAction::Assess => {
+                let rt = populate_runtime().await?;
+                execute_plan(Plan::assess(&rt)?, self.dry_run).await
+            }
             ImageAction::Status => {
                 let rt = populate_runtime().await?;
                 let status = rt.inspect();
@@ -0,0 +1,386 @@
+// SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Block-level delta of volume image against the same volume of base slot.
+// Blocks are compared at the same offsets, so delta is cheap to apply sequentially
+// while streaming (optionally zstd-compressed) delta from stdin:
+//   magic "GHAFDLT1", block size (u32 LE), target size (u64 LE), then operations:
+//   0x01 <count: u64 LE>           copy `count` blocks from base
+//   0x02 <count: u64 LE> <data>    take `count` blocks from delta
+//   0x00                           end of delta
+// Last block of target may be partial, its data is truncated to target size.
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, bail, ensure};
+use sha2::{Digest, Sha256};
+
+const MAGIC: &[u8; 8] = b"GHAFDLT1";
+const OP_END: u8 = 0;
+const OP_COPY: u8 = 1;
+const OP_DATA: u8 = 2;
+
+pub const DEFAULT_BLOCK_SIZE: u32 = 64 * 1024;
+
+/// Size and checksum of reconstructed image, with block statistics of delta
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeltaStats {
+    pub target_size: u64,
+    pub target_sha256: [u8; 32],
+    pub copied_blocks: u64,
+    pub data_blocks: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Copy,
+    Data,
+}
+
+/// Read up to `buf.len()` bytes, less only at end of input
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn blocks(size: u64, block_size: u32) -> u64 {
+    size.div_ceil(u64::from(block_size))
+}
+
+/// Run of blocks of the same operation, data blocks are buffered until run ends
+struct Encoder<W> {
+    out: W,
+    op: Option<Op>,
+    count: u64,
+    data: Vec<u8>,
+}
+
+impl<W: Write> Encoder<W> {
+    fn push(&mut self, op: Op, block: &[u8]) -> io::Result<()> {
+        if self.op != Some(op) {
+            self.flush()?;
+            self.op = Some(op);
+        }
+        self.count += 1;
+        if op == Op::Data {
+            self.data.extend_from_slice(block);
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let Some(op) = self.op.take() else {
+            return Ok(());
+        };
+        let tag = match op {
+            Op::Copy => OP_COPY,
+            Op::Data => OP_DATA,
+        };
+        self.out.write_all(&[tag])?;
+        self.out.write_all(&self.count.to_le_bytes())?;
+        self.out.write_all(&self.data)?;
+        self.count = 0;
+        self.data.clear();
+        Ok(())
+    }
+}
+
+/// Write delta, which turns `base` into `target` of `target_size` bytes
+///
+/// # Errors
+/// Fails on I/O error, or if `target` is shorter than `target_size`
+pub fn create(
+    base: impl Read,
+    target: impl Read,
+    target_size: u64,
+    out: impl Write,
+    block_size: u32,
+) -> anyhow::Result<DeltaStats> {
+    ensure!(block_size > 0, "block size must be positive");
+    let mut base = io::BufReader::new(base);
+    let mut target = io::BufReader::new(target);
+    let mut out = io::BufWriter::new(out);
+    out.write_all(MAGIC)?;
+    out.write_all(&block_size.to_le_bytes())?;
+    out.write_all(&target_size.to_le_bytes())?;
+
+    let mut encoder = Encoder {
+        out,
+        op: None,
+        count: 0,
+        data: Vec::new(),
+    };
+    let mut hasher = Sha256::new();
+    let mut stats = DeltaStats {
+        target_size,
+        ..DeltaStats::default()
+    };
+    let block_size = block_size as usize;
+    let mut base_block = vec![0; block_size];
+    let mut target_block = vec![0; block_size];
+    let mut remaining = target_size;
+    while remaining > 0 {
+        let len = usize::try_from(remaining).map_or(block_size, |r| r.min(block_size));
+        let got = read_full(&mut target, &mut target_block[..len])?;
+        ensure!(got == len, "target is shorter than {target_size} bytes");
+        let block = &target_block[..len];
+        hasher.update(block);
+        // Past end of base, it reads short and never matches
+        let base_len = read_full(&mut base, &mut base_block)?;
+        if base_len >= len && base_block[..len] == *block {
+            encoder.push(Op::Copy, block)?;
+            stats.copied_blocks += 1;
+        } else {
+            encoder.push(Op::Data, block)?;
+            stats.data_blocks += 1;
+        }
+        remaining -= len as u64;
+    }
+    encoder.flush()?;
+    encoder.out.write_all(&[OP_END])?;
+    encoder.out.flush()?;
+    stats.target_sha256 = hasher.finalize().into();
+    Ok(stats)
+}
+
+/// Reconstruct target image from `base` and `delta`, writing it to `target`
+///
+/// # Errors
+/// Fails on I/O error, malformed delta, or if `base` is too short
+pub fn apply(
+    mut base: impl Read + Seek,
+    delta: impl Read,
+    mut target: impl Write,
+) -> anyhow::Result<DeltaStats> {
+    let mut delta = io::BufReader::new(delta);
+    let mut magic = [0; 8];
+    delta
+        .read_exact(&mut magic)
+        .context("reading delta header")?;
+    ensure!(&magic == MAGIC, "not a delta file");
+    let mut block_size = [0; 4];
+    delta.read_exact(&mut block_size)?;
+    let block_size = u32::from_le_bytes(block_size);
+    ensure!(block_size > 0, "invalid delta block size");
+    let target_size = read_u64(&mut delta)?;
+    let total_blocks = blocks(target_size, block_size);
+
+    let mut hasher = Sha256::new();
+    let mut stats = DeltaStats {
+        target_size,
+        ..DeltaStats::default()
+    };
+    let mut buf = vec![0; block_size as usize];
+    let mut offset = 0;
+    loop {
+        let mut tag = [0; 1];
+        delta.read_exact(&mut tag).context("truncated delta")?;
+        let op = match tag[0] {
+            OP_END => break,
+            OP_COPY => Op::Copy,
+            OP_DATA => Op::Data,
+            other => bail!("unknown delta operation {other:#x}"),
+        };
+        let count = read_u64(&mut delta)?;
+        let done = stats.copied_blocks + stats.data_blocks;
+        ensure!(
+            count <= total_blocks - done,
+            "delta describes more than {total_blocks} blocks"
+        );
+        if op == Op::Data {
+            // Keep base in step with target
+            base.seek(SeekFrom::Current(
+                i64::try_from(count * u64::from(block_size)).context("delta too large")?,
+            ))?;
+        }
+        for _ in 0..count {
+            let len = usize::try_from(target_size - offset).map_or(buf.len(), |r| r.min(buf.len()));
+            let block = &mut buf[..len];
+            match op {
+                Op::Copy => {
+                    let got = read_full(&mut base, block)?;
+                    ensure!(got == len, "base is shorter than target at offset {offset}");
+                    stats.copied_blocks += 1;
+                }
+                Op::Data => {
+                    delta.read_exact(block).context("truncated delta")?;
+                    stats.data_blocks += 1;
+                }
+            }
+            hasher.update(&*block);
+            target.write_all(block)?;
+            offset += len as u64;
+        }
+    }
+    ensure!(
+        offset == target_size,
+        "delta is incomplete: {offset} of {target_size} bytes"
+    );
+    target.flush()?;
+    stats.target_sha256 = hasher.finalize().into();
+    Ok(stats)
+}
+
+/// Create delta of `target` image against `base` image into `output` file
+///
+/// # Errors
+/// Fails if files can't be read or written
+pub async fn create_file(
+    base: PathBuf,
+    target: PathBuf,
+    output: PathBuf,
+    block_size: u32,
+) -> anyhow::Result<DeltaStats> {
+    tokio::task::spawn_blocking(move || {
+        let open = |path: &Path| {
+            std::fs::File::open(path).with_context(|| format!("opening {}", path.display()))
+        };
+        let target_file = open(&target)?;
+        let target_size = target_file.metadata()?.len();
+        let out = std::fs::File::create(&output)
+            .with_context(|| format!("creating {}", output.display()))?;
+        let stats = create(open(&base)?, target_file, target_size, &out, block_size)
+            .with_context(|| format!("creating delta of {}", target.display()))?;
+        out.sync_all()?;
+        anyhow::Ok(stats)
+    })
+    .await
+    .context("delta task failed")?
+}
+
+/// Reconstruct `target` volume from `base` volume and `delta` file (stdin if none),
+/// and verify it has expected `size` and `sha256`
+///
+/// # Errors
+/// Fails if delta can't be applied, or reconstructed volume doesn't match
+pub async fn apply_file(
+    base: PathBuf,
+    delta: Option<PathBuf>,
+    target: PathBuf,
+    size: u64,
+    sha256: [u8; 32],
+) -> anyhow::Result<DeltaStats> {
+    let stats = tokio::task::spawn_blocking(move || {
+        let base_file = std::fs::File::open(&base)
+            .with_context(|| format!("opening base {}", base.display()))?;
+        let target_file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&target)
+            .with_context(|| format!("opening target {}", target.display()))?;
+        let mut writer = io::BufWriter::new(&target_file);
+        let stats = match &delta {
+            Some(path) => {
+                let file = std::fs::File::open(path)
+                    .with_context(|| format!("opening delta {}", path.display()))?;
+                apply(base_file, file, &mut writer)
+            }
+            None => apply(base_file, io::stdin().lock(), &mut writer),
+        }
+        .with_context(|| format!("applying delta to {}", target.display()))?;
+        drop(writer);
+        target_file.sync_all()?;
+        anyhow::Ok(stats)
+    })
+    .await
+    .context("delta task failed")??;
+
+    ensure!(
+        stats.target_size == size,
+        "Size mismatch of reconstructed volume: expected {size}, got {}",
+        stats.target_size
+    );
+    ensure!(
+        stats.target_sha256 == sha256,
+        "Checksum mismatch of reconstructed volume: expected {expected}, got {actual}",
+        expected = hex::encode(sha256),
+        actual = hex::encode(stats.target_sha256),
+    );
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn image(len: usize, seed: u8) -> Vec<u8> {
+        (0..len)
+            .map(|i| u8::try_from(i % 251).unwrap() ^ seed)
+            .collect()
+    }
+
+    fn roundtrip(base: &[u8], target: &[u8]) -> anyhow::Result<DeltaStats> {
+        let mut delta = Vec::new();
+        let created = create(base, target, target.len() as u64, &mut delta, 16)?;
+        let mut out = Vec::new();
+        let applied = apply(Cursor::new(base), delta.as_slice(), &mut out)?;
+        assert_eq!(out, target);
+        assert_eq!(created, applied);
+        assert_eq!(
+            applied.target_sha256,
+            <[u8; 32]>::from(Sha256::digest(target))
+        );
+        Ok(applied)
+    }
+
+    #[test]
+    fn changed_blocks_only() -> anyhow::Result<()> {
+        let base = image(16 * 10, 0);
+        let mut target = base.clone();
+        target[20] ^= 0xff;
+        target[130] ^= 0xff;
+        let stats = roundtrip(&base, &target)?;
+        assert_eq!((stats.copied_blocks, stats.data_blocks), (8, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn different_sizes() -> anyhow::Result<()> {
+        let base = image(16 * 4 + 5, 0);
+        // Grown, with partial last block
+        let mut grown = base.clone();
+        grown.extend(image(30, 1));
+        let stats = roundtrip(&base, &grown)?;
+        assert_eq!(stats.copied_blocks, 4);
+        // Shrunk, last block matches prefix of base block
+        let stats = roundtrip(&base, &base[..16 * 2 + 3])?;
+        assert_eq!((stats.copied_blocks, stats.data_blocks), (3, 0));
+        roundtrip(&base, &[])?;
+        Ok(())
+    }
+
+    #[test]
+    fn reject_mismatch() -> anyhow::Result<()> {
+        let base = image(64, 0);
+        let target = image(64, 0);
+        let mut delta = Vec::new();
+        create(base.as_slice(), target.as_slice(), 64, &mut delta, 16)?;
+
+        // Different base gives different checksum
+        let other = image(64, 3);
+        let stats = apply(Cursor::new(&other), delta.as_slice(), io::sink())?;
+        assert_ne!(
+            stats.target_sha256,
+            <[u8; 32]>::from(Sha256::digest(&target))
+        );
+
+        assert!(apply(Cursor::new(&base[..32]), delta.as_slice(), io::sink()).is_err());
+        assert!(apply(Cursor::new(&base), &delta[..delta.len() - 1], io::sink()).is_err());
+        assert!(apply(Cursor::new(&base), &b"garbage"[..], io::sink()).is_err());
+        Ok(())
+    }
+}
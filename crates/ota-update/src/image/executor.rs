@@ -2,12 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
+use async_channel::Sender;
 use std::process::Stdio;
 use tokio::process::Command;
 use tonic::async_trait;
 
 use super::pipeline::Pipeline;
 use super::plan::Plan;
+use super::writer::{WriteProgress, write_volume};
 
 #[async_trait]
 pub(crate) trait Executor {
@@ -52,7 +54,8 @@ impl Executor for ShellExecutor {
             .arg("-c")
             .arg(&cmdline)
             .stdin(Stdio::inherit())
-            .stdout(Stdio::inherit())
+            // Stdout belongs to progress events, pipeline output goes to stderr
+            .stdout(std::io::stderr())
             .stderr(Stdio::inherit())
             .status()
             .await?;
@@ -68,3 +71,46 @@ impl Executor for ShellExecutor {
         Ok(())
     }
 }
+
+/// Performs volume writes in-process (see `image::writer`), other steps via shell
+#[derive(Default)]
+pub struct NativeExecutor {
+    pub shell: ShellExecutor,
+    pub progress: Option<Sender<WriteProgress>>,
+}
+
+impl NativeExecutor {
+    #[must_use]
+    pub fn with_progress(mut self, progress: Sender<WriteProgress>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+}
+
+#[async_trait]
+impl Executor for NativeExecutor {
+    async fn run_pipeline(&self, pipeline: &Pipeline) -> Result<()> {
+        let Some(write) = pipeline.write.clone() else {
+            return self.shell.run_pipeline(pipeline).await;
+        };
+        let progress = self.progress.clone();
+        tokio::task::spawn_blocking(move || {
+            let total = match write.size {
+                Some(size) => Some(size),
+                None if !write.compressed => std::fs::metadata(&write.source).ok().map(|m| m.len()),
+                None => None,
+            };
+            write_volume(&write, |written| {
+                if let Some(tx) = &progress {
+                    let _ = tx.send_blocking(WriteProgress {
+                        target: write.target.clone(),
+                        written,
+                        total,
+                    });
+                }
+            })
+        })
+        .await??;
+        Ok(())
+    }
+}
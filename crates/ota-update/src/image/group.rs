@@ -4,7 +4,7 @@
 use super::Version;
 use super::runtime::KernelParams;
 use super::slot::{Kind, Slot, SlotClass};
-use super::uki::{BootEntry, UkiEntry};
+use super::uki::{Assessment, BootCounter, BootEntry, BootEntryKind, UkiEntry};
 use anyhow::{Result, ensure};
 
 #[derive(Debug, Default, Clone)]
@@ -233,6 +233,23 @@ impl SlotGroup {
             ..self.clone()
         })
     }
+
+    /// Request boot assessment of attached UKI, with `tries` boot attempts
+    pub(crate) fn set_boot_tries(&mut self, tries: u32) {
+        if let Some(BootEntry {
+            kind: BootEntryKind::Managed(uki),
+            ..
+        }) = &mut self.boot
+        {
+            uki.boot_counter = Some(BootCounter::new(tries));
+        }
+    }
+
+    /// Boot assessment state of UKI, `None` if slot has no managed UKI
+    #[must_use]
+    pub fn assessment(&self) -> Option<Assessment> {
+        self.boot.as_ref().and_then(BootEntry::assessment)
+    }
 }
 
 #[cfg(test)]
@@ -3,53 +3,191 @@
 
 use std::path::Path;
 
-use anyhow::Context;
+use anyhow::{Context, ensure};
+use sha2::{Digest, Sha256};
 use tokio::fs::read_to_string;
+use tracing::info;
 
-use super::executor::{DryRunExecutor, Executor, ShellExecutor};
+use super::executor::{DryRunExecutor, Executor, NativeExecutor};
 use super::lvm::read_lvs_output;
-use super::manifest::Manifest;
-use super::plan::Plan;
+use super::manifest::{Delta, Manifest};
+use super::plan::{Plan, ReviewedPlan};
 use super::runtime::Runtime;
+use super::signature::Verification;
+use super::version::Version;
+use super::writer::WriteProgress;
 use crate::bootctl::get_bootctl_info;
 use crate::lock::UpdateLock;
 
 pub(crate) async fn install_from_manifest_path(
     manifest_path: &Path,
+    verification: &Verification,
     validate: bool,
+    boot_tries: Option<u32>,
     dry_run: bool,
 ) -> anyhow::Result<()> {
     let rt = populate_runtime().await?;
+    let plan =
+        plan_from_manifest_path(&rt, manifest_path, verification, validate, boot_tries).await?;
+    execute_plan(
+        plan.map(|reviewed| reviewed.plan.into())
+            .unwrap_or_default(),
+        dry_run,
+    )
+    .await
+}
+
+/// Print install plan of manifest as JSON, or `null` if it is already installed
+pub(crate) async fn print_install_plan(
+    manifest_path: &Path,
+    verification: &Verification,
+    validate: bool,
+    boot_tries: Option<u32>,
+) -> anyhow::Result<()> {
+    let rt = populate_runtime().await?;
+    let plan =
+        plan_from_manifest_path(&rt, manifest_path, verification, validate, boot_tries).await?;
+    println!("{}", serde_json::to_string_pretty(&plan)?);
+    Ok(())
+}
+
+/// Execute plan, previously printed by `print_install_plan()`. Plan is made again
+/// from its manifest, which signature is verified again, and executed only if it is
+/// the same as reviewed one, so nothing from plan file itself is trusted.
+pub(crate) async fn apply_plan_file(
+    plan_path: &Path,
+    verification: &Verification,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let reviewed: ReviewedPlan = serde_json::from_str(
+        &read_to_string(plan_path)
+            .await
+            .with_context(|| format!("reading plan {}", plan_path.display()))?,
+    )
+    .with_context(|| format!("parsing plan {}", plan_path.display()))?;
+    let rt = populate_runtime().await?;
+    reviewed
+        .plan
+        .check_runtime(&rt)
+        .context("plan is outdated, it has to be made and reviewed again")?;
+    let current = plan_from_manifest_path(
+        &rt,
+        &reviewed.manifest,
+        verification,
+        false,
+        reviewed.boot_tries,
+    )
+    .await?
+    .context("image of plan is already installed")?;
+    ensure!(
+        current.manifest_sha256 == reviewed.manifest_sha256,
+        "manifest {} changed since plan was made",
+        reviewed.manifest.display()
+    );
+    ensure!(
+        current == reviewed,
+        "plan differs from one made from manifest {}, it has to be made and reviewed again",
+        reviewed.manifest.display()
+    );
+    info!("Applying plan of {} installation", current.plan.version);
+    execute_plan(current.plan.into(), dry_run).await
+}
+
+async fn plan_from_manifest_path(
+    rt: &Runtime,
+    manifest_path: &Path,
+    verification: &Verification,
+    validate: bool,
+    boot_tries: Option<u32>,
+) -> anyhow::Result<Option<ReviewedPlan>> {
+    let manifest_path = tokio::fs::canonicalize(manifest_path)
+        .await
+        .with_context(|| format!("resolving manifest path {}", manifest_path.display()))?;
     let source_dir = manifest_path
         .parent()
         .context("manifest path has no parent directory")?;
 
-    let manifest = Manifest::from_file(manifest_path)?;
+    let content = tokio::fs::read(&manifest_path)
+        .await
+        .with_context(|| format!("reading manifest {}", manifest_path.display()))?;
+    let manifest = Manifest::from_slice(&content)?;
+    verification.verify(&manifest, &manifest_path).await?;
+    let delta = select_delta(rt, &manifest, source_dir).await?;
     manifest
-        .validate(source_dir, validate)
+        .validate(source_dir, validate, delta)
         .await
         .context("while validating manifest content")?;
-    let plan = Plan::install(&rt, &manifest, source_dir)?;
+    let plan = Plan::install_plan(rt, &manifest, source_dir, delta, boot_tries)?;
+    Ok(plan.map(|plan| ReviewedPlan {
+        manifest_sha256: Sha256::digest(&content).into(),
+        manifest: manifest_path.clone(),
+        boot_tries,
+        plan,
+    }))
+}
 
-    execute_plan(plan, dry_run).await
+/// Delta against active slot, if manifest has one and its files are present
+async fn select_delta<'a>(
+    rt: &Runtime,
+    manifest: &'a Manifest,
+    source_dir: &Path,
+) -> anyhow::Result<Option<&'a Delta>> {
+    let Some(delta) = rt
+        .active_slot()
+        .ok()
+        .and_then(|slot| slot.version())
+        .and_then(|version| manifest.delta_for(version))
+    else {
+        return Ok(None);
+    };
+    for file in [&delta.root.file, &delta.verity.file] {
+        if !tokio::fs::try_exists(file.full_name(source_dir)).await? {
+            info!(
+                "Delta file {} is missing, installing full images",
+                file.name
+            );
+            return Ok(None);
+        }
+    }
+    info!("Installing delta against {:#}", delta.base());
+    Ok(Some(delta))
 }
 
-/// Checks if a manifest at `manifest_path` is valid
+/// Checks if a manifest at `manifest_path` is properly signed and valid
 ///
 /// # Errors
-/// Fails if the path is invalid, cannot be loaded or is not valid
-pub async fn validate_manifest_path(manifest_path: &Path) -> anyhow::Result<()> {
+/// Fails if the path is invalid, cannot be loaded, its signature isn't trusted or
+/// it is not valid
+pub async fn validate_manifest_path(
+    manifest_path: &Path,
+    verification: &Verification,
+) -> anyhow::Result<()> {
     let source_dir = manifest_path
         .parent()
         .context("manifest path has no parent directory")?;
 
     let manifest = Manifest::from_file(manifest_path)?;
+    verification.verify(&manifest, manifest_path).await?;
     manifest
-        .validate(source_dir, true)
+        .validate(source_dir, true, None)
         .await
         .context("while validating manifest content")
 }
 
+/// Switch default boot entry to slot of `version`, or the only inactive one.
+/// Returns version of the slot.
+///
+/// # Errors
+/// Fails if there is no (unambiguous) slot to roll back to, or bootctl fails
+pub async fn rollback_image(version: Option<Version>, dry_run: bool) -> anyhow::Result<Version> {
+    let rt = populate_runtime().await?;
+    let slot = rt.rollback_slot(version.as_ref())?;
+    let plan = Plan::rollback(&rt, slot)?;
+    let target = slot.version().cloned().context("slot has no version")?;
+    execute_plan(plan, dry_run).await?;
+    Ok(target)
+}
+
 pub(crate) async fn populate_runtime() -> anyhow::Result<Runtime> {
     let cmdline = read_to_string("/proc/cmdline")
         .await
@@ -71,9 +209,32 @@ pub(crate) async fn execute_plan(plan: Plan, dry_run: bool) -> anyhow::Result<()
         let exec = DryRunExecutor;
         exec.run_plan(&plan).await?;
     } else {
-        let exec = ShellExecutor::default();
-        exec.run_plan(&plan).await?;
+        let (tx, rx) = async_channel::unbounded();
+        let printer = tokio::spawn(write_progress_printer(rx));
+        let exec = NativeExecutor::default().with_progress(tx);
+        let result = exec.run_plan(&plan).await;
+        drop(exec);
+        printer.await?;
+        result?;
     }
 
     Ok(())
 }
+
+async fn write_progress_printer(rx: async_channel::Receiver<WriteProgress>) {
+    while let Ok(progress) = rx.recv().await {
+        match progress.total {
+            Some(total) => println!(
+                "{}: {} of {} bytes written",
+                progress.target.display(),
+                progress.written,
+                total
+            ),
+            None => println!(
+                "{}: {} bytes written",
+                progress.target.display(),
+                progress.written
+            ),
+        }
+    }
+}
@@ -118,14 +118,14 @@ mod tests {
   }
         "#;
 
-        let volumes = parse_lvs_json(&output).unwrap();
+        let volumes = parse_lvs_json(output).unwrap();
         assert_eq!(volumes.len(), 3);
 
         let (slots, _) = Slot::from_volumes(volumes);
         assert_eq!(slots.len(), 2);
         assert_eq!(slots[0].kind(), Kind::Root);
         assert_eq!(
-            slots[0].version().as_deref(),
+            slots[0].version(),
             Some(&Version::new("1.2.3".into(), Some("deadbeef".into())))
         );
     }
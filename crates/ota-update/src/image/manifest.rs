@@ -13,7 +13,7 @@ use super::Version;
 use super::checksum::read_sha256;
 
 #[serde_as]
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct File {
     #[serde(rename = "file")]
     pub name: String,
@@ -26,7 +26,36 @@ pub struct File {
     pub unpacked_size: Option<u64>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Block-level delta of volume image, see `image::delta`
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DeltaFile {
+    #[serde(flatten)]
+    pub file: File,
+    /// Checksum of reconstructed volume image
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub target_sha256: [u8; 32],
+    pub target_size: u64,
+}
+
+/// Deltas of root and verity images against slot of base version
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Delta {
+    pub base_version: String,
+    /// Hash fragment of base slot, as in its volume names
+    pub base_hash: String,
+    pub root: DeltaFile,
+    pub verity: DeltaFile,
+}
+
+impl Delta {
+    #[must_use]
+    pub fn base(&self) -> Version {
+        Version::new(self.base_version.clone(), Some(self.base_hash.clone()))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Manifest {
     pub meta: HashMap<String, String>,
     #[serde(default)]
@@ -38,6 +67,8 @@ pub struct Manifest {
     #[serde(rename = "root")]
     pub store: File,
     pub verity: File,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deltas: Vec<Delta>,
 }
 
 impl Manifest {
@@ -57,10 +88,24 @@ impl Manifest {
             .with_context(|| format!("writing manifest to {}", filename.display()))
     }
 
+    /// Canonical form, which signatures are made over: normalized paths, sorted
+    /// keys, compact JSON
+    pub(crate) fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut this = self.clone();
+        this.normalize_paths()?;
+        let mut value = serde_json::to_value(&this).context("Serializing manifest")?;
+        value.sort_all_objects();
+        serde_json::to_vec(&value).context("Serializing manifest")
+    }
+
     pub(crate) fn normalize_paths(&mut self) -> anyhow::Result<()> {
         self.kernel.normalize_path()?;
         self.store.normalize_path()?;
         self.verity.normalize_path()?;
+        for delta in &mut self.deltas {
+            delta.root.file.normalize_path()?;
+            delta.verity.file.normalize_path()?;
+        }
         Ok(())
     }
 
@@ -74,17 +119,36 @@ impl Manifest {
         Version::new(self.version.clone(), Some(self.hash_fragment().to_string()))
     }
 
-    // Validate, if all files mentioned in manifest exists (and have matching hash)
-    pub(crate) async fn validate(&self, base_dir: &Path, checksum: bool) -> anyhow::Result<()> {
+    /// Delta, which can be applied to slot of `base` version
+    #[must_use]
+    pub fn delta_for(&self, base: &Version) -> Option<&Delta> {
+        self.deltas.iter().find(|delta| {
+            delta.base_version == base.revision
+                && base.hash_as_ref() == Some(delta.base_hash.as_str())
+        })
+    }
+
+    // Validate, if all files mentioned in manifest exists (and have matching hash).
+    // With `delta`, its files are validated instead of full root and verity images.
+    pub(crate) async fn validate(
+        &self,
+        base_dir: &Path,
+        checksum: bool,
+        delta: Option<&Delta>,
+    ) -> anyhow::Result<()> {
         self.kernel
             .validate(base_dir, checksum)
             .await
             .context("while validating kernel")?;
-        self.store
+        let (store, verity) = match delta {
+            Some(delta) => (&delta.root.file, &delta.verity.file),
+            None => (&self.store, &self.verity),
+        };
+        store
             .validate(base_dir, checksum)
             .await
             .context("while validating store image")?;
-        self.verity
+        verity
             .validate(base_dir, checksum)
             .await
             .context("while validating verity image")?;
@@ -110,7 +174,7 @@ impl File {
         Ok(())
     }
 
-    async fn validate(&self, base_dir: &Path, checksum: bool) -> anyhow::Result<()> {
+    pub(crate) async fn validate(&self, base_dir: &Path, checksum: bool) -> anyhow::Result<()> {
         let full_name = self.full_name(base_dir);
         if !tokio::fs::try_exists(&full_name).await? {
             anyhow::bail!("Missing file {full_name}", full_name = full_name.display())
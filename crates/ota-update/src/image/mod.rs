@@ -3,6 +3,7 @@
 
 pub(crate) mod checksum;
 pub mod cli;
+pub mod delta;
 pub mod executor;
 pub mod group;
 pub mod install;
@@ -11,9 +12,11 @@ pub mod manifest;
 pub mod pipeline;
 pub mod plan;
 pub mod runtime;
+pub mod signature;
 pub mod slot;
 pub mod uki;
 pub mod version;
+pub mod writer;
 
 pub use version::Version;
 
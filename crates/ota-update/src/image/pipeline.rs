@@ -1,18 +1,24 @@
 // SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use serde::{Deserialize, Serialize};
 use shell_escape::escape;
 use std::path::Path;
 
-#[derive(Debug, Clone, PartialEq)]
+use super::writer::VolumeWrite;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CommandSpec {
     pub program: String,
     pub args: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Pipeline {
     pub stages: Vec<CommandSpec>,
+    /// Image write, which executor may perform in-process instead of running stages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write: Option<VolumeWrite>,
 }
 
 impl CommandSpec {
@@ -58,9 +64,16 @@ impl Pipeline {
     pub fn new(first: CommandSpec) -> Self {
         Self {
             stages: vec![first],
+            write: None,
         }
     }
 
+    #[must_use]
+    pub fn with_write(mut self, write: VolumeWrite) -> Self {
+        self.write = Some(write);
+        self
+    }
+
     #[must_use]
     pub fn pipe(mut self, next: CommandSpec) -> Self {
         self.stages.push(next);
@@ -4,32 +4,126 @@
 use super::Version;
 use super::group::SlotGroup;
 use super::lvm::Volume;
-use super::manifest::{File, Manifest};
+use super::manifest::{Delta, DeltaFile, File, Manifest};
 use super::pipeline::{CommandSpec, Pipeline};
-use super::runtime::{Runtime, SlotSelection};
-use anyhow::{Context, bail};
-use std::path::Path;
-
-#[derive(Debug, Clone, PartialEq)]
+use super::runtime::{Runtime, RuntimeSnapshot, SlotGroupSnapshot, SlotSelection};
+use super::uki::{Assessment, BootEntry};
+use super::writer::VolumeWrite;
+use anyhow::{Context, bail, ensure};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Plan {
     pub steps: Vec<Pipeline>,
 }
 
+/// Install plan in reviewable form, see `ota-update image install --plan-json`.
+/// Steps are executed in order of fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstallPlan {
+    /// Version being installed
+    pub version: String,
+    /// Slot, which image is installed into
+    pub slot: SlotGroupSnapshot,
+    /// Creation or resizing of slot volumes
+    pub pre_steps: Vec<Pipeline>,
+    /// Writes of volume images
+    pub pipelines: Vec<Pipeline>,
+    /// Renames of written volumes to new version
+    pub renames: Vec<Pipeline>,
+    /// Installation of UKI and boot loader changes
+    pub boot_steps: Vec<Pipeline>,
+    /// Runtime, which plan was made against
+    pub runtime: RuntimeSnapshot,
+}
+
+/// Install plan with manifest it was made from, as printed for review by
+/// `ota-update image install --plan-json`. Steps in reviewed file are never executed:
+/// on apply plan is made again from verified manifest, and it must equal reviewed one.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewedPlan {
+    /// Absolute path of manifest
+    pub manifest: PathBuf,
+    /// Checksum of manifest file
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub manifest_sha256: [u8; 32],
+    /// Boot attempts of installed slot, if it is assessed
+    pub boot_tries: Option<u32>,
+    #[serde(flatten)]
+    pub plan: InstallPlan,
+}
+
+impl From<InstallPlan> for Plan {
+    fn from(plan: InstallPlan) -> Self {
+        Plan {
+            steps: [
+                plan.pre_steps,
+                plan.pipelines,
+                plan.renames,
+                plan.boot_steps,
+            ]
+            .concat(),
+        }
+    }
+}
+
+impl InstallPlan {
+    /// Ensure runtime is still in state, which plan was made against
+    ///
+    /// # Errors
+    /// Fails if slot groups or boot entries have changed
+    pub fn check_runtime(&self, rt: &Runtime) -> anyhow::Result<()> {
+        let current = rt.snapshot();
+        ensure!(
+            current.active == self.runtime.active,
+            "active version changed since plan was made: planned {:?}, now {:?}",
+            self.runtime.active,
+            current.active
+        );
+        ensure!(
+            current.slot_groups == self.runtime.slot_groups,
+            "slot groups changed since plan was made"
+        );
+        ensure!(
+            current.boot_entries == self.runtime.boot_entries,
+            "boot entries changed since plan was made"
+        );
+        Ok(())
+    }
+}
+
 impl Plan {
-    pub(crate) fn install(rt: &Runtime, m: &Manifest, source: &Path) -> anyhow::Result<Self> {
+    /// Install image into free slot, from full images or `delta` against active slot.
+    /// With `boot_tries` slot is pending, until `systemd-bless-boot.service` or
+    /// `Plan::confirm()` confirms it after successful boot. `None` if image is already installed
+    pub(crate) fn install_plan(
+        rt: &Runtime,
+        m: &Manifest,
+        source: &Path,
+        delta: Option<&Delta>,
+        boot_tries: Option<u32>,
+    ) -> anyhow::Result<Option<InstallPlan>> {
         let selection = rt.select_update_slot(m)?;
 
         match selection {
             SlotSelection::AlreadyInstalled => {
                 // nothing to do
-                Ok(Plan { steps: vec![] })
+                Ok(None)
             }
 
-            SlotSelection::Selected { slot, pre_steps } => {
-                let mut plan = Plan::install_into_slot(rt, m, &slot, source)?;
-                // Prepend lvcreate steps (if any) before the dd/rename steps
-                plan.steps.splice(0..0, pre_steps);
-                Ok(plan)
+            SlotSelection::Selected {
+                mut slot,
+                pre_steps,
+            } => {
+                if let Some(tries) = boot_tries {
+                    slot.set_boot_tries(tries);
+                }
+                let plan = Plan::install_into_slot(rt, m, &slot, source, delta)?;
+                // lvcreate steps (if any) run before the dd/rename steps
+                Ok(Some(InstallPlan { pre_steps, ..plan }))
             }
         }
     }
@@ -39,33 +133,80 @@ impl Plan {
         m: &Manifest,
         slot: &SlotGroup,
         source: &Path,
-    ) -> anyhow::Result<Self> {
+        delta: Option<&Delta>,
+    ) -> anyhow::Result<InstallPlan> {
         let mut steps = Vec::new();
 
         let root = slot.root.as_ref().context("slot has no root volume")?;
         let verity = slot.verity.as_ref().context("slot has no verity volume")?;
 
-        steps.push(Self::install_volume(root.volume(), &m.store, source));
-        steps.push(Self::install_volume(verity.volume(), &m.verity, source));
+        if let Some(delta) = delta {
+            let base = rt.active_slot()?;
+            ensure!(
+                base.version() == Some(&delta.base()),
+                "delta is made against {:#}, not active slot",
+                delta.base()
+            );
+            let base_root = base
+                .root
+                .as_ref()
+                .context("active slot has no root volume")?;
+            let base_verity = base
+                .verity
+                .as_ref()
+                .context("active slot has no verity volume")?;
+            steps.push(Self::apply_delta(
+                base_root.volume(),
+                root.volume(),
+                &delta.root,
+                source,
+            ));
+            steps.push(Self::apply_delta(
+                base_verity.volume(),
+                verity.volume(),
+                &delta.verity,
+                source,
+            ));
+        } else {
+            steps.push(Self::install_volume(root.volume(), &m.store, source));
+            steps.push(Self::install_volume(verity.volume(), &m.verity, source));
+        }
         steps.push(Self::finalize_flush(root.volume()));
         steps.push(Self::finalize_flush(verity.volume()));
 
         // FIXME: clone!
-        steps.push(root.clone().into_version(m.to_version())?.rename());
-        steps.push(verity.clone().into_version(m.to_version())?.rename());
-        steps.push(Self::install_uki(slot, &m.kernel, &rt.boot, source)?);
+        let renames = vec![
+            root.clone().into_version(m.to_version())?.rename(),
+            verity.clone().into_version(m.to_version())?.rename(),
+        ];
+        let mut boot_steps = vec![Self::install_uki(slot, &m.kernel, &rt.boot, source)?];
         if rt.active_slot()?.is_legacy() {
-            steps.extend(Self::legacy_bootloader_migration(rt));
+            boot_steps.extend(Self::legacy_bootloader_migration(rt));
         }
 
-        Ok(Plan { steps })
+        Ok(InstallPlan {
+            version: format!("{:#}", m.to_version()),
+            slot: slot.into(),
+            pre_steps: Vec::new(),
+            pipelines: steps,
+            renames,
+            boot_steps,
+            runtime: rt.snapshot(),
+        })
     }
 
     fn install_volume(volume: &Volume, file: &File, source: &Path) -> Pipeline {
         let target = volume.device_file_string();
         let input = file.full_name(source);
+        let write = VolumeWrite {
+            source: input.clone(),
+            target: target.clone().into(),
+            compressed: file.is_compressed(),
+            sha256: file.sha256sum,
+            size: file.unpacked_size.filter(|_| file.is_compressed()),
+        };
 
-        if file.is_compressed() {
+        let pipeline = if file.is_compressed() {
             Pipeline::new(CommandSpec::new("zstdcat").arg_path(input)).pipe(
                 CommandSpec::new("dd")
                     .arg(format!("of={target}"))
@@ -80,6 +221,25 @@ impl Plan {
                     .arg("bs=4M")
                     .arg("status=progress"),
             )
+        };
+        pipeline.with_write(write)
+    }
+
+    /// Reconstruct `volume` from `base` volume and delta, verifying its checksum
+    fn apply_delta(base: &Volume, volume: &Volume, delta: &DeltaFile, source: &Path) -> Pipeline {
+        let input = delta.file.full_name(source);
+        let apply = CommandSpec::new("ota-update")
+            .arg("image")
+            .arg("apply-delta")
+            .args(["--base", &base.device_file_string()])
+            .args(["--target", &volume.device_file_string()])
+            .args(["--size", &delta.target_size.to_string()])
+            .args(["--sha256", &hex::encode(delta.target_sha256)]);
+
+        if delta.file.is_compressed() {
+            Pipeline::new(CommandSpec::new("zstdcat").arg_path(input)).pipe(apply)
+        } else {
+            apply.arg("--delta").arg_path(input).into()
         }
     }
 
@@ -141,14 +301,20 @@ impl Plan {
             bail!("cannot remove active slot");
         }
 
-        let mut steps = Vec::new();
-
         // Full slot: rename to empty
         let empty_id = match slot.empty_id() {
             Some(h) if !rt.has_empty_with_hash(h) => h.to_string(),
             _ => rt.allocate_empty_identifier()?,
         };
 
+        Ok(Plan {
+            steps: Self::demote_slot(rt, slot, &empty_id),
+        })
+    }
+
+    fn demote_slot(rt: &Runtime, slot: &SlotGroup, empty_id: &str) -> Vec<Pipeline> {
+        let mut steps = Vec::new();
+
         // Remove UKI if present
         if let Some(boot) = &slot.boot {
             steps.push(boot.to_remove());
@@ -161,11 +327,75 @@ impl Plan {
             }
         }
 
-        steps.extend(Self::rename_slot_to_empty(slot, &empty_id));
+        steps.extend(Self::rename_slot_to_empty(slot, empty_id));
+        steps
+    }
+
+    /// Bless UKI of active slot after successful boot, removing its boot counters
+    pub(crate) fn confirm(rt: &Runtime) -> anyhow::Result<Self> {
+        let active = rt.active_slot()?;
+        let Some(uki) = active.boot.as_ref().and_then(BootEntry::uki) else {
+            // Legacy slot, nothing to assess
+            return Ok(Plan { steps: vec![] });
+        };
+        if uki.boot_counter.is_none() {
+            return Ok(Plan { steps: vec![] });
+        }
 
+        let dir = format!("{}/EFI/Linux", rt.boot);
+        Ok(Plan {
+            steps: vec![
+                CommandSpec::new("mv")
+                    .arg_path(uki.full_name(&dir))
+                    .arg_path(uki.blessed().full_name(&dir))
+                    .into(),
+            ],
+        })
+    }
+
+    /// Demote inactive slots, which used all boot attempts without being confirmed,
+    /// and restore boot entry of active slot as default
+    pub(crate) fn assess(rt: &Runtime) -> anyhow::Result<Self> {
+        let mut steps = Vec::new();
+        let mut reserved = Vec::new();
+        for slot in rt.slot_groups() {
+            if slot.is_active(&rt.kernel) || slot.assessment() != Some(Assessment::Bad) {
+                continue;
+            }
+            let empty_id = rt.allocate_empty_identifier_except(&reserved)?;
+            steps.extend(Self::demote_slot(rt, slot, &empty_id));
+            reserved.push(empty_id);
+        }
+
+        if !steps.is_empty() {
+            let boot = rt
+                .active_boot_entry()
+                .context("cannot determine boot entry of active slot")?;
+            steps.push(boot.to_set_default());
+        }
         Ok(Plan { steps })
     }
 
+    /// Make `slot` default boot entry
+    pub(crate) fn rollback(rt: &Runtime, slot: &SlotGroup) -> anyhow::Result<Self> {
+        let version = slot.version().context("slot has no version")?;
+
+        if slot.is_active(&rt.kernel) {
+            bail!("slot {version} is already active");
+        }
+        if !slot.is_complete() {
+            bail!("slot {version} is incomplete");
+        }
+        let boot = slot
+            .boot
+            .as_ref()
+            .with_context(|| format!("slot {version} has no boot entry"))?;
+
+        Ok(Plan {
+            steps: vec![boot.to_set_default()],
+        })
+    }
+
     fn rename_slot_to_empty(slot: &SlotGroup, empty_id: &str) -> Vec<Pipeline> {
         let mut steps = Vec::new();
 
@@ -195,6 +425,18 @@ impl Plan {
 
 #[cfg(test)]
 impl Plan {
+    fn install(
+        rt: &Runtime,
+        m: &Manifest,
+        source: &Path,
+        delta: Option<&Delta>,
+        boot_tries: Option<u32>,
+    ) -> anyhow::Result<Self> {
+        Ok(Self::install_plan(rt, m, source, delta, boot_tries)?
+            .map(Into::into)
+            .unwrap_or_default())
+    }
+
     fn into_script(self) -> Vec<String> {
         self.steps
             .into_iter()
@@ -225,10 +467,139 @@ mod tests {
             "bootctl set-default auto",
         ];
 
-        let plan = Plan::install(&rt, &m, &Path::new("/sysupdate")).expect("install failed");
+        let plan =
+            Plan::install(&rt, &m, Path::new("/sysupdate"), None, None).expect("install failed");
+        let write = plan.steps[0].write.as_ref().expect("volume write");
+        assert_eq!(write.target, Path::new("/dev/mapper/pool-root_empty"));
+        assert_eq!(write.sha256, m.store.sha256sum);
+        assert!(write.compressed);
+        assert!(plan.steps[2].write.is_none());
         assert_eq!(plan.into_script(), expected)
     }
 
+    #[test]
+    fn install_plan_review() -> anyhow::Result<()> {
+        let rt = make_test_runtime();
+        let m = make_test_manifest();
+        let source = Path::new("/sysupdate");
+        let plan = Plan::install_plan(&rt, &m, source, None, None)?.expect("nothing to install");
+        assert_eq!(plan.version, "25.12.1-44cc41b403a2d323");
+        assert_eq!(plan.renames.len(), 2);
+
+        let plan = ReviewedPlan {
+            manifest: "/sysupdate/manifest.json".into(),
+            manifest_sha256: [7; 32],
+            boot_tries: None,
+            plan,
+        };
+        let json = serde_json::to_value(&plan)?;
+        assert_eq!(json["manifest_sha256"], hex::encode([7; 32]));
+        assert_eq!(json["version"], "25.12.1-44cc41b403a2d323");
+
+        let reviewed: ReviewedPlan = serde_json::from_value(json)?;
+        assert_eq!(reviewed, plan);
+        reviewed.plan.check_runtime(&rt)?;
+        assert!(
+            reviewed
+                .plan
+                .check_runtime(&make_test_runtime_installed())
+                .is_err()
+        );
+        assert_eq!(
+            Plan::from(reviewed.plan),
+            Plan::install(&rt, &m, source, None, None)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn install_pending() {
+        let rt = make_test_runtime();
+        let m = make_test_manifest();
+        let plan =
+            Plan::install(&rt, &m, Path::new("/sysupdate"), None, Some(3)).expect("install failed");
+        let script = plan.into_script();
+        assert!(script.contains(&"install -m 0644 /sysupdate/ghaf_kernel_25.12.1_44cc41b403a2d323.efi /boot/EFI/Linux/ghaf-25.12.1-44cc41b403a2d323+3.efi".to_string()));
+    }
+
+    #[test]
+    fn install_delta() {
+        let rt = make_test_runtime_counted(BOOTED_CMDLINE, "");
+        let mut m = manifest("25.12.2", "cafebabecafebabecafebabe");
+        let delta_file = |name: &str| DeltaFile {
+            file: File {
+                name: name.into(),
+                sha256sum: [0; 32],
+                unpacked_size: None,
+            },
+            target_sha256: [0xab; 32],
+            target_size: 4096,
+        };
+        m.deltas.push(Delta {
+            base_version: "25.12.1".into(),
+            base_hash: "deadbeefdeadbeef".into(),
+            root: delta_file("root.delta.zst"),
+            verity: delta_file("verity.delta"),
+        });
+        let delta = m
+            .delta_for(&Version::new(
+                "25.12.1".into(),
+                Some("deadbeefdeadbeef".into()),
+            ))
+            .expect("delta not found");
+        let sha = "ab".repeat(32);
+        let expected = &[
+            format!(
+                "zstdcat /sysupdate/root.delta.zst | ota-update image apply-delta --base /dev/mapper/pool-root_25.12.1_deadbeefdeadbeef --target /dev/mapper/pool-root_empty_0 --size 4096 --sha256 {sha}"
+            ),
+            format!(
+                "ota-update image apply-delta --base /dev/mapper/pool-verity_25.12.1_deadbeefdeadbeef --target /dev/mapper/pool-verity_empty_0 --size 4096 --sha256 {sha} --delta /sysupdate/verity.delta"
+            ),
+        ];
+        let plan = Plan::install(&rt, &m, Path::new("/sysupdate"), Some(delta), None)
+            .expect("install failed");
+        let script = plan.into_script();
+        // After lvcreate of missing empty slot
+        assert_eq!(script[2..4], *expected);
+
+        // Delta against other version is refused
+        let mut other = delta.clone();
+        other.base_version = "25.12.0".into();
+        assert!(Plan::install(&rt, &m, Path::new("/sysupdate"), Some(&other), None).is_err());
+    }
+
+    #[test]
+    fn confirm() {
+        let rt = make_test_runtime_counted(BOOTED_CMDLINE, "+2-1");
+        let expected = &[
+            "mv /boot/EFI/Linux/ghaf-25.12.1-deadbeefdeadbeef+2-1.efi /boot/EFI/Linux/ghaf-25.12.1-deadbeefdeadbeef.efi",
+        ];
+        let plan = Plan::confirm(&rt).expect("confirm failed");
+        assert_eq!(plan.into_script(), expected);
+
+        // Already blessed
+        let rt = make_test_runtime_counted(BOOTED_CMDLINE, "");
+        assert!(Plan::confirm(&rt).expect("confirm failed").steps.is_empty());
+    }
+
+    #[test]
+    fn assess() {
+        // Fallen back to legacy slot, after new one exhausted its tries
+        let rt = make_test_runtime_counted("root=fstab", "+0-3");
+        let expected = &[
+            "bootctl unlink ghaf-25.12.1-deadbeefdeadbeef.efi",
+            "lvrename pool root_25.12.1_deadbeefdeadbeef root_empty_0",
+            "lvrename pool verity_25.12.1_deadbeefdeadbeef verity_empty_0",
+            "bootctl set-default nixos-generation-1.conf",
+        ];
+        let plan = Plan::assess(&rt).expect("assess failed");
+        assert_eq!(plan.into_script(), expected);
+
+        // Pending slot still have tries left
+        let rt = make_test_runtime_counted("root=fstab", "+1-2");
+        assert!(Plan::assess(&rt).expect("assess failed").steps.is_empty());
+    }
+
     #[test]
     fn remove() {
         let rt = make_test_runtime_installed();
@@ -245,6 +616,22 @@ mod tests {
         assert_eq!(plan.into_script(), expected);
     }
 
+    #[test]
+    fn rollback() {
+        let rt = make_test_runtime_installed();
+        let expected = &["bootctl set-default ghaf-25.12.1-deadbeefdeadbeef.efi"];
+        let slot = rt.rollback_slot(None).expect("no slot to roll back");
+        let plan = Plan::rollback(&rt, slot).expect("rollback failed");
+        assert_eq!(plan.into_script(), expected);
+
+        // Legacy slot have no managed boot entry
+        let version = Version::new("0".into(), None);
+        let slot = rt
+            .rollback_slot(Some(&version))
+            .expect("legacy slot not found");
+        assert!(Plan::rollback(&rt, slot).is_err());
+    }
+
     #[test]
     fn remove_legacy() {
         let rt = make_test_runtime_installed();
@@ -10,6 +10,7 @@ use super::slot::{Slot, SlotClass};
 use super::uki::{BootEntry, BootEntryKind, UkiEntry};
 use crate::bootctl::BootctlItem;
 use anyhow::{Context, Result, bail, ensure};
+use serde::{Deserialize, Serialize};
 use std::fmt::Write;
 
 #[derive(Debug)]
@@ -26,6 +27,7 @@ pub struct Runtime {
 
 /// Well-known params from kernel /proc/cmdline
 #[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Default))]
 pub struct KernelParams {
     store_hash: Option<String>,
     pub revision: Option<String>,
@@ -42,6 +44,38 @@ pub enum SlotSelection {
     },
 }
 
+/// Slot group, as volume names and boot entry, see `Runtime::snapshot()`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotGroupSnapshot {
+    pub root: Option<String>,
+    pub verity: Option<String>,
+    pub boot: Option<String>,
+}
+
+/// State of runtime, which reviewed plan is only valid against
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeSnapshot {
+    /// Version of running system
+    pub active: Option<String>,
+    pub slot_groups: Vec<SlotGroupSnapshot>,
+    /// Boot entries not belonging to any slot group
+    pub boot_entries: Vec<String>,
+}
+
+impl From<&SlotGroup> for SlotGroupSnapshot {
+    fn from(group: &SlotGroup) -> Self {
+        let volume = |slot: &Slot| {
+            let volume = slot.volume();
+            format!("{}/{}", volume.vg_name, volume.lv_name)
+        };
+        Self {
+            root: group.root.as_ref().map(volume),
+            verity: group.verity.as_ref().map(volume),
+            boot: group.boot.as_ref().map(ToString::to_string),
+        }
+    }
+}
+
 impl SlotSelection {
     #[must_use]
     pub fn is_none(&self) -> bool {
@@ -263,6 +297,35 @@ impl Runtime {
             .context("no active slot detected")
     }
 
+    /// Slot to roll back to: slot of `version` if given, otherwise the only
+    /// complete, bootable slot except active one
+    pub(crate) fn rollback_slot(&self, version: Option<&Version>) -> Result<&SlotGroup> {
+        if let Some(version) = version {
+            return self.find_slot_group(version);
+        }
+        let kernel = &self.kernel;
+        self.find_exact_one_group(
+            |slot| {
+                slot.is_used()
+                    && slot.is_complete()
+                    && slot.boot.is_some()
+                    && !slot.is_active(kernel)
+            },
+            "rollback (specify version)",
+        )?
+        .context("no previous slot to roll back to")
+    }
+
+    /// Boot entry of active slot: its UKI, or legacy entry for legacy slot
+    pub(crate) fn active_boot_entry(&self) -> Option<&BootEntry> {
+        let active = self.active_slot().ok()?;
+        if active.is_legacy() {
+            self.boot_entries.iter().find(|boot| boot.is_legacy())
+        } else {
+            active.boot.as_ref()
+        }
+    }
+
     #[must_use]
     pub fn has_empty_with_hash(&self, hash: &str) -> bool {
         self.slot_groups()
@@ -270,10 +333,20 @@ impl Runtime {
             .any(|s| s.empty_id() == Some(hash))
     }
 
-    // NOTE: This algoritm intentionally avoid HashMap/HashSet, because we have only 2-3 slots
     pub(crate) fn allocate_empty_identifier(&self) -> Result<String> {
+        self.allocate_empty_identifier_except(&[])
+    }
+
+    /// Same as `allocate_empty_identifier`, but also skip identifiers `reserved`
+    /// by earlier steps of the same plan
+    // NOTE: This algoritm intentionally avoid HashMap/HashSet, because we have only 2-3 slots
+    pub(crate) fn allocate_empty_identifier_except(&self, reserved: &[String]) -> Result<String> {
         let groups = self.slot_groups();
-        let used: Vec<&str> = groups.iter().filter_map(|s| s.empty_id()).collect();
+        let used: Vec<&str> = groups
+            .iter()
+            .filter_map(|s| s.empty_id())
+            .chain(reserved.iter().map(String::as_str))
+            .collect();
 
         for i in 0..100 {
             let candidate = i.to_string();
@@ -285,6 +358,16 @@ impl Runtime {
         bail!("empty identifier space exhausted");
     }
 
+    /// Slot groups and boot entries, which plans are made against
+    #[must_use]
+    pub fn snapshot(&self) -> RuntimeSnapshot {
+        RuntimeSnapshot {
+            active: self.kernel.to_version().map(|v| format!("{v:#}")),
+            slot_groups: self.slotgroups.iter().map(Into::into).collect(),
+            boot_entries: self.boot_entries.iter().map(ToString::to_string).collect(),
+        }
+    }
+
     /// Human-readable runtime introspection.
     /// Intended for debugging, dry-run output and diagnostics.
     #[must_use]
@@ -354,6 +437,9 @@ impl Runtime {
                     writeln!(out, "  boot: <none>")
                 }
             };
+            if let Some(assessment) = group.assessment() {
+                let _ = writeln!(out, "  assessment: {assessment}");
+            }
 
             let _ = writeln!(out);
         }
@@ -448,16 +534,6 @@ impl KernelParams {
     }
 }
 
-#[cfg(test)]
-impl Default for KernelParams {
-    fn default() -> Self {
-        Self {
-            revision: None,
-            store_hash: None,
-        }
-    }
-}
-
 #[cfg(test)]
 impl Default for Runtime {
     fn default() -> Self {
@@ -481,7 +557,7 @@ mod tests {
     #[test]
     fn groups_root_and_verity_into_single_slot() {
         let rt = Runtime {
-            slotgroups: groups(&vec![
+            slotgroups: groups(&[
                 "root_1.2.3_deadbeefdeadbeef",
                 "verity_1.2.3_deadbeefdeadbeef",
             ]),
@@ -493,7 +569,7 @@ mod tests {
 
         let g = &groups[0];
         assert_eq!(
-            g.version().as_deref(),
+            g.version(),
             Some(&Version::new(
                 "1.2.3".into(),
                 Some("deadbeefdeadbeef".into())
@@ -507,7 +583,7 @@ mod tests {
     #[test]
     fn empty_slot_is_grouped() {
         let rt = Runtime {
-            slotgroups: groups(&vec!["root_empty_01", "verity_empty_01"]),
+            slotgroups: groups(&["root_empty_01", "verity_empty_01"]),
             ..Runtime::default()
         };
 
@@ -516,14 +592,14 @@ mod tests {
 
         let g = &groups[0];
         assert_eq!(g.version(), None);
-        assert_eq!(g.empty_id().as_deref(), Some("01"));
+        assert_eq!(g.empty_id(), Some("01"));
     }
 
     // broken slot (only root)
     #[test]
     fn broken_slot_with_only_root_is_preserved() {
         let rt = Runtime {
-            slotgroups: groups(&vec!["root_2.0.0_abcdabcdabcdabcd"]),
+            slotgroups: groups(&["root_2.0.0_abcdabcdabcdabcd"]),
             ..Runtime::default()
         };
 
@@ -539,7 +615,7 @@ mod tests {
     fn non_slot_volumes_are_ignored() {
         let rt = Runtime {
             volumes: vec![Volume::new("swap"), Volume::new("home")],
-            slotgroups: groups(&vec!["root_1.0.0_aaaaaaaaaaaaaaaa"]),
+            slotgroups: groups(&["root_1.0.0_aaaaaaaaaaaaaaaa"]),
             ..Runtime::default()
         };
 
@@ -550,7 +626,7 @@ mod tests {
     #[test]
     fn multiple_slots_are_grouped_separately() {
         let rt = Runtime {
-            slotgroups: groups(&vec![
+            slotgroups: groups(&[
                 "root_1.0.0_aaaaaaaaaaaaaaaa",
                 "verity_1.0.0_aaaaaaaaaaaaaaaa",
                 "root_2.0.0_bbbbbbbbbbbbbbbb",
@@ -565,14 +641,14 @@ mod tests {
 
         assert_eq!(groups.len(), 2);
         assert_eq!(
-            groups[0].version().as_deref(),
+            groups[0].version(),
             Some(&Version::new(
                 "1.0.0".into(),
                 Some("aaaaaaaaaaaaaaaa".into())
             ))
         );
         assert_eq!(
-            groups[1].version().as_deref(),
+            groups[1].version(),
             Some(&Version::new(
                 "2.0.0".into(),
                 Some("bbbbbbbbbbbbbbbb".into())
@@ -583,7 +659,7 @@ mod tests {
     #[test]
     fn legacy_slot_is_grouped_correctly() {
         let rt = Runtime {
-            slotgroups: groups(&vec!["root_0"]),
+            slotgroups: groups(&["root_0"]),
             ..Runtime::default()
         };
 
@@ -597,7 +673,7 @@ mod tests {
     #[test]
     fn select_slot_noop_if_version_already_installed() {
         let rt = Runtime {
-            slotgroups: groups(&vec![
+            slotgroups: groups(&[
                 "root_1.2.3_deadbeefdeadbeef",
                 "verity_1.2.3_deadbeefdeadbeef",
             ]),
@@ -617,7 +693,7 @@ mod tests {
     #[test]
     fn select_empty_slot_pair() {
         let rt = Runtime {
-            slotgroups: groups(&vec![
+            slotgroups: groups(&[
                 "root_1.0.0_aaaaaaaaaaaaaaaa",
                 "verity_1.0.0_aaaaaaaaaaaaaaaa",
                 "root_empty_01",
@@ -643,7 +719,7 @@ mod tests {
     #[test]
     fn incomplete_empty_slot_is_not_selected() {
         let rt = Runtime {
-            slotgroups: groups(&vec!["root_empty_01"]),
+            slotgroups: groups(&["root_empty_01"]),
             ..Runtime::default()
         };
 
@@ -664,7 +740,7 @@ mod tests {
     #[test]
     fn one_of_multiple_empty_slots_is_selected() {
         let rt = Runtime {
-            slotgroups: groups(&vec![
+            slotgroups: groups(&[
                 "root_empty_01",
                 "verity_empty_01",
                 "root_empty_02",
@@ -796,7 +872,7 @@ mod tests {
     #[test]
     fn find_slot() {
         let rt = Runtime {
-            slotgroups: groups(&vec![
+            slotgroups: groups(&[
                 "root_1.0.0_aaaaaaaaaaaaaaaa",
                 "verity_1.0.0_aaaaaaaaaaaaaaaa",
                 "root_empty_01",
@@ -812,7 +888,7 @@ mod tests {
     #[test]
     fn detects_legacy_active_slot() {
         let rt = Runtime {
-            slotgroups: groups(&vec!["root_1.0.0", "verity_1.0.0"]),
+            slotgroups: groups(&["root_1.0.0", "verity_1.0.0"]),
             ..Runtime::default()
         };
 
@@ -0,0 +1,195 @@
+// SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Detached ed25519 signatures of update manifests. Keys are in nix format
+// `<name>:<base64>`, as generated by `nix-store --generate-binary-cache-key`.
+// Signature file `<manifest>.sig` holds lines `<key name>:<base64 signature>`, made over
+// canonical form of manifest, so it survives re-serialization on registry pull.
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{Context, ensure};
+use aws_lc_rs::signature::Ed25519KeyPair;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use givc_common::signature::{PUBLIC_KEY_LEN, split_key};
+pub use givc_common::signature::{PublicKey, TrustedKeys};
+use tracing::{info, warn};
+
+use super::manifest::Manifest;
+
+pub const DEFAULT_TRUSTED_KEYS: &str = "/etc/ota-update/trusted-keys";
+
+const SECRET_KEY_LEN: usize = 64;
+
+/// Path of detached signature for `manifest_path`
+#[must_use]
+pub fn signature_path(manifest_path: &Path) -> PathBuf {
+    manifest_path.with_added_extension("sig")
+}
+
+pub struct SecretKey {
+    name: String,
+    pair: Ed25519KeyPair,
+    public: PublicKey,
+}
+
+impl FromStr for SecretKey {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> anyhow::Result<Self> {
+        let (name, key) = split_key(text)?;
+        ensure!(
+            key.len() == SECRET_KEY_LEN,
+            "secret key {name} must be {SECRET_KEY_LEN} bytes"
+        );
+        let (seed, public) = key.split_at(SECRET_KEY_LEN - PUBLIC_KEY_LEN);
+        let pair = Ed25519KeyPair::from_seed_and_public_key(seed, public)
+            .map_err(|e| anyhow::anyhow!("invalid secret key {name}: {e}"))?;
+        Ok(Self {
+            name: name.to_owned(),
+            public: PublicKey::new(name.to_owned(), public.to_vec())?,
+            pair,
+        })
+    }
+}
+
+impl SecretKey {
+    #[must_use]
+    pub fn public_key(&self) -> PublicKey {
+        self.public.clone()
+    }
+
+    /// Signature line for `manifest`
+    ///
+    /// # Errors
+    /// Fails if manifest can't be brought to canonical form
+    pub fn sign(&self, manifest: &Manifest) -> anyhow::Result<String> {
+        let signature = self.pair.sign(&manifest.canonical_bytes()?);
+        Ok(format!(
+            "{}:{}",
+            self.name,
+            STANDARD.encode(signature.as_ref())
+        ))
+    }
+}
+
+/// How manifest is checked before it is used
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verification {
+    /// Valid signature by one of keys is required
+    Trusted(TrustedKeys),
+    /// Signatures are not checked
+    Unsigned,
+}
+
+impl Verification {
+    /// # Errors
+    /// Fails if trusted keys can't be loaded, unless unsigned manifests are allowed
+    pub fn load(trusted_keys: &Path, allow_unsigned: bool) -> anyhow::Result<Self> {
+        if allow_unsigned {
+            return Ok(Self::Unsigned);
+        }
+        let keys = TrustedKeys::load(trusted_keys)?;
+        ensure!(
+            !keys.is_empty(),
+            "no trusted keys in {}",
+            trusted_keys.display()
+        );
+        Ok(Self::Trusted(keys))
+    }
+
+    /// Check `signatures` (content of detached signature file) of `manifest`
+    ///
+    /// # Errors
+    /// Fails if signatures are missing or none of them is valid
+    pub fn check(&self, manifest: &Manifest, signatures: Option<&str>) -> anyhow::Result<()> {
+        let Self::Trusted(keys) = self else {
+            warn!(
+                "Signature of manifest {} is not verified, unsigned manifests are allowed",
+                manifest.version
+            );
+            return Ok(());
+        };
+        let signatures = signatures.context("manifest is not signed")?;
+        let key = keys
+            .verify(&manifest.canonical_bytes()?, signatures)
+            .context("verifying signature of manifest")?;
+        info!("Manifest {} is signed by {key}", manifest.version);
+        Ok(())
+    }
+
+    /// Verify `manifest` loaded from `manifest_path` against its detached signature
+    ///
+    /// # Errors
+    /// Fails if signature is missing or invalid
+    pub async fn verify(&self, manifest: &Manifest, manifest_path: &Path) -> anyhow::Result<()> {
+        let path = signature_path(manifest_path);
+        let signatures = match self {
+            Self::Trusted(_) if tokio::fs::try_exists(&path).await? => Some(
+                tokio::fs::read_to_string(&path)
+                    .await
+                    .with_context(|| format!("reading signature {}", path.display()))?,
+            ),
+            _ => None,
+        };
+        self.check(manifest, signatures.as_deref())
+            .with_context(|| format!("verifying {}", manifest_path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::test::manifest;
+    use aws_lc_rs::signature::KeyPair;
+
+    fn secret_key(name: &str, seed: u8) -> SecretKey {
+        let pair = Ed25519KeyPair::from_seed_unchecked(&[seed; 32]).unwrap();
+        let mut raw = vec![seed; 32];
+        raw.extend_from_slice(pair.public_key().as_ref());
+        format!("{name}:{}", STANDARD.encode(raw)).parse().unwrap()
+    }
+
+    #[test]
+    fn sign_and_verify() -> anyhow::Result<()> {
+        let release = secret_key("release-1", 1);
+        let other = secret_key("other-1", 2);
+        let trusted = TrustedKeys::parse(&format!("# release keys\n{}\n", release.public_key()))?;
+        let verify = |m: &Manifest, signature: &str| -> anyhow::Result<String> {
+            Ok(trusted.verify(&m.canonical_bytes()?, signature)?.to_owned())
+        };
+
+        let m = manifest("25.12.1", "deadbeefdeadbeefcafebabe");
+        let signature = release.sign(&m)?;
+        assert!(signature.starts_with("release-1:"));
+        assert_eq!(verify(&m, &signature)?, "release-1");
+
+        // Untrusted key is skipped, while trusted one is found
+        let both = format!("{}\n{signature}\n", other.sign(&m)?);
+        assert_eq!(verify(&m, &both)?, "release-1");
+        assert!(verify(&m, &other.sign(&m)?).is_err());
+
+        // Tampered manifest
+        let mut tampered = m.clone();
+        tampered.version = "25.12.2".into();
+        assert!(verify(&tampered, &signature).is_err());
+
+        // Signature survives path normalization on pull
+        let mut renamed = m.clone();
+        renamed.kernel.name = format!("./{}", renamed.kernel.name);
+        assert_eq!(verify(&renamed, &signature)?, "release-1");
+
+        let verification = Verification::Trusted(trusted);
+        verification.check(&m, Some(&signature))?;
+        assert!(verification.check(&m, None).is_err());
+        Verification::Unsigned.check(&tampered, None)?;
+        Ok(())
+    }
+
+    #[test]
+    fn reject_malformed_keys() {
+        assert!(SecretKey::from_str("no-colon").is_err());
+        assert!(SecretKey::from_str("short:AAAA").is_err());
+    }
+}
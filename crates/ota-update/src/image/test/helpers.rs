@@ -34,6 +34,7 @@ pub fn manifest(version: &str, hash: &str) -> Manifest {
             sha256sum: [0; 32],
             unpacked_size: Some(60_000_000), // ~57 MiB
         },
+        deltas: Vec::new(),
     }
 }
 
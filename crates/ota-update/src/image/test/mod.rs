@@ -13,17 +13,30 @@ use data::{BOOTCTL, KERNEL_CMDLINE, LVS, LVS_INSTALLED, MANIFEST};
 pub use helpers::{groups, manifest, slots};
 
 pub fn make_test_runtime() -> Runtime {
-    let bootctl = parse_bootctl(&BOOTCTL).unwrap();
-    let volumes = parse_lvs_json(&LVS).unwrap();
+    let bootctl = parse_bootctl(BOOTCTL).unwrap();
+    let volumes = parse_lvs_json(LVS).unwrap();
     Runtime::new(volumes, "root=fstab", bootctl).unwrap()
 }
 
 pub fn make_test_runtime_installed() -> Runtime {
-    let bootctl = parse_bootctl(&BOOTCTL).unwrap();
-    let volumes = parse_lvs_json(&LVS_INSTALLED).unwrap();
-    Runtime::new(volumes, &KERNEL_CMDLINE, bootctl).unwrap()
+    let bootctl = parse_bootctl(BOOTCTL).unwrap();
+    let volumes = parse_lvs_json(LVS_INSTALLED).unwrap();
+    Runtime::new(volumes, KERNEL_CMDLINE, bootctl).unwrap()
+}
+
+pub const BOOTED_CMDLINE: &str = "ghaf.revision=25.12.1 ghaf.storehash=deadbeefdeadbeef000000000000000000000000000000000000000000000000 root=fstab";
+
+/// Installed runtime booted with `cmdline`, with boot `counter` (like `+2-1`) in UKI name
+pub fn make_test_runtime_counted(cmdline: &str, counter: &str) -> Runtime {
+    let bootctl = BOOTCTL.replace(
+        "ghaf-25.12.1-deadbeefdeadbeef+2-1.efi",
+        &format!("ghaf-25.12.1-deadbeefdeadbeef{counter}.efi"),
+    );
+    let bootctl = parse_bootctl(&bootctl).unwrap();
+    let volumes = parse_lvs_json(LVS_INSTALLED).unwrap();
+    Runtime::new(volumes, cmdline, bootctl).unwrap()
 }
 
 pub fn make_test_manifest() -> Manifest {
-    serde_json::from_str(&MANIFEST).unwrap()
+    serde_json::from_str(MANIFEST).unwrap()
 }
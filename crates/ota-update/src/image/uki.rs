@@ -45,6 +45,37 @@ pub struct BootCounter {
     pub used: Option<u32>,
 }
 
+/// Boot assessment state, as tracked by systemd-boot counters in UKI name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Assessment {
+    /// No counters, entry was blessed (or installed without assessment)
+    Good,
+    /// Boot attempts left, waiting to be confirmed
+    Pending,
+    /// All boot attempts used without confirmation
+    Bad,
+}
+
+impl BootCounter {
+    #[must_use]
+    pub fn new(tries: u32) -> Self {
+        Self {
+            remaining: tries,
+            used: None,
+        }
+    }
+
+    #[must_use]
+    pub fn assessment(&self) -> Assessment {
+        if self.remaining == 0 {
+            Assessment::Bad
+        } else {
+            Assessment::Pending
+        }
+    }
+}
+
 impl fmt::Display for UkiEntry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "ghaf-{:#}", self.version)?;
@@ -121,8 +152,13 @@ impl BootEntry {
             let id = item.id;
 
             let kind = match item.r#type.as_str() {
-                // UKI entries
-                "type2" => match id.parse() {
+                // UKI entries. Boot counters are kept only in file name, not in id
+                "type2" => match item
+                    .path
+                    .file_name()
+                    .and_then(|name| name.to_str()?.parse().ok())
+                    .map_or_else(|| id.parse(), Ok)
+                {
                     Ok(uki) => BootEntryKind::Managed(uki),
                     Err(_) => BootEntryKind::Unmanaged,
                 },
@@ -173,6 +209,19 @@ impl BootEntry {
             .arg(&self.id)
             .into()
     }
+
+    #[must_use]
+    pub fn assessment(&self) -> Option<Assessment> {
+        self.uki().map(UkiEntry::assessment)
+    }
+
+    #[must_use]
+    pub fn to_set_default(&self) -> Pipeline {
+        CommandSpec::new("bootctl")
+            .arg("set-default")
+            .arg(&self.id)
+            .into()
+    }
 }
 
 impl From<UkiEntry> for BootEntry {
@@ -194,6 +243,22 @@ impl UkiEntry {
     pub fn matches(&self, slot: &Slot) -> bool {
         slot.version() == Some(&self.version)
     }
+
+    #[must_use]
+    pub fn assessment(&self) -> Assessment {
+        self.boot_counter
+            .as_ref()
+            .map_or(Assessment::Good, BootCounter::assessment)
+    }
+
+    /// Same entry without boot counters, as systemd-bless-boot renames it
+    #[must_use]
+    pub fn blessed(&self) -> Self {
+        Self {
+            version: self.version.clone(),
+            boot_counter: None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -228,6 +293,27 @@ mod tests {
         assert_eq!(c.used, Some(1));
     }
 
+    #[test]
+    fn uki_assessment() {
+        let parse = |name: &str| UkiEntry::from_str(name).unwrap().assessment();
+        assert_eq!(parse("ghaf-1.2.3-deadbeefdeadbeef.efi"), Assessment::Good);
+        assert_eq!(
+            parse("ghaf-1.2.3-deadbeefdeadbeef+3.efi"),
+            Assessment::Pending
+        );
+        assert_eq!(
+            parse("ghaf-1.2.3-deadbeefdeadbeef+1-2.efi"),
+            Assessment::Pending
+        );
+        assert_eq!(
+            parse("ghaf-1.2.3-deadbeefdeadbeef+0-3.efi"),
+            Assessment::Bad
+        );
+
+        let uki = UkiEntry::from_str("ghaf-1.2.3-deadbeefdeadbeef+1-2.efi").unwrap();
+        assert_eq!(uki.blessed().to_string(), "ghaf-1.2.3-deadbeefdeadbeef.efi");
+    }
+
     #[test]
     fn reject_empty_version_uki() {
         assert!(UkiEntry::from_str("ghaf-empty-deadbeefdeadbeef.efi").is_err());
@@ -0,0 +1,226 @@
+// SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// In-process writing of volume images, used by `NativeExecutor` instead of
+// `zstdcat | dd`. Source file is hashed as it is read, and compared with manifest
+// checksum once whole file is consumed. Target is written with `O_DIRECT` in aligned
+// blocks (except unaligned tail), bypassing page cache, and synced at the end.
+use std::fs::OpenOptions;
+use std::io::{self, Read, Write};
+use std::os::unix::fs::{FileExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, ensure};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+
+const ALIGN: usize = 4096;
+const BUFFER_SIZE: usize = 4 * 1024 * 1024;
+const PROGRESS_STEP: u64 = 64 * 1024 * 1024;
+
+/// Image write, which pipeline performs, see `Pipeline::with_write()`
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VolumeWrite {
+    pub source: PathBuf,
+    pub target: PathBuf,
+    /// Source is zstd-compressed
+    pub compressed: bool,
+    /// Expected checksum of source file
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub sha256: [u8; 32],
+    /// Expected size of written image, if known
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteProgress {
+    pub target: PathBuf,
+    pub written: u64,
+    pub total: Option<u64>,
+}
+
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Read up to `buf.len()` bytes, less only at end of input
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Open `path` for direct I/O, or for regular one if it isn't supported (tmpfs)
+fn open_direct(path: &Path) -> io::Result<std::fs::File> {
+    match OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_DIRECT)
+        .open(path)
+    {
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {
+            OpenOptions::new().write(true).open(path)
+        }
+        result => result,
+    }
+}
+
+/// Copy `reader` to `target`, returning number of bytes written
+fn copy_direct(
+    reader: &mut impl Read,
+    target: &Path,
+    mut on_progress: impl FnMut(u64),
+) -> anyhow::Result<u64> {
+    let out = open_direct(target).with_context(|| format!("opening {}", target.display()))?;
+    // Direct I/O needs aligned memory, so buffer is carved out of larger allocation
+    let mut storage = vec![0u8; BUFFER_SIZE + ALIGN];
+    let start = storage.as_ptr().align_offset(ALIGN);
+    let buf = &mut storage[start..start + BUFFER_SIZE];
+
+    let mut written = 0u64;
+    let mut next_report = PROGRESS_STEP;
+    loop {
+        let filled = read_full(reader, buf).context("reading image")?;
+        let aligned = filled - filled % ALIGN;
+        (&out)
+            .write_all(&buf[..aligned])
+            .with_context(|| format!("writing {}", target.display()))?;
+        if aligned < filled {
+            // Unaligned tail can't be written directly, it is the last block anyway
+            let tail = OpenOptions::new()
+                .write(true)
+                .open(target)
+                .with_context(|| format!("opening {}", target.display()))?;
+            tail.write_all_at(&buf[aligned..filled], written + aligned as u64)
+                .with_context(|| format!("writing {}", target.display()))?;
+            tail.sync_all()?;
+        }
+        written += filled as u64;
+        if written >= next_report {
+            on_progress(written);
+            next_report = written + PROGRESS_STEP;
+        }
+        if filled < buf.len() {
+            break;
+        }
+    }
+    out.sync_all()
+        .with_context(|| format!("syncing {}", target.display()))?;
+    on_progress(written);
+    Ok(written)
+}
+
+/// Write image described by `write`, verifying checksum of its source.
+/// Returns number of bytes written to target.
+///
+/// # Errors
+/// Fails on I/O or decompression error, or if checksum or size doesn't match
+pub fn write_volume(write: &VolumeWrite, on_progress: impl FnMut(u64)) -> anyhow::Result<u64> {
+    let source = std::fs::File::open(&write.source)
+        .with_context(|| format!("opening {}", write.source.display()))?;
+    let mut reader = HashingReader {
+        inner: source,
+        hasher: Sha256::new(),
+    };
+
+    let written = if write.compressed {
+        let mut decoder =
+            zstd::stream::read::Decoder::new(&mut reader).context("initializing zstd decoder")?;
+        let written = copy_direct(&mut decoder, &write.target, on_progress)?;
+        // Trailing data after last frame is covered by checksum too
+        io::copy(&mut decoder.finish(), &mut io::sink())?;
+        written
+    } else {
+        copy_direct(&mut reader, &write.target, on_progress)?
+    };
+
+    let actual: [u8; 32] = reader.hasher.finalize().into();
+    ensure!(
+        actual == write.sha256,
+        "Checksum mismatch for {name}: expected {expected}, got {actual}",
+        name = write.source.display(),
+        expected = hex::encode(write.sha256),
+        actual = hex::encode(actual),
+    );
+    if let Some(size) = write.size {
+        ensure!(
+            written == size,
+            "Size mismatch for {name}: expected {size}, written {written}",
+            name = write.source.display(),
+        );
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ota-update-writer-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn write_and_verify() -> anyhow::Result<()> {
+        let image: Vec<u8> = (0..ALIGN * 3 + 100)
+            .map(|i| u8::try_from(i % 251).unwrap())
+            .collect();
+        let compressed = zstd::encode_all(image.as_slice(), 0)?;
+        let source = temp_path("root.raw.zst");
+        let target = temp_path("volume");
+        std::fs::write(&source, &compressed)?;
+        std::fs::write(&target, vec![0xffu8; ALIGN * 5])?;
+
+        let mut write = VolumeWrite {
+            source: source.clone(),
+            target: target.clone(),
+            compressed: true,
+            sha256: Sha256::digest(&compressed).into(),
+            size: Some(image.len() as u64),
+        };
+        let mut reported = Vec::new();
+        assert_eq!(
+            write_volume(&write, |n| reported.push(n))?,
+            image.len() as u64
+        );
+        assert_eq!(reported.last(), Some(&(image.len() as u64)));
+        let volume = std::fs::read(&target)?;
+        assert_eq!(&volume[..image.len()], image.as_slice());
+        // Rest of volume is untouched
+        assert!(volume[image.len()..].iter().all(|b| *b == 0xff));
+
+        write.sha256 = [0; 32];
+        assert!(write_volume(&write, |_| ()).is_err());
+
+        // Uncompressed source with wrong expected size
+        std::fs::write(&source, &image)?;
+        let write = VolumeWrite {
+            compressed: false,
+            sha256: Sha256::digest(&image).into(),
+            size: Some(1),
+            ..write
+        };
+        assert!(write_volume(&write, |_| ()).is_err());
+
+        std::fs::remove_file(source)?;
+        std::fs::remove_file(target)?;
+        Ok(())
+    }
+}
@@ -3,8 +3,10 @@
 
 pub mod bootctl;
 pub mod cli;
+pub mod generation;
 pub mod image;
 pub mod profile;
+pub mod progress;
 pub mod query;
 pub mod registry;
 pub mod types;
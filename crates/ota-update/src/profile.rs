@@ -1,15 +1,20 @@
 // SPDX-FileCopyrightText: 2025-2026 TII (SSRC) and the Ghaf contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::bootctl::{find_init, get_bootctl_info};
+use crate::bootctl::{BootctlItem, find_init, get_bootctl_info};
 use crate::nixos::{read_kernel_version, read_nixos_version};
-use crate::types::{GenerationDetails, ProfileElement};
+use crate::types::{BootEntry, GenerationDetails, ProfileElement};
 use anyhow::Context;
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
 use tokio::fs;
 use tokio::process::Command;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 #[must_use]
 pub fn format_profile_link(profile: &str, generation: i32) -> String {
@@ -84,16 +89,91 @@ pub async fn read_profile_links(
         };
 
         let current = default_target_str == name;
+        let created = fs::symlink_metadata(&full_path)
+            .await
+            .and_then(|meta| meta.modified())
+            .ok();
 
         generations.push(ProfileElement {
             num,
             store_path,
             current,
+            created,
         });
     }
     Ok((default_gen_no, generations))
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PathInfo {
+    // Present only in legacy (array) output
+    path: Option<PathBuf>,
+    closure_size: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PathInfoOutput {
+    // nix >= 2.19 keys output by store path
+    Map(HashMap<PathBuf, Option<PathInfo>>),
+    List(Vec<PathInfo>),
+}
+
+/// Pure parser of `nix path-info --json --closure-size` output, for test data injection
+/// # Errors
+/// Returns `Err` if output isn't valid JSON of either known layout
+pub fn parse_closure_sizes(output: &[u8]) -> anyhow::Result<HashMap<PathBuf, u64>> {
+    let parsed: PathInfoOutput =
+        serde_json::from_slice(output).context("while parsing nix path-info output")?;
+    Ok(match parsed {
+        PathInfoOutput::Map(infos) => infos
+            .into_iter()
+            .filter_map(|(path, info)| Some((path, info?.closure_size?)))
+            .collect(),
+        PathInfoOutput::List(infos) => infos
+            .into_iter()
+            .filter_map(|info| Some((info.path?, info.closure_size?)))
+            .collect(),
+    })
+}
+
+/// Query closure sizes of `paths` in one `nix path-info` call
+async fn read_closure_sizes(paths: &[&Path]) -> anyhow::Result<HashMap<PathBuf, u64>> {
+    if paths.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let output = Command::new("nix")
+        .arg("--extra-experimental-features")
+        .arg("nix-command")
+        .arg("path-info")
+        .arg("--json")
+        .arg("--closure-size")
+        .args(paths)
+        .output()
+        .await
+        .context("executing nix path-info")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "nix path-info failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )
+    }
+    parse_closure_sizes(&output.stdout)
+}
+
+fn format_date(time: SystemTime) -> Option<String> {
+    OffsetDateTime::from(time).format(&Rfc3339).ok()
+}
+
+fn boot_entry(bootctl: Option<&BootctlItem>) -> BootEntry {
+    match bootctl {
+        None => BootEntry::Missing,
+        Some(item) if item.is_default => BootEntry::Default,
+        Some(_) => BootEntry::Present,
+    }
+}
+
 /// Read list of nixos generations from directory
 /// # Errors
 /// Returns `Err` on IO Errors or UTF decoding failures
@@ -102,6 +182,15 @@ pub async fn read_generations() -> anyhow::Result<Vec<GenerationDetails>> {
     let current_system = read_symlink("/run/current-system").await?;
     let bootctl = get_bootctl_info().await?;
     let (_, system_profiles) = read_profile_links("/nix/var/nix/profiles", "system").await?;
+    // Size is informational, don't fail listing if nix can't report it
+    let store_paths: Vec<&Path> = system_profiles
+        .iter()
+        .map(|profile| profile.store_path.as_path())
+        .collect();
+    let closure_sizes = read_closure_sizes(&store_paths)
+        .await
+        .inspect_err(|err| warn!("Unable to query closure sizes: {err:#}"))
+        .unwrap_or_default();
 
     let mut generations = Vec::new();
 
@@ -126,13 +215,23 @@ pub async fn read_generations() -> anyhow::Result<Vec<GenerationDetails>> {
             .iter()
             .find(|bootctl| find_init(bootctl) == Some(&bootspec.bootspec.init))
             .map(ToOwned::to_owned);
-        let bootable = bootctl.as_ref().is_some_and(|bootctl| bootctl.is_default);
+        let boot_entry = boot_entry(bootctl.as_ref());
+        let bootable = boot_entry == BootEntry::Default;
         let current = profile.store_path == current_system;
         let booted = profile.store_path == booted_system;
+        let mut specialisations: Vec<String> = bootspec
+            .specialisations
+            .keys()
+            .map(|name| name.0.clone())
+            .collect();
+        specialisations.sort();
 
         generations.push(GenerationDetails {
             generation: profile.num,
             name: bootspec.bootspec.label.clone(),
+            date: profile.created.and_then(format_date),
+            specialisations,
+            closure_size: closure_sizes.get(&profile.store_path).copied(),
             store_path: profile.store_path,
             nixos_version: version.nixos_version,
             nixpkgs_revision: version.nixpkgs_revision,
@@ -142,6 +241,7 @@ pub async fn read_generations() -> anyhow::Result<Vec<GenerationDetails>> {
             booted,
             default: profile.current,
             bootable,
+            boot_entry,
             bootspec,
             bootctl,
         });
@@ -163,6 +263,7 @@ pub async fn set(path: &Path, profile: &OsStr, closure: &Path) -> anyhow::Result
         .arg(&full_path)
         .arg("--set")
         .arg(closure)
+        .stdout(std::io::stderr())
         .status()
         .await
         .context("Fail to execute nix-env")?;
@@ -172,6 +273,50 @@ pub async fn set(path: &Path, profile: &OsStr, closure: &Path) -> anyhow::Result
     Ok(())
 }
 
+/// Point `profile` to existing `generation`, same caveats as for `set()`
+///
+/// # Errors
+/// Fails if subsequent exec of `nix-env` fails
+pub async fn switch_generation(
+    path: &Path,
+    profile: &OsStr,
+    generation: i32,
+) -> anyhow::Result<()> {
+    let full_path = path.join(profile);
+    let nix_env = Command::new("nix-env")
+        .arg("-p")
+        .arg(&full_path)
+        .arg("--switch-generation")
+        .arg(generation.to_string())
+        .stdout(std::io::stderr())
+        .status()
+        .await
+        .context("Fail to execute nix-env")?;
+    if !nix_env.success() {
+        anyhow::bail!("nix-env failed")
+    }
+    Ok(())
+}
+
+/// Make `closure` default boot entry, and refresh list of boot entries
+///
+/// # Errors
+/// Fails if `switch-to-configuration` can't be executed or fails
+pub async fn install_bootloader(closure: &Path) -> anyhow::Result<()> {
+    let boot_path = closure.join("bin/switch-to-configuration");
+    let status = Command::new(&boot_path)
+        .arg("boot")
+        // Keep our stdout clean for `--output jsonl` consumers
+        .stdout(std::io::stderr())
+        .status()
+        .await
+        .with_context(|| format!("Fail to execute {}", boot_path.display()))?;
+    if !status.success() {
+        anyhow::bail!("{} failed", boot_path.display())
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -210,4 +355,35 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_parse_closure_sizes() -> anyhow::Result<()> {
+        let foo = PathBuf::from("/nix/store/aaaa-nixos-system-foo");
+        let bar = PathBuf::from("/nix/store/bbbb-nixos-system-bar");
+
+        let current = br#"{
+            "/nix/store/aaaa-nixos-system-foo": {"narSize": 100, "closureSize": 4096},
+            "/nix/store/bbbb-nixos-system-bar": null
+        }"#;
+        let sizes = parse_closure_sizes(current)?;
+        assert_eq!(sizes.get(&foo), Some(&4096));
+        assert_eq!(sizes.get(&bar), None);
+
+        let legacy = br#"[
+            {"path": "/nix/store/aaaa-nixos-system-foo", "closureSize": 4096},
+            {"path": "/nix/store/bbbb-nixos-system-bar", "closureSize": 8192}
+        ]"#;
+        let sizes = parse_closure_sizes(legacy)?;
+        assert_eq!(sizes.get(&foo), Some(&4096));
+        assert_eq!(sizes.get(&bar), Some(&8192));
+
+        assert!(parse_closure_sizes(b"garbage").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_date() {
+        let time = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        assert_eq!(format_date(time).as_deref(), Some("2023-11-14T22:13:20Z"));
+    }
 }
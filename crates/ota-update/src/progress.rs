@@ -0,0 +1,267 @@
+// SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Progress of system update (cachix/local), printed as JSON lines for admin to relay.
+// Fetch progress is derived from `nix build --log-format internal-json` messages.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::registry::cli::OutputFormat;
+
+// Nix activity and result types, see `libutil/logging.hh`
+const ACT_COPY_PATH: u64 = 100;
+const ACT_COPY_PATHS: u64 = 103;
+const ACT_SUBSTITUTE: u64 = 108;
+const RES_PROGRESS: u64 = 105;
+const RES_SET_EXPECTED: u64 = 106;
+
+const MIN_REPORT_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivationStep {
+    /// Point system profile to new generation
+    SetProfile,
+    /// Run `switch-to-configuration boot`
+    InstallBootloader,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum UpdateEvent {
+    /// Update target resolved
+    Resolved {
+        store_path: String,
+    },
+    /// Download of store paths
+    Fetching {
+        paths_done: u64,
+        paths_total: u64,
+        bytes_done: u64,
+        bytes_total: u64,
+        current: Option<String>,
+    },
+    Activating {
+        step: ActivationStep,
+    },
+    Done,
+}
+
+impl UpdateEvent {
+    /// Print event in requested `format`
+    pub fn report(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Jsonl => {
+                if let Ok(line) = serde_json::to_string(self) {
+                    println!("{line}");
+                }
+            }
+            OutputFormat::Text => match self {
+                UpdateEvent::Resolved { store_path } => println!("update: {store_path}"),
+                UpdateEvent::Fetching {
+                    paths_done,
+                    paths_total,
+                    bytes_done,
+                    bytes_total,
+                    ..
+                } => println!(
+                    "fetched {paths_done}/{paths_total} paths, {bytes_done}/{bytes_total} bytes"
+                ),
+                UpdateEvent::Activating { step } => println!("activating: {step:?}"),
+                UpdateEvent::Done => println!("done"),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+enum NixMessage {
+    Start {
+        id: u64,
+        #[serde(rename = "type")]
+        kind: u64,
+        #[serde(default)]
+        fields: Vec<serde_json::Value>,
+    },
+    Stop {
+        id: u64,
+    },
+    Result {
+        id: u64,
+        #[serde(rename = "type")]
+        kind: u64,
+        #[serde(default)]
+        fields: Vec<serde_json::Value>,
+    },
+    #[serde(other)]
+    Other,
+}
+
+fn field(fields: &[serde_json::Value], n: usize) -> u64 {
+    fields
+        .get(n)
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0)
+}
+
+/// Accumulates nix activities into `UpdateEvent::Fetching` reports
+#[derive(Debug, Default)]
+pub struct NixProgress {
+    activities: HashMap<u64, u64>,
+    // Per `actCopyPath` activity: bytes done, bytes expected
+    copies: HashMap<u64, (u64, u64)>,
+    paths: (u64, u64),
+    // Expected bytes, announced by parent `actCopyPaths`
+    bytes_expected: u64,
+    current: Option<String>,
+    last_report: Option<Instant>,
+}
+
+impl NixProgress {
+    fn snapshot(&self) -> UpdateEvent {
+        let (bytes_done, expected) = self
+            .copies
+            .values()
+            .fold((0, 0), |(done, total), (d, t)| (done + d, total + t));
+        UpdateEvent::Fetching {
+            paths_done: self.paths.0,
+            paths_total: self.paths.1,
+            bytes_done,
+            bytes_total: self.bytes_expected.max(expected),
+            current: self.current.clone(),
+        }
+    }
+
+    /// Feed one stderr line of `nix build --log-format internal-json`. Returns event
+    /// on significant change (new path, path completed), or on byte progress if
+    /// enough time passed since last report.
+    pub fn feed(&mut self, line: &str, now: Instant) -> Option<UpdateEvent> {
+        let message: NixMessage = serde_json::from_str(line.strip_prefix("@nix ")?).ok()?;
+        let significant = match message {
+            NixMessage::Start { id, kind, fields } => {
+                self.activities.insert(id, kind);
+                match kind {
+                    ACT_SUBSTITUTE => {
+                        self.current = fields
+                            .first()
+                            .and_then(serde_json::Value::as_str)
+                            .map(ToOwned::to_owned);
+                        true
+                    }
+                    ACT_COPY_PATH => {
+                        self.copies.insert(id, (0, 0));
+                        false
+                    }
+                    _ => return None,
+                }
+            }
+            NixMessage::Stop { id } => {
+                self.activities.remove(&id);
+                return None;
+            }
+            NixMessage::Result {
+                id,
+                kind: RES_PROGRESS,
+                fields,
+            } => match self.activities.get(&id) {
+                Some(&ACT_COPY_PATHS) => {
+                    let paths = (field(&fields, 0), field(&fields, 1));
+                    let changed = paths != self.paths;
+                    self.paths = paths;
+                    changed
+                }
+                Some(&ACT_COPY_PATH) => {
+                    self.copies
+                        .insert(id, (field(&fields, 0), field(&fields, 1)));
+                    false
+                }
+                _ => return None,
+            },
+            NixMessage::Result {
+                id,
+                kind: RES_SET_EXPECTED,
+                fields,
+            } if self.activities.get(&id) == Some(&ACT_COPY_PATHS) => {
+                if field(&fields, 0) == ACT_COPY_PATH {
+                    self.bytes_expected = field(&fields, 1);
+                }
+                return None;
+            }
+            NixMessage::Result { .. } | NixMessage::Other => return None,
+        };
+        let due = self
+            .last_report
+            .is_none_or(|last| now.duration_since(last) >= MIN_REPORT_INTERVAL);
+        if significant || due {
+            self.last_report = Some(now);
+            Some(self.snapshot())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nix_progress() {
+        let mut progress = NixProgress::default();
+        let now = Instant::now();
+        let mut feed = |line: &str| progress.feed(line, now);
+
+        assert_eq!(feed("not a nix message"), None);
+        assert_eq!(
+            feed(r#"@nix {"action":"start","id":1,"level":0,"type":103,"text":"","fields":[]}"#),
+            None
+        );
+        feed(r#"@nix {"action":"result","id":1,"type":106,"fields":[100,4096]}"#);
+        let started = feed(
+            r#"@nix {"action":"start","id":2,"type":108,"fields":["/nix/store/abc-foo","https://cache"]}"#,
+        );
+        assert_eq!(
+            started,
+            Some(UpdateEvent::Fetching {
+                paths_done: 0,
+                paths_total: 0,
+                bytes_done: 0,
+                bytes_total: 4096,
+                current: Some("/nix/store/abc-foo".into()),
+            })
+        );
+        feed(r#"@nix {"action":"start","id":3,"type":100,"fields":[]}"#);
+        // Reported only once per interval
+        assert_eq!(
+            feed(r#"@nix {"action":"result","id":3,"type":105,"fields":[1024,4096,0,0]}"#),
+            None
+        );
+        let done = feed(r#"@nix {"action":"result","id":1,"type":105,"fields":[1,2,0,0]}"#);
+        assert_eq!(
+            done,
+            Some(UpdateEvent::Fetching {
+                paths_done: 1,
+                paths_total: 2,
+                bytes_done: 1024,
+                bytes_total: 4096,
+                current: Some("/nix/store/abc-foo".into()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_event_json() -> anyhow::Result<()> {
+        let event = UpdateEvent::Activating {
+            step: ActivationStep::InstallBootloader,
+        };
+        let json = serde_json::to_string(&event)?;
+        assert_eq!(
+            json,
+            r#"{"event":"activating","step":"install_bootloader"}"#
+        );
+        assert_eq!(serde_json::from_str::<UpdateEvent>(&json)?, event);
+        Ok(())
+    }
+}
@@ -10,6 +10,7 @@ use super::{
     UntaggedReference, discover_updates, fetch_changelog, prune_downloaded_updates, pull_update,
     push_update_with_feedback,
 };
+use crate::image::cli::TrustOptions;
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum OutputFormat {
@@ -21,11 +22,11 @@ pub enum OutputFormat {
 #[group(requires_all = ["username", "password"])]
 pub struct PasswordAuth {
     /// Registry username (basic auth)
-    #[arg(long, required = false)]
+    #[arg(long, env = "GIVC_REGISTRY_USERNAME", required = false)]
     pub username: String,
 
     /// Registry password (basic auth)
-    #[arg(long, required = false)]
+    #[arg(long, env = "GIVC_REGISTRY_PASSWORD", required = false)]
     pub password: String,
 }
 
@@ -43,7 +44,7 @@ pub struct RegistryCommand {
     pub auth: Option<PasswordAuth>,
 
     /// Registry API token (bearer auth)
-    #[arg(long, conflicts_with_all = ["username", "password"])]
+    #[arg(long, env = "GIVC_REGISTRY_TOKEN", conflicts_with_all = ["username", "password"])]
     pub token: Option<String>,
 
     /// Use HTTP instead of HTTPS for registry access
@@ -79,6 +80,13 @@ pub enum RegistryAction {
         /// Apply installation immediately after successful pull
         #[arg(long)]
         install: bool,
+
+        /// Boot attempts before installed slot is rolled back, 0 disables boot assessment
+        #[arg(long, env = "OTA_BOOT_TRIES", default_value_t = 0)]
+        boot_tries: u32,
+
+        #[command(flatten)]
+        trust: TrustOptions,
     },
 
     /// Fetch changelog text for a specific tag
@@ -158,6 +166,8 @@ impl RegistryCommand {
                 validate,
                 no_validate,
                 install,
+                boot_tries,
+                trust,
             } => {
                 let result = pull_update(
                     &PullOptions {
@@ -166,7 +176,9 @@ impl RegistryCommand {
                         credentials,
                         client_protocol,
                         install,
+                        boot_tries: (boot_tries > 0).then_some(boot_tries),
                         validate: validate && !no_validate,
+                        verification: trust.load()?,
                     },
                     Some(&feedback_tx),
                     None,
@@ -283,6 +295,20 @@ fn print_text_event(event: &RegistryEvent) {
         } => {
             println!("pull start: {reference} -> {destination}");
         }
+        RegistryEvent::BlobResumed {
+            digest,
+            offset,
+            total,
+        } => {
+            println!(
+                "resume {}: from {offset}/{}",
+                short_hash(digest),
+                total.unwrap_or(0)
+            );
+        }
+        RegistryEvent::BlobCached { digest } => {
+            println!("cached {}: already downloaded", short_hash(digest));
+        }
         RegistryEvent::PushStarted { reference, layers } => {
             println!("push start: {reference} ({layers} layers)");
         }
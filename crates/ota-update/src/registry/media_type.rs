@@ -20,7 +20,15 @@ pub enum MediaType {
     #[strum(serialize = "application/vnd.ghaf.ota.verity.v1+raw")]
     #[serde(rename = "application/vnd.ghaf.ota.verity.v1+raw")]
     Verity,
+    /// Delta of root or verity image, see `image::delta`. Layer title is file
+    /// name as in manifest.
+    #[strum(serialize = "application/vnd.ghaf.ota.delta.v1+raw")]
+    #[serde(rename = "application/vnd.ghaf.ota.delta.v1+raw")]
+    Delta,
     #[strum(serialize = "application/vnd.ghaf.ota.changelog.v1+plain")]
     #[serde(rename = "application/vnd.ghaf.ota.changelog.v1+plain")]
     Changelog,
+    #[strum(serialize = "application/vnd.ghaf.ota.signature.v1+plain")]
+    #[serde(rename = "application/vnd.ghaf.ota.signature.v1+plain")]
+    Signature,
 }
@@ -3,9 +3,7 @@
 
 pub mod cli;
 pub mod media_type;
-mod oras;
 pub mod progress;
-pub mod types;
 
 use async_channel::Sender;
 use std::path::{Path, PathBuf};
@@ -16,12 +14,20 @@ use std::collections::BTreeMap;
 use tokio::time::{Duration, timeout};
 use tokio_util::sync::CancellationToken;
 
-use crate::image::install::install_from_manifest_path;
-use crate::image::manifest::Manifest;
+use crate::image::Version;
+use crate::image::install::{install_from_manifest_path, populate_runtime};
+use crate::image::manifest::{Delta, Manifest};
+use crate::image::signature::{Verification, signature_path};
 use crate::lock::UpdateLock;
+use givc_oras::client as oras;
+pub use givc_oras::{
+    ArtifactOptions, ClientProtocol, PulledArtifact, RegistryCredentials, TaggedReference,
+    UntaggedReference, pull_artifact, resolve_artifact_digest,
+};
+use givc_oras::{
+    ORG_OPENCONTAINERS_IMAGE_TITLE, sanitize_path_component, sanitize_relative_file_path,
+};
 pub use media_type::MediaType;
-pub use oci_client::client::ClientProtocol;
-pub use types::{TaggedReference, UntaggedReference};
 
 fn notify<T>(feedback: Option<&Sender<T>>, event: T) {
     if let Some(tx) = feedback {
@@ -29,13 +35,6 @@ fn notify<T>(feedback: Option<&Sender<T>>, event: T) {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub enum RegistryCredentials {
-    Anonymous,
-    Basic { username: String, password: String },
-    Bearer { token: String },
-}
-
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AvailableUpdate {
     pub repository: String,
@@ -58,7 +57,10 @@ pub struct PullOptions {
     pub credentials: RegistryCredentials,
     pub client_protocol: ClientProtocol,
     pub install: bool,
+    /// Boot attempts of installed slot, `None` disables boot assessment
+    pub boot_tries: Option<u32>,
     pub validate: bool,
+    pub verification: Verification,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -228,18 +230,53 @@ pub async fn pull_update(
                     stage: "fetch-manifest".to_string(),
                 },
             );
-            let _ = tokio::fs::remove_dir_all(&output_dir).await;
+            // Only if empty, partial blobs of earlier pull are kept
+            let _ = tokio::fs::remove_dir(&output_dir).await;
             anyhow::bail!("pull cancelled");
         }
         Err(err) => {
-            let _ = tokio::fs::remove_dir_all(&output_dir).await;
+            let _ = tokio::fs::remove_dir(&output_dir).await;
             return Err(err).context("pull manifest fetch failed");
         }
     };
     let mut manifest = Manifest::from_slice(remote.config_json.as_bytes())?;
     manifest.normalize_paths()?;
 
-    let artifact_bindings = select_artifact_bindings(&manifest, &remote.layers)?;
+    // Reject untrusted manifest before downloading images
+    let signatures = match find_layer_by_media_type(&remote.layers, MediaType::Signature) {
+        Some(layer) => {
+            let bytes = timeout(
+                Duration::from_secs(30),
+                oras::download_blob_to_vec(
+                    &client,
+                    &options.reference,
+                    layer,
+                    &options.credentials,
+                    ct,
+                ),
+            )
+            .await
+            .context("pull timeout while downloading signature")??;
+            Some(String::from_utf8(bytes).context("signature blob is not valid UTF-8")?)
+        }
+        None => None,
+    };
+    if let Err(err) = options.verification.check(&manifest, signatures.as_deref()) {
+        let _ = tokio::fs::remove_dir_all(&output_dir).await;
+        return Err(err).context("pulled manifest is not trusted");
+    }
+
+    // Delta against active slot saves downloading full images, if it was pushed
+    let base = populate_runtime().await.ok().and_then(|rt| {
+        rt.active_slot()
+            .ok()
+            .and_then(|slot| slot.version().cloned())
+    });
+    let delta = select_delta_layers(&manifest, &remote.layers, base.as_ref());
+    if let Some(delta) = delta {
+        println!("pulling delta against {:#}", delta.base());
+    }
+    let artifact_bindings = select_artifact_bindings(&manifest, &remote.layers, delta)?;
     for binding in artifact_bindings {
         let local = output_dir.join(&binding.local_name);
         if let Some(parent) = local.parent() {
@@ -247,49 +284,68 @@ pub async fn pull_update(
                 .await
                 .with_context(|| format!("creating parent dir {}", parent.display()))?;
         }
-        let part = local.with_added_extension("part");
-        let file = tokio::fs::File::create(&part)
-            .await
-            .with_context(|| format!("creating temp blob file {}", part.display()))?;
-        let download = oras::download_blob_to_file(
-            &client,
-            &options.reference,
-            &binding.blob,
-            &options.credentials,
-            file,
-            |downloaded, total| {
-                notify(
-                    feedback,
-                    progress::RegistryEvent::BlobDownloading {
-                        digest: binding.digest.clone(),
-                        downloaded,
-                        total,
-                    },
-                );
-            },
-            ct,
-        )
-        .await;
-        match download {
-            Ok(()) => {}
-            Err(err) if err.is::<oras::CancellationError>() => {
-                notify(
-                    feedback,
-                    progress::RegistryEvent::Cancelled {
-                        stage: format!("blob-download:{}", binding.digest),
-                    },
-                );
-                let _ = tokio::fs::remove_dir_all(&output_dir).await;
-                anyhow::bail!("pull cancelled");
-            }
-            Err(err) => {
-                let _ = tokio::fs::remove_dir_all(&output_dir).await;
-                return Err(err).with_context(|| format!("downloading blob {}", binding.digest));
+        if oras::is_blob_complete(&local, &binding.blob).await? {
+            notify(
+                feedback,
+                progress::RegistryEvent::BlobCached {
+                    digest: binding.digest.clone(),
+                },
+            );
+        } else {
+            // Keyed by digest, so partial blob is never resumed with other content
+            let part = output_dir.join(format!(
+                ".{}.part",
+                sanitize_path_component(&binding.digest)
+            ));
+            let download = oras::download_blob_to_file(
+                &client,
+                &options.reference,
+                &binding.blob,
+                &options.credentials,
+                &part,
+                |progress, total| {
+                    let event = match progress {
+                        oras::DownloadProgress::Resumed(offset) => {
+                            progress::RegistryEvent::BlobResumed {
+                                digest: binding.digest.clone(),
+                                offset,
+                                total,
+                            }
+                        }
+                        oras::DownloadProgress::Downloaded(downloaded) => {
+                            progress::RegistryEvent::BlobDownloading {
+                                digest: binding.digest.clone(),
+                                downloaded,
+                                total,
+                            }
+                        }
+                    };
+                    notify(feedback, event);
+                },
+                ct,
+            )
+            .await;
+            // Partial blobs are kept, next pull resumes them
+            match download {
+                Ok(()) => {}
+                Err(err) if err.is::<oras::CancellationError>() => {
+                    notify(
+                        feedback,
+                        progress::RegistryEvent::Cancelled {
+                            stage: format!("blob-download:{}", binding.digest),
+                        },
+                    );
+                    anyhow::bail!("pull cancelled");
+                }
+                Err(err) => {
+                    return Err(err)
+                        .with_context(|| format!("downloading blob {}", binding.digest));
+                }
             }
+            tokio::fs::rename(&part, &local)
+                .await
+                .with_context(|| format!("renaming {} to {}", part.display(), local.display()))?;
         }
-        tokio::fs::rename(&part, &local)
-            .await
-            .with_context(|| format!("renaming {} to {}", part.display(), local.display()))?;
         notify(
             feedback,
             progress::RegistryEvent::BlobVerified {
@@ -310,6 +366,15 @@ pub async fn pull_update(
     manifest
         .write_to_file(&manifest_path)
         .with_context(|| format!("writing manifest file {}", manifest_path.display()))?;
+    let sig_path = signature_path(&manifest_path);
+    match &signatures {
+        Some(signatures) => tokio::fs::write(&sig_path, signatures)
+            .await
+            .with_context(|| format!("writing signature file {}", sig_path.display()))?,
+        None => {
+            let _ = tokio::fs::remove_file(&sig_path).await;
+        }
+    }
     notify(
         feedback,
         progress::RegistryEvent::ManifestWritten {
@@ -319,7 +384,7 @@ pub async fn pull_update(
 
     if options.validate {
         manifest
-            .validate(&output_dir, true)
+            .validate(&output_dir, true, delta)
             .await
             .context("while validating pulled artifacts")?;
     }
@@ -331,9 +396,15 @@ pub async fn pull_update(
                 manifest: manifest_path.display().to_string(),
             },
         );
-        install_from_manifest_path(&manifest_path, options.validate, false)
-            .await
-            .context("while installing pulled manifest")?;
+        install_from_manifest_path(
+            &manifest_path,
+            &options.verification,
+            options.validate,
+            options.boot_tries,
+            false,
+        )
+        .await
+        .context("while installing pulled manifest")?;
     }
 
     println!("manifest path: {}", manifest_path.display());
@@ -408,7 +479,7 @@ pub(crate) async fn prune_downloaded_updates(options: &PruneOptions) -> anyhow::
         }
 
         if !tags.is_empty() {
-            tags.sort_by(|a, b| b.1.cmp(&a.1));
+            tags.sort_by_key(|tag| std::cmp::Reverse(tag.1));
             for (path, _) in tags.into_iter().skip(KEEP_PER_REPOSITORY) {
                 tokio::fs::remove_dir_all(&path)
                     .await
@@ -449,7 +520,7 @@ pub async fn push_update_with_feedback(
         .parent()
         .context("manifest path has no parent directory")?;
     manifest
-        .validate(base_dir, true)
+        .validate(base_dir, true, None)
         .await
         .context("while validating manifest content")?;
 
@@ -466,6 +537,14 @@ pub async fn push_update_with_feedback(
         manifest.verity.full_name(base_dir),
         MediaType::Verity,
     )?);
+    for delta in &manifest.deltas {
+        for file in [&delta.root.file, &delta.verity.file] {
+            file.validate(base_dir, true)
+                .await
+                .with_context(|| format!("while validating delta against {:#}", delta.base()))?;
+            layers.push(delta_layer_input(base_dir, file)?);
+        }
+    }
 
     if let Some(changelog_path) = &options.changelog_path {
         let title = changelog_path
@@ -479,20 +558,47 @@ pub async fn push_update_with_feedback(
         );
         layers.push(oras::LayerInput {
             path: changelog_path.clone(),
-            media_type: MediaType::Changelog,
+            kind: MediaType::Changelog,
             annotations: Some(annotations),
         });
     }
 
+    let sig_path = signature_path(&options.manifest_path);
+    if tokio::fs::try_exists(&sig_path).await? {
+        layers.push(layer_input_with_title(sig_path, MediaType::Signature)?);
+    }
+
     let client = oras::build_client(options.client_protocol.clone());
+    let reference = options.reference.to_string();
+    let feedback_tx = feedback.cloned();
     let pushed = oras::push_layers_and_config(
         &client,
         &options.reference,
         &options.credentials,
         layers,
         config_bytes,
-        MediaType::Manifest,
-        feedback,
+        MediaType::Manifest.as_ref(),
+        move |event| {
+            let event = match event {
+                oras::PushProgress::Started { layers } => progress::RegistryEvent::PushStarted {
+                    reference: reference.clone(),
+                    layers,
+                },
+                oras::PushProgress::LayerUploading {
+                    kind,
+                    uploaded,
+                    total,
+                } => progress::RegistryEvent::LayerUploading {
+                    kind,
+                    uploaded,
+                    total: Some(total),
+                },
+                oras::PushProgress::LayerUploaded { kind, digest } => {
+                    progress::RegistryEvent::LayerUploaded { kind, digest }
+                }
+            };
+            notify(feedback_tx.as_ref(), event);
+        },
     )
     .await?;
 
@@ -522,19 +628,37 @@ pub async fn push_update_with_feedback(
 fn layer_input_with_title(
     path: PathBuf,
     media_type: MediaType,
-) -> anyhow::Result<oras::LayerInput> {
+) -> anyhow::Result<oras::LayerInput<MediaType>> {
     let title = path
         .file_name()
         .and_then(|value| value.to_str())
         .context("layer path has invalid filename")?;
     let mut annotations = BTreeMap::new();
     annotations.insert(
-        oci_client::annotations::ORG_OPENCONTAINERS_IMAGE_TITLE.to_string(),
+        ORG_OPENCONTAINERS_IMAGE_TITLE.to_string(),
         sanitize_relative_file_path(title)?,
     );
     Ok(oras::LayerInput {
         path,
-        media_type,
+        kind: media_type,
+        annotations: Some(annotations),
+    })
+}
+
+/// Delta layer keeps file name of manifest as title, deltas against
+/// different bases are told apart by it
+fn delta_layer_input(
+    base_dir: &Path,
+    file: &crate::image::manifest::File,
+) -> anyhow::Result<oras::LayerInput<MediaType>> {
+    let mut annotations = BTreeMap::new();
+    annotations.insert(
+        ORG_OPENCONTAINERS_IMAGE_TITLE.to_string(),
+        sanitize_relative_file_path(&file.name)?,
+    );
+    Ok(oras::LayerInput {
+        path: file.full_name(base_dir),
+        kind: MediaType::Delta,
         annotations: Some(annotations),
     })
 }
@@ -547,15 +671,48 @@ struct ArtifactBinding {
     local_name: String,
 }
 
+/// Delta of `manifest` against `base` version, if both its layers were pushed
+fn select_delta_layers<'a>(
+    manifest: &'a Manifest,
+    layers: &[oras::BlobDescriptor],
+    base: Option<&Version>,
+) -> Option<&'a Delta> {
+    let delta = manifest.delta_for(base?)?;
+    [&delta.root.file, &delta.verity.file]
+        .into_iter()
+        .all(|file| find_delta_layer(layers, &file.name).is_some())
+        .then_some(delta)
+}
+
+/// Layers to download: kernel, and either `delta` or full root and verity images
 fn select_artifact_bindings(
     manifest: &Manifest,
     layers: &[oras::BlobDescriptor],
+    delta: Option<&Delta>,
 ) -> anyhow::Result<Vec<ArtifactBinding>> {
-    let mut bindings = vec![
-        required_binding(layers, MediaType::Uki, manifest.kernel.name.clone())?,
-        required_binding(layers, MediaType::Root, manifest.store.name.clone())?,
-        required_binding(layers, MediaType::Verity, manifest.verity.name.clone())?,
-    ];
+    let mut bindings = vec![required_binding(
+        layers,
+        MediaType::Uki,
+        manifest.kernel.name.clone(),
+    )?];
+    if let Some(delta) = delta {
+        for file in [&delta.root.file, &delta.verity.file] {
+            let layer = find_delta_layer(layers, &file.name)
+                .with_context(|| format!("missing delta layer {}", file.name))?;
+            bindings.push(make_binding(layer, MediaType::Delta, file.name.clone()));
+        }
+    } else {
+        bindings.push(required_binding(
+            layers,
+            MediaType::Root,
+            manifest.store.name.clone(),
+        )?);
+        bindings.push(required_binding(
+            layers,
+            MediaType::Verity,
+            manifest.verity.name.clone(),
+        )?);
+    }
 
     if let Some(layer) = find_layer_by_media_type(layers, MediaType::Changelog) {
         bindings.push(make_binding(
@@ -587,6 +744,20 @@ fn find_layer_by_media_type(
         .find(|layer| layer.media_type == media_type.as_ref())
 }
 
+fn find_delta_layer<'a>(
+    layers: &'a [oras::BlobDescriptor],
+    name: &str,
+) -> Option<&'a oras::BlobDescriptor> {
+    layers.iter().find(|layer| {
+        layer.media_type == MediaType::Delta.as_ref()
+            && layer
+                .annotations
+                .as_ref()
+                .and_then(|annotations| annotations.get(ORG_OPENCONTAINERS_IMAGE_TITLE))
+                .is_some_and(|title| title == name)
+    })
+}
+
 fn make_binding(
     layer: &oras::BlobDescriptor,
     kind: MediaType,
@@ -610,34 +781,6 @@ fn changelog_local_name(layer: &oras::BlobDescriptor) -> String {
     "changelog.txt".to_string()
 }
 
-fn sanitize_path_component(value: &str) -> String {
-    value.replace(':', "_")
-}
-
-fn sanitize_relative_file_path(value: &str) -> anyhow::Result<String> {
-    let mut out = PathBuf::new();
-    let path = Path::new(value);
-    if path.is_absolute() {
-        anyhow::bail!("absolute path is not allowed: {value}");
-    }
-    for component in path.components() {
-        match component {
-            std::path::Component::Normal(part) => out.push(part),
-            std::path::Component::CurDir => {}
-            std::path::Component::ParentDir => {
-                anyhow::bail!("parent dir is not allowed: {value}");
-            }
-            std::path::Component::RootDir | std::path::Component::Prefix(_) => {
-                anyhow::bail!("non-relative path is not allowed: {value}");
-            }
-        }
-    }
-    if out.as_os_str().is_empty() {
-        anyhow::bail!("empty path is not allowed");
-    }
-    Ok(out.to_string_lossy().into_owned())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -653,47 +796,117 @@ mod tests {
         }
     }
 
-    #[test]
-    fn changelog_local_name_defaults_when_title_missing() {
-        let value = changelog_local_name(&descriptor(MediaType::Changelog));
-        assert_eq!(value, "changelog.txt");
+    fn file(name: &str) -> crate::image::manifest::File {
+        crate::image::manifest::File {
+            name: name.to_string(),
+            sha256sum: [0; 32],
+            unpacked_size: None,
+        }
     }
 
-    #[test]
-    fn sanitize_relative_file_path_rejects_parent_dir() {
-        let err = sanitize_relative_file_path("../../etc/passwd").expect_err("must fail");
-        assert!(err.to_string().contains("parent dir"));
+    fn delta_file(name: &str) -> crate::image::manifest::DeltaFile {
+        crate::image::manifest::DeltaFile {
+            file: file(name),
+            target_sha256: [0; 32],
+            target_size: 10,
+        }
     }
 
-    #[test]
-    fn select_artifact_bindings_returns_error_when_required_layer_missing() {
-        let manifest = Manifest {
+    fn test_manifest() -> Manifest {
+        Manifest {
             meta: Default::default(),
             manifest_version: 1,
             system: None,
             version: "1.0".to_string(),
             root_verity_hash: "0123456789abcdef0123456789abcdef".to_string(),
-            kernel: crate::image::manifest::File {
-                name: "kernel.efi".to_string(),
-                sha256sum: [0; 32],
-                unpacked_size: None,
-            },
-            store: crate::image::manifest::File {
-                name: "root.raw".to_string(),
-                sha256sum: [0; 32],
-                unpacked_size: None,
-            },
-            verity: crate::image::manifest::File {
-                name: "verity.raw".to_string(),
-                sha256sum: [0; 32],
-                unpacked_size: None,
-            },
-        };
+            kernel: file("kernel.efi"),
+            store: file("root.raw"),
+            verity: file("verity.raw"),
+            deltas: vec![Delta {
+                base_version: "0.9".to_string(),
+                base_hash: "fedcba9876543210".to_string(),
+                root: delta_file("deltas/0.9/root.delta"),
+                verity: delta_file("deltas/0.9/verity.delta"),
+            }],
+        }
+    }
+
+    fn delta_descriptor(name: &str) -> oras::BlobDescriptor {
+        let mut annotations = BTreeMap::new();
+        annotations.insert(ORG_OPENCONTAINERS_IMAGE_TITLE.to_string(), name.to_string());
+        oras::BlobDescriptor {
+            digest: format!("sha256:{name}"),
+            annotations: Some(annotations),
+            ..descriptor(MediaType::Delta)
+        }
+    }
+
+    #[test]
+    fn changelog_local_name_defaults_when_title_missing() {
+        let value = changelog_local_name(&descriptor(MediaType::Changelog));
+        assert_eq!(value, "changelog.txt");
+    }
+
+    #[test]
+    fn select_artifact_bindings_returns_error_when_required_layer_missing() {
+        let manifest = test_manifest();
         let layers = vec![descriptor(MediaType::Uki)];
-        let err = select_artifact_bindings(&manifest, &layers).expect_err("must fail");
+        let err = select_artifact_bindings(&manifest, &layers, None).expect_err("must fail");
         assert!(err.to_string().contains("missing required artifact layer"));
     }
 
+    #[test]
+    fn select_artifact_bindings_prefers_delta_against_base() {
+        let manifest = test_manifest();
+        let layers = vec![
+            descriptor(MediaType::Uki),
+            descriptor(MediaType::Root),
+            descriptor(MediaType::Verity),
+            delta_descriptor("deltas/0.9/root.delta"),
+            delta_descriptor("deltas/0.9/verity.delta"),
+        ];
+        let base = Version::new("0.9".to_string(), Some("fedcba9876543210".to_string()));
+
+        let delta = select_delta_layers(&manifest, &layers, Some(&base));
+        assert_eq!(delta, manifest.deltas.first());
+        let bindings = select_artifact_bindings(&manifest, &layers, delta).expect("bindings");
+        let kinds: Vec<_> = bindings
+            .iter()
+            .map(|binding| (binding.kind, binding.local_name.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (MediaType::Uki, "kernel.efi"),
+                (MediaType::Delta, "deltas/0.9/root.delta"),
+                (MediaType::Delta, "deltas/0.9/verity.delta"),
+            ]
+        );
+        assert_eq!(bindings[2].digest, "sha256:deltas/0.9/verity.delta");
+    }
+
+    #[test]
+    fn select_delta_layers_falls_back_to_full_images() {
+        let manifest = test_manifest();
+        let layers = vec![
+            descriptor(MediaType::Uki),
+            descriptor(MediaType::Root),
+            descriptor(MediaType::Verity),
+            delta_descriptor("deltas/0.9/root.delta"),
+        ];
+        let base = Version::new("0.9".to_string(), Some("fedcba9876543210".to_string()));
+        let other = Version::new("0.8".to_string(), Some("fedcba9876543210".to_string()));
+
+        // Verity delta wasn't pushed
+        assert_eq!(select_delta_layers(&manifest, &layers, Some(&base)), None);
+        assert_eq!(select_delta_layers(&manifest, &layers, Some(&other)), None);
+        assert_eq!(select_delta_layers(&manifest, &layers, None), None);
+
+        let bindings = select_artifact_bindings(&manifest, &layers, None).expect("bindings");
+        let kinds: Vec<_> = bindings.iter().map(|binding| binding.kind).collect();
+        assert_eq!(kinds, [MediaType::Uki, MediaType::Root, MediaType::Verity]);
+    }
+
     #[tokio::test]
     async fn prune_keeps_two_newest_directories_per_repository() {
         let unique = SystemTime::now()
@@ -728,7 +941,7 @@ mod tests {
         .await
         .expect("prune ok");
 
-        assert!(tokio::fs::try_exists(&d1).await.expect("exists d1") == false);
+        assert!(!tokio::fs::try_exists(&d1).await.expect("exists d1"));
         assert!(tokio::fs::try_exists(&d2).await.expect("exists d2"));
         assert!(tokio::fs::try_exists(&d3).await.expect("exists d3"));
 
@@ -745,4 +958,17 @@ mod tests {
             .expect("title annotation");
         assert_eq!(title, "image.efi");
     }
+
+    #[test]
+    fn delta_layer_input_keeps_relative_title() {
+        let input = delta_layer_input(Path::new("/updates"), &file("deltas/0.9/root.delta"))
+            .expect("layer input");
+        assert_eq!(input.path, PathBuf::from("/updates/deltas/0.9/root.delta"));
+        assert_eq!(input.kind, MediaType::Delta);
+        let title = input
+            .annotations
+            .and_then(|a| a.get("org.opencontainers.image.title").cloned())
+            .expect("title annotation");
+        assert_eq!(title, "deltas/0.9/root.delta");
+    }
 }
@@ -1,13 +1,15 @@
 // SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use strum::IntoStaticStr;
 
 use super::MediaType;
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, IntoStaticStr)]
 #[serde(tag = "event", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
 pub enum RegistryEvent {
     DiscoverStarted {
         reference: String,
@@ -34,6 +36,15 @@ pub enum RegistryEvent {
         downloaded: u64,
         total: Option<u64>,
     },
+    BlobResumed {
+        digest: String,
+        offset: u64,
+        total: Option<u64>,
+    },
+    /// Blob is already downloaded by previous pull
+    BlobCached {
+        digest: String,
+    },
     BlobVerified {
         digest: String,
     },
@@ -3,6 +3,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 // Fields here should match with output of `nixos-rebuild list-generation --json`
 // FIXME: this structure eventually would be merged with `Generations` from sibling PR
@@ -19,6 +20,24 @@ pub struct ProfileElement {
     pub num: i32,
     pub store_path: PathBuf,
     pub current: bool,
+    // Modification time of profile link, i.e. when generation was created
+    pub created: Option<SystemTime>,
+}
+
+/// State of boot loader entry for generation
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, strum::IntoStaticStr,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BootEntry {
+    /// No boot loader entry, generation can't be booted
+    #[default]
+    Missing,
+    /// Entry exists, but other generation is booted by default
+    Present,
+    /// Entry is default boot candidate
+    Default,
 }
 
 /// This structure more or less matched output of nixos-rebuild list-generations --json
@@ -38,6 +57,18 @@ pub struct GenerationDetails {
 
     pub store_path: PathBuf,
 
+    // Creation time of generation, RFC 3339
+    #[serde(default)]
+    pub date: Option<String>,
+
+    // Names of specialisations, from bootspec
+    #[serde(default)]
+    pub specialisations: Vec<String>,
+
+    // Size of generation closure in bytes, if nix was able to report it
+    #[serde(default)]
+    pub closure_size: Option<u64>,
+
     // This generation match /run/current-system
     pub current: bool,
 
@@ -50,6 +81,10 @@ pub struct GenerationDetails {
     // This generation match /nix/var/nix/profiles/system default
     pub default: bool,
 
+    // State of boot loader entry, derived from bootctl record
+    #[serde(default)]
+    pub boot_entry: BootEntry,
+
     // Raw bootspec data
     pub bootspec: bootspec::v1::GenerationV1,
 
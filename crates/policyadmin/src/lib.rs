@@ -3,5 +3,9 @@
 
 pub mod policy;
 pub mod policy_manager;
+pub mod policy_oci;
 pub mod policy_repo;
+pub mod policy_signature;
+pub mod policy_status;
 pub mod policy_urls;
+pub mod policy_validator;
@@ -9,6 +9,7 @@ use tracing::{debug, error, info};
 use crate::policy_manager::{PolicyManager, UpdateReceiver};
 use crate::policy_repo::PolicyRepoMonitor;
 use crate::policy_urls::PolicyUrlMonitor;
+use crate::policy_validator::PolicyValidator;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Copy, Default)]
 #[serde(rename_all = "kebab-case")]
@@ -17,6 +18,8 @@ pub enum PolicySourceType {
     None,
     GitUrl,
     PerPolicy,
+    /* Per-policy OCI artifacts, updater URL is OCI reference */
+    Oci,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
@@ -29,6 +32,15 @@ pub struct PolicySource {
     pub branch: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub poll_interval_secs: Option<u64>,
+    /* Keys, which policy artifacts must be signed with, `<name>:<base64>` */
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_keys: Vec<String>,
+    /* Use HTTP instead of HTTPS for OCI registry */
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub insecure: bool,
+    /* Credentials of OCI registry, `GIVC_REGISTRY_*=value` lines */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials_file: Option<PathBuf>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
@@ -48,6 +60,9 @@ pub struct PolicyConfigPolicy {
     pub vms: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub per_policy_updater: Option<PolicyConfigPolicyUpdater>,
+    /* Checks of policy files before they are sent to VMs */
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validators: Vec<PolicyValidator>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
@@ -82,8 +97,9 @@ pub fn run_policy_admin(
     let policy_path = policy_root.join("data").join("vm-policies");
     debug!("policy-monitor: starting policy monitor...");
 
-    let (manager, updates) =
-        PolicyManager::new(policy_path, &config).context("Policy manager initialization failed")?;
+    let status_file = policy_root.join("delivery-status.json");
+    let (manager, updates) = PolicyManager::new(policy_path, status_file, &config)
+        .context("Policy manager initialization failed")?;
 
     debug!("policy-monitor: thread spawned successfully");
 
@@ -100,8 +116,12 @@ pub fn run_policy_admin(
                 }
             }
         }
-        PolicySourceType::PerPolicy => {
-            info!("Monitoring URLs for Policy updates");
+        PolicySourceType::PerPolicy | PolicySourceType::Oci => {
+            if source_type == PolicySourceType::Oci {
+                info!("Monitoring OCI registry for Policy updates");
+            } else {
+                info!("Monitoring URLs for Policy updates");
+            }
             match PolicyUrlMonitor::new(&policy_root, &config, manager.clone()) {
                 Ok(monitor) => Some(monitor.start()),
                 Err(e) => {
@@ -5,7 +5,7 @@ use std::{
     collections::HashMap,
     fs,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 use anyhow::{Result, anyhow};
@@ -15,11 +15,25 @@ use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
 use crate::policy::PolicyConfig;
+use crate::policy_status::{ChangeKind, DeliveryStore};
+use crate::policy_validator::validate;
+
+/// Change of policy file, which is propagated to VMs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileChange {
+    /// File was added or modified, its content is sent
+    Update,
+    /// File was deleted. Without file name, policy is removed whatever file it was
+    Delete,
+    /// File was renamed within policy, to given file name
+    Rename(String),
+}
 
 pub struct Update {
     pub vm_name: String,
     pub file: PathBuf,
     pub policy: String,
+    pub change: FileChange,
 }
 
 pub type UpdateReceiver = UnboundedReceiver<(Update, Sender<Result<()>>)>;
@@ -33,6 +47,8 @@ pub type UpdateReceiver = UnboundedReceiver<(Update, Sender<Result<()>>)>;
 pub struct Policy {
     pub policy_name: String,
     pub file: String,
+    pub change: FileChange,
+    pub revision: Option<String>,
 }
 
 /*
@@ -48,17 +64,24 @@ pub struct PolicyManager {
     configs: PolicyConfig,
     update_channel: UnboundedSender<(Update, Sender<Result<()>>)>,
     workers: HashMap<String, (UnboundedSender<Policy>, JoinHandle<()>)>,
+    status: Arc<DeliveryStore>,
+    /* Head of git repo, shared by all policies */
+    repo_revision: Mutex<Option<String>>,
+    /* Heads of per-policy URLs */
+    revisions: Mutex<HashMap<String, String>>,
 }
 
 impl PolicyManager {
     /*
      * 1. Loads config.
      * 2. Creates a shared Tokio Runtime.
-     * 3. Spawns initial workers for VMs defined in the config.
+     * 3. Loads delivery status of policies from `status_file`.
+     * 4. Spawns initial workers for VMs defined in the config.
      */
     #[allow(clippy::unnecessary_wraps)]
     pub(crate) fn new(
         store_dir: PathBuf,
+        status_file: PathBuf,
         configs: &PolicyConfig,
     ) -> Result<(Arc<Self>, UpdateReceiver)> {
         let vm_names = configs
@@ -75,6 +98,9 @@ impl PolicyManager {
             configs: configs.clone(),
             update_channel,
             workers: HashMap::new(),
+            status: Arc::new(DeliveryStore::load(status_file)?),
+            repo_revision: Mutex::new(None),
+            revisions: Mutex::new(HashMap::new()),
         };
 
         for vm_name in vm_names {
@@ -100,7 +126,12 @@ impl PolicyManager {
         let (tx, rx) = unbounded_channel();
         let vm_name = vm.to_string();
 
-        let handle = tokio::spawn(Self::worker_loop(vm_name, rx, self.update_channel.clone()));
+        let handle = tokio::spawn(Self::worker_loop(
+            vm_name,
+            rx,
+            self.update_channel.clone(),
+            self.status.clone(),
+        ));
 
         self.workers.insert(vm.to_string(), (tx, handle));
     }
@@ -111,50 +142,107 @@ impl PolicyManager {
      * The main loop running inside each VM's worker thread.
      * Blocks on rx.recv() until a message arrives, then uses the Runtime to
      * execute the async push_policy_update call.
+     * Attempts and their outcome are recorded in delivery status.
      */
     async fn worker_loop(
         vm: String,
         mut rx: UnboundedReceiver<Policy>,
         update_channel: UnboundedSender<(Update, Sender<Result<()>>)>,
+        status: Arc<DeliveryStore>,
     ) {
         debug!("policy-admin: Worker [{}] started.", vm);
 
         while let Some(msg) = rx.recv().await {
+            let policy = msg.policy_name;
+            let file = PathBuf::from(msg.file);
+            status.attempt(&vm, &policy, msg.revision, &file, &msg.change);
+
             /* Execute async code synchronously within this thread */
             let (tx, rx) = oneshot::channel();
             let _ = update_channel.send((
                 Update {
                     vm_name: vm.clone(),
-                    file: msg.file.into(),
-                    policy: msg.policy_name,
+                    file,
+                    policy: policy.clone(),
+                    change: msg.change,
                 },
                 tx,
             ));
-            let result = rx.await;
+            let result = rx
+                .await
+                .unwrap_or_else(|_| Err(anyhow!("Update was dropped before delivery")));
 
-            if let Err(e) = result {
+            if let Err(e) = &result {
                 error!("policy-admin:Worker [{}]: Failed to push update: {}", vm, e);
             } else {
                 debug!("policy-admin:Worker [{}]: Successfully pushed update", vm);
             }
+            status.finish(&vm, &policy, &result);
         }
     }
 
+    /**
+     * Delivery status of policies to VMs, as recorded by workers.
+     */
+    #[must_use]
+    pub fn status(&self) -> &DeliveryStore {
+        &self.status
+    }
+
+    /*
+     * set_repo_revision
+     *
+     * Records head of git repo, which policies sent from now on come from.
+     */
+    pub(crate) fn set_repo_revision(&self, revision: String) {
+        *self.repo_revision.lock().unwrap() = Some(revision);
+    }
+
+    /*
+     * set_policy_revision
+     *
+     * Records head of per-policy URL, taking precedence over repo head.
+     */
+    pub(crate) fn set_policy_revision(&self, policy_name: &str, revision: String) {
+        self.revisions
+            .lock()
+            .unwrap()
+            .insert(policy_name.to_string(), revision);
+    }
+
+    fn revision(&self, policy_name: &str) -> Option<String> {
+        self.revisions
+            .lock()
+            .unwrap()
+            .get(policy_name)
+            .cloned()
+            .or_else(|| self.repo_revision.lock().unwrap().clone())
+    }
+
     /*
-     * send_to_vm
+     * send_change_to_vm
      *
      * Helper to construct metadata JSON and send the policy task to the worker.
+     * Files aren't validated here, see `send_change_to_vms()`.
      */
-    pub(crate) fn send_to_vm(&self, vm: &str, policy_name: &str, file_path: &Path) -> Result<()> {
+    pub(crate) fn send_change_to_vm(
+        &self,
+        vm: &str,
+        policy_name: &str,
+        file_path: &Path,
+        change: FileChange,
+    ) -> Result<()> {
         debug!(
-            "policy-admin:send_to_vm() sending policy {} to vm {}.",
-            policy_name, vm
+            "policy-admin:send_change_to_vm() sending policy {} ({:?}) to vm {}.",
+            policy_name, change, vm
         );
 
         if let Some((tx, _)) = self.workers.get(vm) {
             tx.send(Policy {
                 policy_name: policy_name.to_string(),
                 file: file_path.to_string_lossy().to_string(),
+                change,
+                revision: self.revision(policy_name),
             })
             .map_err(|_| anyhow!("Worker channel disconnected"))?;
             Ok(())
@@ -163,61 +251,165 @@ impl PolicyManager {
         }
     }
 
-    /**
-     * Iterates through all policies. If the VM is subscribed to a policy,
-     * sends all files in that policy directory to the VM.
+    /*
+     * send_change_to_vms
      *
-     * # Errors
+     * Sends change of policy file to every VM of `vms`. Updated file is
+     * validated once, before fan-out. Invalid file isn't sent, VMs keep
+     * previous one, and rejection shows in their delivery status.
+     */
+    pub(crate) async fn send_change_to_vms<S: AsRef<str>>(
+        &self,
+        vms: &[S],
+        policy_name: &str,
+        file_path: &Path,
+        change: &FileChange,
+    ) {
+        if *change == FileChange::Update
+            && let Some(policy) = self.configs.policies.get(policy_name)
+            && let Err(e) = validate(&policy.validators, file_path).await
+        {
+            error!("policy-admin:Rejected policy {}: {:#}", policy_name, e);
+            let revision = self.revision(policy_name);
+            let result = Err(e);
+            for vm in vms {
+                self.status.attempt(
+                    vm.as_ref(),
+                    policy_name,
+                    revision.clone(),
+                    file_path,
+                    change,
+                );
+                self.status.finish(vm.as_ref(), policy_name, &result);
+            }
+            return;
+        }
+
+        for vm in vms {
+            if let Err(e) =
+                self.send_change_to_vm(vm.as_ref(), policy_name, file_path, change.clone())
+            {
+                error!(
+                    "policy-admin:Failed to send policy {} to {}: {:#}",
+                    policy_name,
+                    vm.as_ref(),
+                    e
+                );
+            }
+        }
+    }
+
+    /*
+     * send_policies
      *
+     * Sends every file of policies to those of `vms`, which are subscribed to
+     * them. Each file is validated once, whatever number of VMs it goes to.
      */
-    pub fn send_all_policies(&self, vm_name: &str) -> Result<()> {
+    async fn send_policies(&self, vms: &[&str]) {
         for policy in self.configs.policies.keys() {
             let policy_dir = self.policy_dir.join(policy);
+            let subscribed = vms
+                .iter()
+                .copied()
+                .filter(|vm| self.is_subscribed(policy, vm))
+                .collect::<Vec<_>>();
+            if subscribed.is_empty() || !policy_dir.exists() {
+                continue;
+            }
 
-            if policy_dir.exists() {
-                /* Send only those policies that the VM is subscribed to. */
-                let should_process = self
-                    .configs
-                    .policies
-                    .get(policy)
-                    .is_some_and(|p| p.vms.iter().any(|v| v == vm_name));
-
-                if should_process {
-                    /* Read directory and send every file */
-                    match fs::read_dir(&policy_dir) {
-                        Ok(entries) => {
-                            for entry in entries.flatten() {
-                                let path = entry.path();
-
-                                if path.is_file() {
-                                    if let Err(e) = self.send_to_vm(vm_name, policy, &path) {
-                                        error!(
-                                            "policy-admin:Failed to send policy update for {}: {}",
-                                            vm_name, e
-                                        );
-                                    } else {
-                                        debug!(
-                                            "policy-admin:Sent policy update for {}: {}",
-                                            vm_name,
-                                            path.display()
-                                        );
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            warn!(
-                                "policy-manager: policy directory '{}' access failed!: {}",
-                                policy_dir.display(),
-                                e
-                            );
+            if !Self::has_files(&policy_dir) {
+                /* Policy was emptied while VM was away, make sure it isn't enforced */
+                self.send_change_to_vms(&subscribed, policy, Path::new(""), &FileChange::Delete)
+                    .await;
+                continue;
+            }
+
+            /* Read directory and send every file */
+            match fs::read_dir(&policy_dir) {
+                Ok(entries) => {
+                    for entry in entries.flatten() {
+                        let path = entry.path();
+                        if path.is_file() {
+                            self.send_change_to_vms(
+                                &subscribed,
+                                policy,
+                                &path,
+                                &FileChange::Update,
+                            )
+                            .await;
                         }
                     }
                 }
+                Err(e) => {
+                    warn!(
+                        "policy-manager: policy directory '{}' access failed!: {}",
+                        policy_dir.display(),
+                        e
+                    );
+                }
             }
         }
+    }
 
-        Ok(())
+    /**
+     * Sends every file of policies, which VM is subscribed to. Policies emptied
+     * while VM was away are removed from it.
+     */
+    pub async fn send_all_policies(&self, vm_name: &str) {
+        self.send_policies(&[vm_name]).await;
+    }
+
+    /**
+     * Resends policies to re-registered VM, see `send_all_policies()`, and
+     * retries deletions of policy files, which previously failed to reach it.
+     * Failed updates and renames are covered by resending current files.
+     * Failures are logged, and recorded in delivery status.
+     */
+    pub async fn resync_vm(&self, vm_name: &str) {
+        self.send_all_policies(vm_name).await;
+
+        for (policy, status) in self.status.failed(vm_name) {
+            if status.change != Some(ChangeKind::Delete) || !self.is_subscribed(&policy, vm_name) {
+                continue;
+            }
+            let file_path = status.file.map_or_else(PathBuf::new, |file| {
+                self.policy_dir.join(&policy).join(file)
+            });
+            if file_path.exists() {
+                /* File is back, it was sent above */
+                continue;
+            }
+            info!(
+                "policy-admin:Retrying deletion of policy {} for {}",
+                policy, vm_name
+            );
+            if let Err(e) = self.send_change_to_vm(vm_name, &policy, &file_path, FileChange::Delete)
+            {
+                error!(
+                    "policy-admin:Failed to retry policy removal for {}: {}",
+                    vm_name, e
+                );
+            }
+        }
+    }
+
+    /*
+     * reject_update
+     *
+     * Records rejected update of `policy`, or of every policy for git repo,
+     * as failed delivery to subscribed VMs, which keep previous policy.
+     */
+    pub(crate) fn reject_update(&self, policy: Option<&str>, error: &anyhow::Error) {
+        let policies = self
+            .configs
+            .policies
+            .iter()
+            .filter(|(name, _)| policy.is_none_or(|policy| policy == name.as_str()));
+        for (name, config) in policies {
+            for vm in &config.vms {
+                self.status.reject(vm, name, error);
+            }
+        }
     }
 
     /*
@@ -226,60 +418,235 @@ impl PolicyManager {
      * Triggers a full policy refresh for every registered VM worker.
      */
     #[allow(clippy::unnecessary_wraps)]
-    pub(crate) fn force_update_all_vms(&self) -> Result<()> {
-        for vm in self.workers.keys() {
-            if let Err(e) = self.send_all_policies(vm) {
-                error!("Failed to force update for VM {}: {}", vm, e);
+    pub(crate) async fn force_update_all_vms(&self) -> Result<()> {
+        let vms = self.workers.keys().map(String::as_str).collect::<Vec<_>>();
+        self.send_policies(&vms).await;
+        Ok(())
+    }
+
+    fn has_files(policy_dir: &Path) -> bool {
+        fs::read_dir(policy_dir).is_ok_and(|entries| {
+            entries
+                .flatten()
+                .any(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        })
+    }
+
+    fn is_subscribed(&self, policy_name: &str, vm_name: &str) -> bool {
+        self.configs
+            .policies
+            .get(policy_name)
+            .is_some_and(|p| p.vms.iter().any(|v| v == vm_name))
+    }
+
+    /*
+     * dispatch_change
+     *
+     * Sends change of policy file to every VM subscribed to the policy.
+     * Updates of files, which no longer exist, are skipped. Deleted and renamed
+     * files are removed from store, as checkout leaves them behind.
+     */
+    async fn dispatch_change(&self, policy_name: &str, file_name: &str, change: &FileChange) {
+        let full_path = self.policy_dir.join(policy_name).join(file_name);
+
+        match change {
+            FileChange::Update if !full_path.exists() => return,
+            FileChange::Update => {}
+            FileChange::Delete | FileChange::Rename(_) => {
+                if let Err(e) = fs::remove_file(&full_path)
+                    && e.kind() != std::io::ErrorKind::NotFound
+                {
+                    warn!(
+                        "policy-admin: failed to remove '{}': {}",
+                        full_path.display(),
+                        e
+                    );
+                }
             }
         }
-        Ok(())
+
+        let vms = self
+            .configs
+            .policies
+            .get(policy_name)
+            .map(|p| p.vms.as_slice())
+            .unwrap_or_default();
+        self.send_change_to_vms(vms, policy_name, &full_path, change)
+            .await;
     }
 
     /*
      * process_changeset
      *
-     * Parses a git-style changeset string (e.g., "M vm-policies/policyA/file.json")
-     * and dispatches updates to relevant VMs.
+     * Dispatches changes of policy files in git-style changeset, see
+     * `parse_changeset()`, to relevant VMs.
      */
     #[allow(clippy::unnecessary_wraps)]
-    pub(crate) fn process_changeset(&self, changeset: &str) -> Result<()> {
-        for line in changeset.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
-            debug!("policy-admin:process_changeset() changeset: {}.", changeset);
+    pub(crate) async fn process_changeset(&self, changeset: &str) -> Result<()> {
+        debug!("policy-admin:process_changeset() changeset: {}.", changeset);
 
-            /* Path is the second token of a line */
-            let Some(relative_path) = line.split_whitespace().nth(1) else {
-                continue;
-            };
+        for (policy_name, file_name, change) in parse_changeset(changeset) {
+            self.dispatch_change(policy_name, file_name, &change).await;
+        }
+        Ok(())
+    }
+}
 
-            let Some(rest) = relative_path.strip_prefix("vm-policies/") else {
-                continue;
-            };
+/*
+ * parse_changeset
+ *
+ * Parses a git-style changeset string (e.g., "M vm-policies/policyA/file.json",
+ * "R vm-policies/policyA/old.json vm-policies/policyA/new.json") into changes
+ * of policy files, as (policy, file, change), in order they have to be applied.
+ * Rename within policy is followed by update, as content may have changed along
+ * with name. Move between policies, or in/out of vm-policies, is deletion from
+ * old policy and update of new one. Files outside of vm-policies are skipped.
+ */
+fn parse_changeset(changeset: &str) -> Vec<(&str, &str, FileChange)> {
+    let mut changes = Vec::new();
+    for line in changeset.lines() {
+        let mut tokens = line.split_whitespace();
+        let (Some(kind), Some(path)) = (tokens.next(), tokens.next()) else {
+            continue;
+        };
 
-            let Some((policy_name, file_name)) = ({
-                let mut parts = rest.split('/');
-                parts.next().zip(parts.next())
-            }) else {
-                continue;
-            };
-
-            let full_path = self.policy_dir.join(policy_name).join(file_name);
-
-            if full_path.exists() {
-                let vms = self
-                    .configs
-                    .policies
-                    .get(policy_name)
-                    .into_iter()
-                    .flat_map(|p| &p.vms);
-                for vm in vms {
-                    let _ = self.send_to_vm(vm, policy_name, &full_path);
+        match (kind, tokens.next()) {
+            ("D", _) => {
+                if let Some((policy_name, file_name)) = policy_file(path) {
+                    changes.push((policy_name, file_name, FileChange::Delete));
+                }
+            }
+            ("R", Some(new_path)) => match (policy_file(path), policy_file(new_path)) {
+                (Some((old_policy, old_file)), Some((policy_name, file_name)))
+                    if old_policy == policy_name =>
+                {
+                    changes.push((
+                        policy_name,
+                        old_file,
+                        FileChange::Rename(file_name.to_string()),
+                    ));
+                    changes.push((policy_name, file_name, FileChange::Update));
+                }
+                (old, new) => {
+                    if let Some((policy_name, file_name)) = old {
+                        changes.push((policy_name, file_name, FileChange::Delete));
+                    }
+                    if let Some((policy_name, file_name)) = new {
+                        changes.push((policy_name, file_name, FileChange::Update));
+                    }
+                }
+            },
+            _ => {
+                if let Some((policy_name, file_name)) = policy_file(path) {
+                    changes.push((policy_name, file_name, FileChange::Update));
                 }
             }
         }
+    }
+    changes
+}
+
+/*
+ * Splits "vm-policies/<policy>/<file>" into policy and file name. Files in
+ * nested directories are not policy files, as only top level ones are sent.
+ */
+fn policy_file(path: &str) -> Option<(&str, &str)> {
+    let rest = path.strip_prefix("vm-policies/")?;
+    let (policy, file) = rest.split_once('/')?;
+    (!policy.is_empty() && !file.is_empty() && !file.contains('/')).then_some((policy, file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_files() {
+        assert_eq!(
+            policy_file("vm-policies/firewall/rules.json"),
+            Some(("firewall", "rules.json"))
+        );
+        assert_eq!(policy_file("vm-policies/firewall"), None);
+        assert_eq!(policy_file("vm-policies/firewall/"), None);
+        assert_eq!(policy_file("vm-policies//rules.json"), None);
+        assert_eq!(policy_file("vm-policies/firewall/nested/rules.json"), None);
+        assert_eq!(policy_file("other/firewall/rules.json"), None);
+        assert_eq!(policy_file("README.md"), None);
+    }
+
+    #[test]
+    fn changesets() {
+        let changeset = "\
+M vm-policies/firewall/rules.json
+A vm-policies/usb/allow.cedar
+D vm-policies/usb/old.cedar
+M README.md
+R vm-policies/firewall/a.json vm-policies/firewall/b.json
+R vm-policies/firewall/c.json vm-policies/usb/c.json
+R vm-policies/usb/d.json docs/d.json
+R docs/e.json vm-policies/usb/e.json
+R vm-policies/usb/f.json
+
+";
+        assert_eq!(
+            parse_changeset(changeset),
+            [
+                ("firewall", "rules.json", FileChange::Update),
+                ("usb", "allow.cedar", FileChange::Update),
+                ("usb", "old.cedar", FileChange::Delete),
+                (
+                    "firewall",
+                    "a.json",
+                    FileChange::Rename("b.json".to_string())
+                ),
+                ("firewall", "b.json", FileChange::Update),
+                ("firewall", "c.json", FileChange::Delete),
+                ("usb", "c.json", FileChange::Update),
+                ("usb", "d.json", FileChange::Delete),
+                ("usb", "e.json", FileChange::Update),
+                ("usb", "f.json", FileChange::Update),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn validate_once_before_fan_out() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("policyadmin-manager-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        let runs = dir.join("runs");
+        let file = dir.join("rules.json");
+        fs::write(&file, "{}")?;
+
+        /* Validator counts its runs, and rejects every file */
+        let configs: PolicyConfig = serde_json::from_value(serde_json::json!({
+            "source": { "type": "none" },
+            "policies": {
+                "firewall": {
+                    "vms": ["vm1", "vm2"],
+                    "validators": [{
+                        "type": "command",
+                        "command": ["sh", "-c", format!("echo >> {}; exit 1", runs.display()), "sh"],
+                    }],
+                },
+            },
+        }))?;
+        let (manager, _updates) =
+            PolicyManager::new(dir.clone(), dir.join("status.json"), &configs)?;
+
+        manager
+            .send_change_to_vms(&["vm1", "vm2"], "firewall", &file, &FileChange::Update)
+            .await;
+        assert_eq!(fs::read_to_string(&runs)?.lines().count(), 1);
+        let statuses = manager.status().list(None);
+        assert_eq!(statuses.len(), 2);
+        for (_, policy, status) in statuses {
+            assert_eq!(policy, "firewall");
+            assert!(status.error.is_some_and(|e| e.contains("invalid")));
+        }
+
+        manager.status().flush();
+        fs::remove_dir_all(&dir)?;
         Ok(())
     }
 }
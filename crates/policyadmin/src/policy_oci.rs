@@ -0,0 +1,366 @@
+// SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail, ensure};
+use givc_oras::{
+    ArtifactOptions, ClientProtocol, RegistryCredentials, pull_artifact, resolve_artifact_digest,
+};
+use tracing::{debug, info};
+
+use crate::policy::PolicyConfig;
+use crate::policy_signature::{Rejected, SIGNATURE_SUFFIX, TrustedKeys};
+use crate::policy_urls::PolicyUpdate;
+
+/*
+ * OciSource
+ *
+ * Pulls per-policy OCI artifacts, for `oci` policy source. Updater URL of
+ * policy is OCI reference pinned to tag or digest, e.g. `registry/policies/proxy:stable`.
+ * Head of policy is manifest digest, tag is polled for new digests.
+ * Every titled layer of artifact is policy file, `<file>.sig` layers are
+ * detached signatures, checked if trusted keys are configured.
+ */
+pub(crate) struct OciSource {
+    protocol: ClientProtocol,
+    credentials: RegistryCredentials,
+    /* Artifacts are pulled here, and moved into policy store once verified */
+    staging_dir: PathBuf,
+}
+
+/*
+ * Registry credentials, from file of `KEY=value` lines, with same keys
+ * as environment of ota-update: `GIVC_REGISTRY_TOKEN`, or
+ * `GIVC_REGISTRY_USERNAME` and `GIVC_REGISTRY_PASSWORD`. So same file can be
+ * used as systemd `EnvironmentFile`.
+ */
+fn parse_credentials(text: &str) -> Result<RegistryCredentials> {
+    let (mut username, mut password, mut token) = (None, None, None);
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            bail!("Expected `KEY=value`, got {line}");
+        };
+        let value = value.trim().trim_matches('"').to_string();
+        match key.trim() {
+            "GIVC_REGISTRY_USERNAME" => username = Some(value),
+            "GIVC_REGISTRY_PASSWORD" => password = Some(value),
+            "GIVC_REGISTRY_TOKEN" => token = Some(value),
+            other => debug!("policy-oci: Ignoring {} of credentials file", other),
+        }
+    }
+    match (username, password, token) {
+        (None, None, Some(token)) => Ok(RegistryCredentials::Bearer { token }),
+        (Some(username), Some(password), None) => {
+            Ok(RegistryCredentials::Basic { username, password })
+        }
+        (None, None, None) => Ok(RegistryCredentials::Anonymous),
+        _ => bail!("Expected either token, or both username and password"),
+    }
+}
+
+impl OciSource {
+    pub(crate) fn new(policy_root: &Path, configs: &PolicyConfig) -> Result<Self> {
+        let protocol = if configs.source.insecure {
+            ClientProtocol::Http
+        } else {
+            ClientProtocol::Https
+        };
+        let credentials = match &configs.source.credentials_file {
+            Some(path) => fs::read_to_string(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|text| parse_credentials(&text))
+                .with_context(|| format!("Loading registry credentials {}", path.display()))?,
+            None => RegistryCredentials::Anonymous,
+        };
+        Ok(Self {
+            protocol,
+            credentials,
+            staging_dir: policy_root.join("oci-staging"),
+        })
+    }
+
+    /*
+     * poll
+     *
+     * Resolves digest of `reference`, and if it differs from `current_head`,
+     * pulls artifact into `policy_dir`, replacing files of previous one.
+     * Returns: Ok(Some(update)) if artifact was pulled, with files of previous
+     * artifact it no longer has.
+     */
+    pub(crate) async fn poll(
+        &self,
+        name: &str,
+        reference: &str,
+        current_head: &str,
+        trusted_keys: Option<&TrustedKeys>,
+        policy_dir: &Path,
+    ) -> Result<Option<PolicyUpdate>> {
+        let options = ArtifactOptions {
+            reference: reference.parse()?,
+            credentials: self.credentials.clone(),
+            client_protocol: self.protocol.clone(),
+        };
+
+        let digest = resolve_artifact_digest(&options).await?;
+        if digest == current_head {
+            return Ok(None);
+        }
+
+        debug!("policy-oci: [{}] Pulling {}@{}", name, reference, digest);
+        let staging = self.staging_dir.join(name);
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        let result = match pull_artifact(&options, &digest, &staging).await {
+            Ok(pulled) => Self::verify(&pulled.files, &staging, &digest, trusted_keys)
+                .with_context(|| Rejected {
+                    revision: digest.clone(),
+                    policy: Some(name.to_string()),
+                })
+                .and_then(|files| {
+                    let removed = Self::install(&staging, &files, policy_dir)?;
+                    Ok(PolicyUpdate {
+                        head: digest.clone(),
+                        files,
+                        removed,
+                    })
+                }),
+            Err(e) => Err(e.context(format!("Pulling {reference}@{digest}"))),
+        };
+        let _ = fs::remove_dir_all(&staging);
+
+        result.map(Some)
+    }
+
+    /*
+     * Policy files of pulled artifact, checked against their signatures,
+     * if trusted keys are configured. Whole artifact is rejected if any of
+     * its files isn't trusted.
+     */
+    fn verify(
+        pulled: &[PathBuf],
+        staging: &Path,
+        digest: &str,
+        trusted_keys: Option<&TrustedKeys>,
+    ) -> Result<Vec<String>> {
+        let files = pulled
+            .iter()
+            .filter_map(|path| path.file_name()?.to_str())
+            .filter(|file| !file.ends_with(SIGNATURE_SUFFIX))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        ensure!(!files.is_empty(), "Artifact has no policy files");
+
+        if let Some(keys) = trusted_keys {
+            for file in &files {
+                let content = fs::read(staging.join(file))?;
+                let signatures =
+                    fs::read_to_string(staging.join(format!("{file}{SIGNATURE_SUFFIX}")))
+                        .with_context(|| format!("No signature of {file} in artifact"))?;
+                let key = keys
+                    .verify(&content, &signatures)
+                    .with_context(|| format!("Verifying {file}"))?;
+                info!("policy-oci: {} of {} is signed by {}", file, digest, key);
+            }
+        }
+        Ok(files)
+    }
+
+    /*
+     * Moves verified `files` from staging into `policy_dir`, replacing previous ones.
+     * Returns names of removed files, which VMs have to delete too.
+     */
+    fn install(staging: &Path, files: &[String], policy_dir: &Path) -> Result<Vec<String>> {
+        /* Files of previous artifact would be resent on VM registration */
+        fs::create_dir_all(policy_dir)?;
+        let mut removed = Vec::new();
+        for entry in fs::read_dir(policy_dir)?.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.file_type().is_ok_and(|t| t.is_file()) && !files.contains(&name) {
+                fs::remove_file(entry.path())?;
+                removed.push(name);
+            }
+        }
+        for file in files {
+            fs::rename(staging.join(file), policy_dir.join(file))?;
+        }
+        removed.sort();
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use givc_oras::test_registry::TestRegistry;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("policyadmin-oci-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn source(root: &Path, credentials_file: Option<&Path>) -> Result<OciSource> {
+        let configs = serde_json::from_value(serde_json::json!({
+            "source": {
+                "type": "oci",
+                "insecure": true,
+                "credentials_file": credentials_file,
+            },
+            "policies": {},
+        }))?;
+        OciSource::new(root, &configs)
+    }
+
+    fn file_names(dir: &Path) -> Result<Vec<String>> {
+        let mut names = fs::read_dir(dir)?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+            .collect::<Result<Vec<_>>>()?;
+        names.sort();
+        Ok(names)
+    }
+
+    #[test]
+    fn credentials() -> Result<()> {
+        assert_eq!(parse_credentials("")?, RegistryCredentials::Anonymous);
+        assert_eq!(
+            parse_credentials("# registry\nGIVC_REGISTRY_TOKEN=\"abc\"\nOTHER=1\n")?,
+            RegistryCredentials::Bearer {
+                token: "abc".to_string()
+            }
+        );
+        assert_eq!(
+            parse_credentials("GIVC_REGISTRY_USERNAME=reader\nGIVC_REGISTRY_PASSWORD=a=b\n")?,
+            RegistryCredentials::Basic {
+                username: "reader".to_string(),
+                password: "a=b".to_string(),
+            }
+        );
+        assert!(parse_credentials("GIVC_REGISTRY_USERNAME=reader").is_err());
+        assert!(parse_credentials("GIVC_REGISTRY_TOKEN").is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pull_and_prune() -> Result<()> {
+        let registry = TestRegistry::start().await?;
+        let reference = registry.reference("policies/usb", "stable");
+        let root = temp_dir("pull");
+        let policy_dir = root.join("usb");
+        let source = source(&root, None)?;
+
+        let first = registry.push_artifact(
+            "policies/usb",
+            "stable",
+            &[("allow.cedar", b"permit"), ("old.cedar", b"forbid")],
+        );
+        let update = source
+            .poll("usb", &reference, "", None, &policy_dir)
+            .await?
+            .expect("update");
+        assert_eq!(
+            update,
+            PolicyUpdate {
+                head: first.clone(),
+                files: vec!["allow.cedar".to_string(), "old.cedar".to_string()],
+                removed: Vec::new(),
+            }
+        );
+        assert!(
+            source
+                .poll("usb", &reference, &first, None, &policy_dir)
+                .await?
+                .is_none()
+        );
+
+        let second = registry.push_artifact(
+            "policies/usb",
+            "stable",
+            &[("allow.cedar", b"permit all"), ("new.cedar", b"permit")],
+        );
+        let update = source
+            .poll("usb", &reference, &first, None, &policy_dir)
+            .await?
+            .expect("update");
+        assert_eq!(update.head, second);
+        assert_eq!(update.removed, vec!["old.cedar".to_string()]);
+        assert_eq!(file_names(&policy_dir)?, ["allow.cedar", "new.cedar"]);
+        assert_eq!(fs::read(policy_dir.join("allow.cedar"))?, b"permit all");
+
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reject_unsigned() -> Result<()> {
+        let registry = TestRegistry::start().await?;
+        let reference = registry.reference("policies/usb", "stable");
+        let root = temp_dir("unsigned");
+        let policy_dir = root.join("usb");
+        fs::create_dir_all(&policy_dir)?;
+        fs::write(policy_dir.join("allow.cedar"), "previous")?;
+        let keys = TrustedKeys::parse(&format!("policy-1:{}=", "A".repeat(43)))?;
+
+        let digest = registry.push_artifact(
+            "policies/usb",
+            "stable",
+            &[
+                ("allow.cedar", b"permit"),
+                ("allow.cedar.sig", b"policy-1:AAAA"),
+            ],
+        );
+        let error = source(&root, None)?
+            .poll("usb", &reference, "", Some(&keys), &policy_dir)
+            .await
+            .expect_err("must be rejected");
+        let rejected = error.downcast_ref::<Rejected>().expect("rejection");
+        assert_eq!(rejected.revision, digest);
+        assert_eq!(rejected.policy.as_deref(), Some("usb"));
+
+        /* Previous revision stays in store */
+        assert_eq!(fs::read(policy_dir.join("allow.cedar"))?, b"previous");
+        assert_eq!(file_names(&policy_dir)?, ["allow.cedar"]);
+
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pull_with_credentials() -> Result<()> {
+        let registry = TestRegistry::start_with_basic_auth("reader", "secret").await?;
+        let reference = registry.reference("policies/usb", "stable");
+        let root = temp_dir("credentials");
+        fs::create_dir_all(&root)?;
+        let credentials_file = root.join("credentials");
+        fs::write(
+            &credentials_file,
+            "GIVC_REGISTRY_USERNAME=reader\nGIVC_REGISTRY_PASSWORD=secret\n",
+        )?;
+        let digest = registry.push_artifact("policies/usb", "stable", &[("allow.cedar", b"ok")]);
+
+        let policy_dir = root.join("usb");
+        assert!(
+            source(&root, None)?
+                .poll("usb", &reference, "", None, &policy_dir)
+                .await
+                .is_err()
+        );
+        let update = source(&root, Some(&credentials_file))?
+            .poll("usb", &reference, "", None, &policy_dir)
+            .await?
+            .expect("update");
+        assert_eq!(update.head, digest);
+        assert!(source(&root, Some(&root.join("missing"))).is_err());
+
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+}
@@ -18,6 +18,9 @@ use tracing::{debug, error, info};
 
 use crate::policy::PolicyConfig;
 use crate::policy_manager::PolicyManager;
+use crate::policy_signature::{Rejected, SIGNATURE_SUFFIX, TrustedKeys, trusted_keys};
+
+const POLICY_DIR: &str = "vm-policies";
 
 /*
  * RepoState
@@ -27,6 +30,8 @@ use crate::policy_manager::PolicyManager;
 struct RepoState {
     new_head: Option<ObjectId>,
     old_head: Option<ObjectId>,
+    /* Last remote revision, which failed signature verification */
+    rejected_head: Option<ObjectId>,
 }
 
 /*
@@ -42,6 +47,7 @@ pub struct PolicyRepoMonitor {
     remote_name: String,
     poll_interval: Duration,
     manager: Arc<PolicyManager>,
+    trusted_keys: Option<TrustedKeys>,
 
     // Mutable State (Thread-Safe)
     state: Arc<Mutex<RepoState>>,
@@ -58,6 +64,7 @@ impl PolicyRepoMonitor {
         let destination = policy_root.as_ref().join("data");
 
         let interval_secs = configs.source.poll_interval_secs.unwrap_or(300);
+        let trusted_keys = trusted_keys(&configs.source.trusted_keys)?;
 
         let monitor = Arc::new(Self {
             url,
@@ -66,9 +73,11 @@ impl PolicyRepoMonitor {
             remote_name: "origin".to_string(),
             poll_interval: Duration::from_secs(interval_secs),
             manager,
+            trusted_keys,
             state: Arc::new(Mutex::new(RepoState {
                 new_head: None,
                 old_head: None,
+                rejected_head: None,
             })),
         });
 
@@ -83,12 +92,17 @@ impl PolicyRepoMonitor {
                         "url",
                     );
 
-                    if remote_url.is_some_and(|u| *u == monitor.url) {
+                    if remote_url.is_some_and(|u| *u == monitor.url.as_bytes())
+                        && monitor
+                            .verify_revision(&repo, repo.head_id()?.detach())
+                            .is_ok()
+                    {
                         let mut state = monitor.state.lock().unwrap();
                         let head = repo.head_id()?;
                         state.new_head = Some(head.detach());
                         state.old_head = Some(head.detach());
                         drop(state);
+                        monitor.manager.set_repo_revision(head.to_string());
                         return Ok(monitor);
                     }
                     info!("policy-repo: Remote URL mismatch, will be cloned during update.");
@@ -104,6 +118,35 @@ impl PolicyRepoMonitor {
         Ok(monitor)
     }
 
+    /*
+     * verify_revision
+     * With trusted keys configured, revision must carry `vm-policies.sig`
+     * at repo root, signing hex id of its `vm-policies` tree. Tree id covers
+     * content of every policy file, so one signature per revision suffices.
+     */
+    fn verify_revision(&self, repo: &gix::Repository, id: ObjectId) -> Result<()> {
+        let Some(keys) = &self.trusted_keys else {
+            return Ok(());
+        };
+        let tree = repo.find_commit(id)?.tree()?;
+        let policies = tree
+            .lookup_entry_by_path(POLICY_DIR)?
+            .with_context(|| format!("No {POLICY_DIR} in revision {id}"))?;
+        let signature_file = format!("{POLICY_DIR}{SIGNATURE_SUFFIX}");
+        let signatures = tree
+            .lookup_entry_by_path(&signature_file)?
+            .with_context(|| format!("No {signature_file} in revision {id}"))?
+            .object()?;
+        let signatures =
+            std::str::from_utf8(&signatures.data).context("Malformed signature file")?;
+
+        let key = keys
+            .verify(policies.object_id().to_string().as_bytes(), signatures)
+            .with_context(|| format!("Verifying revision {id}"))?;
+        info!("policy-repo: revision {} is signed by {}", id, key);
+        Ok(())
+    }
+
     /*
      * clone_repo
      * Performs a fresh clone of the repository.
@@ -124,6 +167,19 @@ impl PolicyRepoMonitor {
         let (mut checkout, _) = prepare.fetch_then_checkout(gix::progress::Discard, interrupt)?;
         let (repo, _) = checkout.main_worktree(gix::progress::Discard, interrupt)?;
 
+        /* Unverified clone never replaces current checkout */
+        let head = repo.head_id()?.detach();
+        if let Err(e) = self.verify_revision(&repo, head) {
+            drop(repo);
+            std::fs::remove_dir_all(&temp_dest)?;
+            let e = e.context(Rejected {
+                revision: head.to_string(),
+                policy: None,
+            });
+            self.manager.reject_update(None, &e);
+            return Err(e);
+        }
+
         /* Drop repo handle so we can move the directory */
         drop(repo);
 
@@ -140,6 +196,7 @@ impl PolicyRepoMonitor {
         let mut state = self.state.lock().unwrap();
         state.new_head = Some(head.detach());
         state.old_head = None; // Reset old head on fresh clone
+        self.manager.set_repo_revision(head.to_string());
 
         debug!(
             "policy-repo: Cloned successfully. HEAD: {}",
@@ -188,7 +245,17 @@ impl PolicyRepoMonitor {
         /* 3. Checkout if changed */
         let mut state = self.state.lock().unwrap();
         // Note: Using unwrap_or to force update if local head is missing
-        if Some(remote_id) == state.new_head {
+        if Some(remote_id) == state.new_head || Some(remote_id) == state.rejected_head {
+            Ok(false)
+        } else if let Err(e) = self.verify_revision(&repo, remote_id) {
+            let e = e.context(Rejected {
+                revision: remote_id.to_string(),
+                policy: None,
+            });
+            /* Keep previous revision checked out and distributed */
+            error!("policy-repo: {:#}, keeping {:?}", e, state.new_head);
+            self.manager.reject_update(None, &e);
+            state.rejected_head = Some(remote_id);
             Ok(false)
         } else {
             debug!("policy-repo: Update detected. Moving to {}", remote_id);
@@ -220,6 +287,7 @@ impl PolicyRepoMonitor {
             /* Update Heads */
             state.old_head = state.new_head;
             state.new_head = Some(remote_id);
+            self.manager.set_repo_revision(remote_id.to_string());
             Ok(true)
         }
     }
@@ -245,14 +313,32 @@ impl PolicyRepoMonitor {
         old_tree
             .changes()?
             .for_each_to_obtain_tree(&new_tree, |change| {
-                if let Some((kind, subject)) = match change {
-                    Change::Modification { location, .. } => Some(('M', location)),
-                    Change::Addition { location, .. } => Some(('A', location)),
-                    Change::Deletion { location, .. } => Some(('D', location)),
-                    Change::Rewrite { .. } => None,
-                } {
-                    let _ = write!(changes_str, "{kind} {}", subject.to_str_lossy());
-                }
+                let _ = match change {
+                    Change::Modification { location, .. } => {
+                        writeln!(changes_str, "M {}", location.to_str_lossy())
+                    }
+                    Change::Addition { location, .. } => {
+                        writeln!(changes_str, "A {}", location.to_str_lossy())
+                    }
+                    Change::Deletion { location, .. } => {
+                        writeln!(changes_str, "D {}", location.to_str_lossy())
+                    }
+                    Change::Rewrite {
+                        location,
+                        copy: true,
+                        ..
+                    } => writeln!(changes_str, "A {}", location.to_str_lossy()),
+                    Change::Rewrite {
+                        source_location,
+                        location,
+                        ..
+                    } => writeln!(
+                        changes_str,
+                        "R {} {}",
+                        source_location.to_str_lossy(),
+                        location.to_str_lossy()
+                    ),
+                };
                 Ok::<_, std::convert::Infallible>(Action::Continue(()))
             })?;
 
@@ -275,13 +361,13 @@ impl PolicyRepoMonitor {
                 match self.get_update() {
                     Ok(true) => match self.get_change_set() {
                         Ok(changes) if !changes.is_empty() => {
-                            if let Err(e) = self.manager.process_changeset(&changes) {
+                            if let Err(e) = self.manager.process_changeset(&changes).await {
                                 error!("policy-repo: failed to apply changeset: {}", e);
                                 update_err = true;
                             }
                         }
                         Ok(_) => {
-                            if let Err(e) = self.manager.force_update_all_vms() {
+                            if let Err(e) = self.manager.force_update_all_vms().await {
                                 error!("policy-repo: failed to force update: {}", e);
                                 update_err = true;
                             }
@@ -305,7 +391,7 @@ impl PolicyRepoMonitor {
 
                 if update_err {
                     let _ = self.ensure_clone().await;
-                    let _ = self.manager.force_update_all_vms();
+                    let _ = self.manager.force_update_all_vms().await;
                     update_err = false;
                 } else {
                     wait_time = self.poll_interval;
@@ -0,0 +1,75 @@
+// SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt;
+
+use anyhow::{Context, Result};
+pub use givc_common::signature::TrustedKeys;
+
+/* Suffix of detached signature, next to signed artifact */
+pub(crate) const SIGNATURE_SUFFIX: &str = ".sig";
+
+/**
+ * Parses trusted keys of policy source config, which policy artifacts must be
+ * signed with before they are written to policy store and distributed to VMs.
+ * No keys means signatures aren't checked.
+ *
+ * # Errors
+ * Fails on malformed key.
+ */
+pub fn trusted_keys(keys: &[String]) -> Result<Option<TrustedKeys>> {
+    if keys.is_empty() {
+        return Ok(None);
+    }
+    keys.iter()
+        .map(|text| text.parse().with_context(|| format!("trusted key {text}")))
+        .collect::<Result<_>>()
+        .map(Some)
+}
+
+/*
+ * Rejected
+ *
+ * Context of error, which rejected untrusted update of policy source: of one
+ * policy, or of every policy for git repo. Rejections are recorded in delivery
+ * status of subscribed VMs, see `PolicyManager::reject_update()`.
+ */
+#[derive(Debug)]
+pub(crate) struct Rejected {
+    pub(crate) revision: String,
+    pub(crate) policy: Option<String>,
+}
+
+impl fmt::Display for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rejected update {}", self.revision)?;
+        match &self.policy {
+            Some(policy) => write!(f, " of policy {policy}"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_trusted_keys() -> Result<()> {
+        assert!(trusted_keys(&[])?.is_none());
+        let key = format!("policy-1:{}", "A".repeat(43) + "=");
+        assert!(trusted_keys(&[key])?.is_some_and(|keys| !keys.is_empty()));
+        assert!(trusted_keys(&["short:AAAA".to_string()]).is_err());
+
+        let error = anyhow::anyhow!("not signed by trusted key").context(Rejected {
+            revision: "sha256:abc".to_string(),
+            policy: Some("usb".to_string()),
+        });
+        assert_eq!(
+            error.to_string(),
+            "Rejected update sha256:abc of policy usb"
+        );
+        assert!(error.downcast_ref::<Rejected>().is_some());
+        Ok(())
+    }
+}
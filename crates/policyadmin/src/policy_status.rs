@@ -0,0 +1,415 @@
+// SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, mpsc},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use base16ct::HexDisplay;
+use sha2::{Digest, Sha256};
+use tracing::{error, warn};
+
+use crate::policy_manager::FileChange;
+
+/* Kind of last change sent, `FileChange` without payload */
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangeKind {
+    Update,
+    Delete,
+    Rename,
+}
+
+impl std::fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::Rename => "rename",
+        })
+    }
+}
+
+impl From<&FileChange> for ChangeKind {
+    fn from(change: &FileChange) -> Self {
+        match change {
+            FileChange::Update => Self::Update,
+            FileChange::Delete => Self::Delete,
+            FileChange::Rename(_) => Self::Rename,
+        }
+    }
+}
+
+/// Delivery of policy to VM, as of last attempt
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct DeliveryStatus {
+    /// Revision of policy source (git head, or head of per-policy URL)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    /// Policy file last sent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change: Option<ChangeKind>,
+    /// Checksum of policy file last sent, absent for deletions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Unix time (seconds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_attempt: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<u64>,
+    /// Error of last attempt, absent if it succeeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/* VM name -> policy name -> delivery status */
+type DeliveryMap = BTreeMap<String, BTreeMap<String, DeliveryStatus>>;
+
+#[derive(Debug)]
+enum Message {
+    Save(DeliveryMap),
+    #[cfg(test)]
+    Flush(mpsc::Sender<()>),
+}
+
+/*
+ * DeliveryStore
+ *
+ * Tracks which policy revision each VM has applied, persisted as JSON in
+ * the policy store, so it survives restarts of givc-admin. Snapshots are
+ * written by background thread, so async workers never wait for disk.
+ */
+pub struct DeliveryStore {
+    state: Mutex<DeliveryMap>,
+    tx: mpsc::Sender<Message>,
+}
+
+fn persist(path: &Path, state: &DeliveryMap) {
+    let temp = path.with_extension("tmp");
+    let result = serde_json::to_vec_pretty(state)
+        .context("Serializing delivery status")
+        .and_then(|json| fs::write(&temp, json).context("Writing delivery status"))
+        .and_then(|()| fs::rename(&temp, path).context("Replacing delivery status"));
+    if let Err(e) = result {
+        warn!("policy-status: failed to persist {}: {e:#}", path.display());
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+impl DeliveryStore {
+    /*
+     * Loads persisted state from `path`, starting from empty one if it
+     * doesn't exist or is unreadable, and starts its writer thread.
+     * Fails only if thread can't be spawned.
+     */
+    pub(crate) fn load(path: PathBuf) -> Result<Self> {
+        let state = if path.exists() {
+            fs::read(&path)
+                .context("Reading delivery status")
+                .and_then(|json| serde_json::from_slice(&json).context("Parsing delivery status"))
+                .unwrap_or_else(|e| {
+                    warn!("policy-status: {e:#}, starting from scratch");
+                    DeliveryMap::new()
+                })
+        } else {
+            DeliveryMap::new()
+        };
+        let (tx, rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("policy-status".into())
+            .spawn(move || {
+                for message in rx {
+                    match message {
+                        Message::Save(state) => persist(&path, &state),
+                        #[cfg(test)]
+                        Message::Flush(done) => {
+                            let _ = done.send(());
+                        }
+                    }
+                }
+            })
+            .context("spawning delivery status writer")?;
+        Ok(Self {
+            state: Mutex::new(state),
+            tx,
+        })
+    }
+
+    fn persist(&self, state: &DeliveryMap) {
+        if self.tx.send(Message::Save(state.clone())).is_err() {
+            error!("policy-status: writer is gone, delivery status not persisted");
+        }
+    }
+
+    /* Waits until all queued snapshots are written */
+    #[cfg(test)]
+    pub(crate) fn flush(&self) {
+        let (done, wait) = mpsc::channel();
+        if self.tx.send(Message::Flush(done)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
+
+    fn update(&self, vm: &str, policy: &str, f: impl FnOnce(&mut DeliveryStatus)) {
+        let mut state = self.state.lock().unwrap();
+        f(state
+            .entry(vm.to_string())
+            .or_default()
+            .entry(policy.to_string())
+            .or_default());
+        self.persist(&state);
+    }
+
+    /*
+     * Records start of delivery of `change` of `file` to VM.
+     */
+    pub(crate) fn attempt(
+        &self,
+        vm: &str,
+        policy: &str,
+        revision: Option<String>,
+        file: &Path,
+        change: &FileChange,
+    ) {
+        let sha256 = match change {
+            FileChange::Update => fs::read(file)
+                .ok()
+                .map(|content| format!("{:x}", HexDisplay(Sha256::digest(content).as_slice()))),
+            FileChange::Delete | FileChange::Rename(_) => None,
+        };
+        let file = match change {
+            FileChange::Rename(new_name) => Some(new_name.clone()),
+            FileChange::Update | FileChange::Delete => file
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned()),
+        };
+        self.update(vm, policy, |status| {
+            status.revision = revision;
+            status.file = file;
+            status.change = Some(change.into());
+            status.sha256 = sha256;
+            status.last_attempt = Some(now());
+        });
+    }
+
+    /*
+     * Records outcome of last delivery attempt to VM.
+     */
+    pub(crate) fn finish(&self, vm: &str, policy: &str, result: &Result<()>) {
+        self.update(vm, policy, |status| match result {
+            Ok(()) => {
+                status.last_success = status.last_attempt;
+                status.error = None;
+            }
+            Err(e) => status.error = Some(format!("{e:#}")),
+        });
+    }
+
+    /*
+     * Records rejected update of policy source, which wasn't sent to VM.
+     * Revision and file stay of last delivery, as VM keeps them.
+     */
+    pub(crate) fn reject(&self, vm: &str, policy: &str, error: &anyhow::Error) {
+        self.update(vm, policy, |status| {
+            status.last_attempt = Some(now());
+            status.error = Some(format!("{error:#}"));
+        });
+    }
+
+    /**
+     * Delivery status of every policy, to every VM or only to `vm`,
+     * as (vm, policy, status) triples.
+     */
+    #[must_use]
+    pub fn list(&self, vm: Option<&str>) -> Vec<(String, String, DeliveryStatus)> {
+        let state = self.state.lock().unwrap();
+        state
+            .iter()
+            .filter(|(name, _)| vm.is_none_or(|vm| vm == *name))
+            .flat_map(|(name, policies)| {
+                policies
+                    .iter()
+                    .map(|(policy, status)| (name.clone(), policy.clone(), status.clone()))
+            })
+            .collect()
+    }
+
+    /*
+     * Policies, which last delivery to `vm` failed.
+     */
+    pub(crate) fn failed(&self, vm: &str) -> Vec<(String, DeliveryStatus)> {
+        let state = self.state.lock().unwrap();
+        state
+            .get(vm)
+            .into_iter()
+            .flatten()
+            .filter(|(_, status)| status.error.is_some())
+            .map(|(policy, status)| (policy.clone(), status.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "policyadmin-status-{}-{name}.json",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn attempt_and_finish() -> Result<()> {
+        let path = temp_path("attempt");
+        let policy_file = path.with_extension("cedar");
+        fs::write(&policy_file, "permit(principal, action, resource);")?;
+        let store = DeliveryStore::load(path.clone())?;
+
+        store.attempt(
+            "vm1",
+            "usb",
+            Some("abc".to_string()),
+            &policy_file,
+            &FileChange::Update,
+        );
+        let (vm, policy, status) = store.list(None).remove(0);
+        assert_eq!((vm.as_str(), policy.as_str()), ("vm1", "usb"));
+        assert_eq!(status.revision.as_deref(), Some("abc"));
+        assert_eq!(
+            status.file.as_deref(),
+            policy_file.file_name().and_then(|name| name.to_str())
+        );
+        assert_eq!(status.change, Some(ChangeKind::Update));
+        assert_eq!(
+            status.sha256,
+            Some(format!(
+                "{:x}",
+                HexDisplay(Sha256::digest(fs::read(&policy_file)?).as_slice())
+            ))
+        );
+        assert!(status.last_attempt.is_some());
+        assert_eq!(status.last_success, None);
+
+        store.finish("vm1", "usb", &Ok(()));
+        let status = store.list(Some("vm1")).remove(0).2;
+        assert_eq!(status.last_success, status.last_attempt);
+        assert_eq!(status.error, None);
+        assert!(store.failed("vm1").is_empty());
+
+        /* Rename records new name, deletion has no checksum */
+        store.attempt(
+            "vm2",
+            "usb",
+            None,
+            &policy_file,
+            &FileChange::Rename("new.cedar".to_string()),
+        );
+        assert_eq!(
+            store.list(Some("vm2")).remove(0).2.file.as_deref(),
+            Some("new.cedar")
+        );
+        store.attempt("vm2", "usb", None, &policy_file, &FileChange::Delete);
+        let status = store.list(Some("vm2")).remove(0).2;
+        assert_eq!(status.change, Some(ChangeKind::Delete));
+        assert_eq!(status.sha256, None);
+        assert_eq!(store.list(None).len(), 2);
+
+        fs::remove_file(policy_file)?;
+        Ok(())
+    }
+
+    #[test]
+    fn failed_deliveries_persist() -> Result<()> {
+        let path = temp_path("failed");
+        let store = DeliveryStore::load(path.clone())?;
+        let missing = Path::new("/nonexistent/firewall.json");
+        store.attempt("vm1", "firewall", None, missing, &FileChange::Update);
+        store.finish("vm1", "firewall", &Err(anyhow!("vm is down")));
+        store.attempt("vm1", "usb", None, missing, &FileChange::Delete);
+        store.finish("vm1", "usb", &Ok(()));
+
+        let failed = store.failed("vm1");
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "firewall");
+        assert_eq!(failed[0].1.error.as_deref(), Some("vm is down"));
+        assert_eq!(failed[0].1.sha256, None);
+        assert!(store.failed("vm2").is_empty());
+
+        /* Outcome survives restart */
+        store.flush();
+        let reloaded = DeliveryStore::load(path.clone())?;
+        assert_eq!(reloaded.list(None), store.list(None));
+        assert_eq!(reloaded.failed("vm1"), failed);
+
+        /* Later success clears error */
+        store.finish("vm1", "firewall", &Ok(()));
+        assert!(store.failed("vm1").is_empty());
+
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn rejected_update() -> Result<()> {
+        let path = temp_path("rejected");
+        let store = DeliveryStore::load(path.clone())?;
+        let missing = Path::new("/nonexistent/usb.cedar");
+        store.attempt(
+            "vm1",
+            "usb",
+            Some("sha256:good".to_string()),
+            missing,
+            &FileChange::Update,
+        );
+        store.finish("vm1", "usb", &Ok(()));
+
+        let error = anyhow!("not signed by trusted key").context("Rejected update sha256:bad");
+        store.reject("vm1", "usb", &error);
+        let status = store.list(Some("vm1")).remove(0).2;
+        assert_eq!(status.revision.as_deref(), Some("sha256:good"));
+        assert_eq!(status.file.as_deref(), Some("usb.cedar"));
+        assert_eq!(
+            status.error.as_deref(),
+            Some("Rejected update sha256:bad: not signed by trusted key")
+        );
+        assert_eq!(store.failed("vm1").len(), 1);
+
+        store.flush();
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn corrupt_file_starts_empty() -> Result<()> {
+        let path = temp_path("corrupt");
+        fs::write(&path, "{not json")?;
+        let store = DeliveryStore::load(path.clone())?;
+        assert!(store.list(None).is_empty());
+
+        /* It is replaced on first change */
+        store.attempt("vm1", "usb", None, &path, &FileChange::Delete);
+        store.flush();
+        let reloaded = DeliveryStore::load(path.clone())?;
+        assert_eq!(reloaded.list(None).len(), 1);
+
+        fs::remove_file(path)?;
+        Ok(())
+    }
+}
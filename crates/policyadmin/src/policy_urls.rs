@@ -18,8 +18,10 @@ use sha2::{Digest, Sha256};
 use tokio::{sync::Mutex, time::sleep};
 use tracing::{debug, error, info, warn};
 
-use crate::policy::PolicyConfig;
-use crate::policy_manager::PolicyManager;
+use crate::policy::{PolicyConfig, PolicySourceType};
+use crate::policy_manager::{FileChange, PolicyManager};
+use crate::policy_oci::OciSource;
+use crate::policy_signature::{Rejected, SIGNATURE_SUFFIX, TrustedKeys, trusted_keys};
 
 /* -----------------------------------------------------------------------------
  * Constants
@@ -27,11 +29,23 @@ use crate::policy_manager::PolicyManager;
 const CONFIG_FILE_NAME: &str = "config.json";
 const DEFAULT_POLL_INTERVAL: u64 = 60;
 
+/* New revision of policy, stored in its directory */
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct PolicyUpdate {
+    pub head: String,
+    /* Files of new revision */
+    pub files: Vec<String>,
+    /* Files of previous revision, removed from store */
+    pub removed: Vec<String>,
+}
+
 /* -----------------------------------------------------------------------------
  * PolicyUrlMonitor
  *
  * Monitors a list of URLs defined in a JSON config.
- * - Downloads files if ETag/Last-Modified/Hash changes.
+ * - Downloads files if ETag/Last-Modified/Hash changes, or pulls OCI
+ *   artifacts if their digest changes, for `oci` source.
+ * - Verifies detached signature `<url>.sig`, if trusted keys are configured.
  * - Updates the local config with the new "head" state.
  * - Dispatches updates to the PolicyManager.
  * -------------------------------------------------------------------------- */
@@ -45,6 +59,10 @@ pub(crate) struct PolicyUrlMonitor {
     /* The root directory where downloaded policy files are stored */
     output_dir: PathBuf,
     manager: Arc<PolicyManager>,
+    /* Taken from config passed by admin, not from local copy */
+    trusted_keys: Option<Arc<TrustedKeys>>,
+    /* URLs are OCI references, if set */
+    oci: Option<Arc<OciSource>>,
 }
 
 impl PolicyUrlMonitor {
@@ -64,6 +82,10 @@ impl PolicyUrlMonitor {
         let destination = root.join("data").join("vm-policies");
         let config_file = root.join(CONFIG_FILE_NAME);
         let cfgpath = config_file.display();
+        let trusted_keys = trusted_keys(&configs.source.trusted_keys)?.map(Arc::new);
+        let oci = (configs.source.kind == PolicySourceType::Oci)
+            .then(|| OciSource::new(root, configs).map(Arc::new))
+            .transpose()?;
 
         /* Ensure output directory exists */
         fs::create_dir_all(&destination).with_context(|| {
@@ -96,6 +118,8 @@ impl PolicyUrlMonitor {
             config_file,
             output_dir: destination,
             manager,
+            trusted_keys,
+            oci,
         })
     }
 
@@ -160,6 +184,11 @@ impl PolicyUrlMonitor {
             return Ok(());
         }
 
+        if !current_head.is_empty() {
+            self.manager
+                .set_policy_revision(&policy_name, current_head.clone());
+        }
+
         /* Retry request every 30 seconds until the first successful response */
         let mut wait_time = Duration::from_secs(30);
 
@@ -169,26 +198,33 @@ impl PolicyUrlMonitor {
         );
 
         loop {
-            match self.poll_once(&policy_name, &url, &current_head).await {
-                Ok(Some((new_head, file_name))) => {
+            match self.poll(&policy_name, &url, &current_head).await {
+                Ok(Some(PolicyUpdate {
+                    head: new_head,
+                    files,
+                    removed,
+                })) => {
                     info!(
                         "policy-url-monitor: [{}] Update detected -> {}",
                         policy_name, new_head
                     );
 
-                    /* 1. Notify PolicyManager for each VM */
-                    let full_path = self.output_dir.join(&policy_name).join(&file_name);
-
-                    for vm in &vms {
-                        if let Err(e) =
-                            self.manager
-                                .send_to_vm(vm, policy_name.as_str(), &full_path)
-                        {
-                            error!(
-                                "policy-url-monitor: [{}] Failed to send to VM {}: {}",
-                                policy_name, vm, e
-                            );
-                        }
+                    /* 1. Notify PolicyManager for each VM, removed files are deleted first */
+                    self.manager
+                        .set_policy_revision(&policy_name, new_head.clone());
+                    let changes = removed
+                        .iter()
+                        .map(|file_name| (file_name, FileChange::Delete))
+                        .chain(
+                            files
+                                .iter()
+                                .map(|file_name| (file_name, FileChange::Update)),
+                        );
+                    for (file_name, change) in changes {
+                        let full_path = self.output_dir.join(&policy_name).join(file_name);
+                        self.manager
+                            .send_change_to_vms(&vms, &policy_name, &full_path, &change)
+                            .await;
                     }
 
                     /* 2. Update State & Persist Config */
@@ -210,7 +246,10 @@ impl PolicyUrlMonitor {
                     wait_time = Duration::from_secs(interval);
                 }
                 Err(e) => {
-                    error!("policy-url-monitor: [{}] Poll failed: {}", policy_name, e);
+                    error!("policy-url-monitor: [{}] Poll failed: {:#}", policy_name, e);
+                    if let Some(rejected) = e.downcast_ref::<Rejected>() {
+                        self.manager.reject_update(rejected.policy.as_deref(), &e);
+                    }
                 }
             }
 
@@ -218,6 +257,41 @@ impl PolicyUrlMonitor {
         }
     }
 
+    /* -------------------------------------------------------------------------
+     * poll
+     *
+     * Checks policy source for update, HTTP URL or OCI reference.
+     * Returns: Ok(Some(update)) if files were downloaded.
+     * ---------------------------------------------------------------------- */
+    async fn poll(
+        &self,
+        name: &str,
+        url: &str,
+        current_head: &str,
+    ) -> Result<Option<PolicyUpdate>> {
+        match &self.oci {
+            Some(oci) => {
+                let policy_dir = self.output_dir.join(name);
+                oci.poll(
+                    name,
+                    url,
+                    current_head,
+                    self.trusted_keys.as_deref(),
+                    &policy_dir,
+                )
+                .await
+            }
+            None => Ok(self
+                .poll_once(name, url, current_head)
+                .await?
+                .map(|(head, file_name)| PolicyUpdate {
+                    head,
+                    files: vec![file_name],
+                    removed: Vec::new(),
+                })),
+        }
+    }
+
     /* -------------------------------------------------------------------------
      * poll_once
      *
@@ -290,7 +364,22 @@ impl PolicyUrlMonitor {
             remote_head.unwrap()
         };
 
-        /* Step 4: Write to Disk */
+        /* Step 4: Verify signature, rejected file isn't written and head stays */
+        if let Some(keys) = &self.trusted_keys {
+            let key = self
+                .verify_download(keys, url, &body)
+                .await
+                .with_context(|| Rejected {
+                    revision: final_head.clone(),
+                    policy: Some(name.to_string()),
+                })?;
+            info!(
+                "policy-url-monitor: [{}] Update {} is signed by {}",
+                name, final_head, key
+            );
+        }
+
+        /* Step 5: Write to Disk */
         let file_name = url.split('/').next_back().unwrap_or("policy.bin");
         let policy_dir = self.output_dir.join(name);
         let file_path = policy_dir.join(file_name);
@@ -304,6 +393,22 @@ impl PolicyUrlMonitor {
         Ok(Some((final_head, file_name.to_string())))
     }
 
+    /* -------------------------------------------------------------------------
+     * Helper: Fetch detached signature and verify downloaded file
+     * ---------------------------------------------------------------------- */
+    async fn verify_download(&self, keys: &TrustedKeys, url: &str, body: &[u8]) -> Result<String> {
+        let sig_url = format!("{url}{SIGNATURE_SUFFIX}");
+        let sig_resp = self.client.get(&sig_url).send().await?;
+        if !sig_resp.status().is_success() {
+            bail!(
+                "Fetching signature {sig_url} failed with status: {}",
+                sig_resp.status()
+            );
+        }
+        let signatures = sig_resp.text().await?;
+        keys.verify(body, &signatures).map(str::to_string)
+    }
+
     /* -------------------------------------------------------------------------
      * Helper: Read Policy Config safely
      * ---------------------------------------------------------------------- */
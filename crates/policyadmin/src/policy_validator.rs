@@ -0,0 +1,209 @@
+// SPDX-FileCopyrightText: 2026 TII (SSRC) and the Ghaf contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use anyhow::{Context, Result, anyhow, ensure};
+use cedar_policy::PolicySet;
+use serde_json::Value;
+use tokio::{process::Command, time::timeout};
+
+/* Validator command, which doesn't finish in time, is killed and file rejected */
+const DEFAULT_COMMAND_TIMEOUT_SECS: u64 = 30;
+
+/*
+ * PolicyValidator
+ *
+ * Check of policy file content, declared per policy in config. Validators run
+ * in policy admin before file is sent to VMs, so malformed policy is caught
+ * centrally and VMs keep enforcing the previous one.
+ */
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum PolicyValidator {
+    /* File must be JSON, valid against schema in given file */
+    JsonSchema {
+        schema: PathBuf,
+    },
+    /* File must parse as Cedar policy set */
+    Cedar,
+    /* File must not be larger than given number of bytes */
+    MaxSize {
+        bytes: u64,
+    },
+    /* External checker, gets path of file as last argument and rejects it by non-zero exit */
+    Command {
+        command: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
+}
+
+impl PolicyValidator {
+    async fn check(&self, path: &Path) -> Result<()> {
+        match self {
+            Self::Command {
+                command,
+                timeout_secs,
+            } => {
+                let limit =
+                    Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_COMMAND_TIMEOUT_SECS));
+                run_command(command, path, limit).await
+            }
+            /* File IO and parsing would block runtime */
+            validator => {
+                let (validator, path) = (validator.clone(), path.to_path_buf());
+                tokio::task::spawn_blocking(move || validator.check_content(&path)).await?
+            }
+        }
+    }
+
+    fn check_content(&self, path: &Path) -> Result<()> {
+        match self {
+            Self::JsonSchema { schema } => {
+                let schema_json: Value = serde_json::from_slice(
+                    &fs::read(schema)
+                        .with_context(|| format!("Reading schema {}", schema.display()))?,
+                )
+                .with_context(|| format!("Parsing schema {}", schema.display()))?;
+                let validator = jsonschema::validator_for(&schema_json)
+                    .map_err(|e| anyhow!("Invalid schema {}: {e}", schema.display()))?;
+                let instance: Value =
+                    serde_json::from_slice(&fs::read(path)?).context("Not valid JSON")?;
+                let errors = validator
+                    .iter_errors(&instance)
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>();
+                ensure!(
+                    errors.is_empty(),
+                    "Doesn't match schema {}: {}",
+                    schema.display(),
+                    errors.join("; ")
+                );
+            }
+            Self::Cedar => {
+                let text = fs::read_to_string(path)?;
+                PolicySet::from_str(&text).map_err(|e| anyhow!("Not valid Cedar: {e}"))?;
+            }
+            Self::MaxSize { bytes } => {
+                let size = fs::metadata(path)?.len();
+                ensure!(size <= *bytes, "Size {size} exceeds limit of {bytes} bytes");
+            }
+            /* Run by check(), as child process */
+            Self::Command { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+async fn run_command(command: &[String], path: &Path, limit: Duration) -> Result<()> {
+    let (program, args) = command.split_first().context("Empty validator command")?;
+    let output = timeout(
+        limit,
+        Command::new(program)
+            .args(args)
+            .arg(path)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| anyhow!("Validator {program} timed out after {}s", limit.as_secs()))?
+    .with_context(|| format!("Running validator {program}"))?;
+    ensure!(
+        output.status.success(),
+        "Rejected by {program} ({}): {}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(())
+}
+
+/*
+ * validate
+ *
+ * Runs validators of policy against file, failing on first rejection.
+ * Checks don't block runtime, and commands are killed after their timeout.
+ */
+pub(crate) async fn validate(validators: &[PolicyValidator], path: &Path) -> Result<()> {
+    for validator in validators {
+        validator
+            .check(path)
+            .await
+            .with_context(|| format!("Policy file {} is invalid", path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, content: &str) -> Result<PathBuf> {
+        let path = std::env::temp_dir().join(format!(
+            "policyadmin-validator-{}-{name}",
+            std::process::id()
+        ));
+        fs::write(&path, content)?;
+        Ok(path)
+    }
+
+    #[tokio::test]
+    async fn validators() -> Result<()> {
+        let schema = temp_file(
+            "schema.json",
+            r#"{"type": "object", "required": ["rules"]}"#,
+        )?;
+        let good = temp_file("good.json", r#"{"rules": []}"#)?;
+        let bad = temp_file("bad.json", r#"{"other": 1}"#)?;
+        let cedar = temp_file("policy.cedar", "permit(principal, action, resource);")?;
+
+        let json_schema = PolicyValidator::JsonSchema {
+            schema: schema.clone(),
+        };
+        validate(std::slice::from_ref(&json_schema), &good).await?;
+        assert!(validate(&[json_schema], &bad).await.is_err());
+
+        validate(&[PolicyValidator::Cedar], &cedar).await?;
+        assert!(validate(&[PolicyValidator::Cedar], &good).await.is_err());
+
+        validate(&[PolicyValidator::MaxSize { bytes: 100 }], &good).await?;
+        assert!(
+            validate(&[PolicyValidator::MaxSize { bytes: 5 }], &good)
+                .await
+                .is_err()
+        );
+
+        let command = |command: &[&str], timeout_secs| PolicyValidator::Command {
+            command: command.iter().map(ToString::to_string).collect(),
+            timeout_secs,
+        };
+        validate(&[command(&["true"], None)], &good).await?;
+        assert!(validate(&[command(&["false"], None)], &good).await.is_err());
+
+        /* Hanging checker is killed, and file rejected */
+        let started = std::time::Instant::now();
+        let error = validate(&[command(&["sh", "-c", "sleep 10", "sh"], Some(1))], &good)
+            .await
+            .expect_err("must time out");
+        assert!(format!("{error:#}").contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        /* Config representation */
+        let parsed: Vec<PolicyValidator> = serde_json::from_str(
+            r#"[{"type": "cedar"}, {"type": "max-size", "bytes": 10},
+                {"type": "command", "command": ["check", "--strict"]}]"#,
+        )?;
+        assert_eq!(parsed[1], PolicyValidator::MaxSize { bytes: 10 });
+        assert_eq!(parsed[2], command(&["check", "--strict"], None));
+
+        for path in [schema, good, bad, cedar] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
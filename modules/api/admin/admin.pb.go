@@ -611,6 +611,7 @@ type QueryListItem struct {
 	ServiceType   string                 `protobuf:"bytes,6,opt,name=ServiceType,proto3" json:"ServiceType,omitempty"`   // Type of the service (future use)
 	VmName        *string                `protobuf:"bytes,7,opt,name=VmName,proto3,oneof" json:"VmName,omitempty"`       // Name of the VM to query; None for host running services
 	AgentName     *string                `protobuf:"bytes,8,opt,name=AgentName,proto3,oneof" json:"AgentName,omitempty"` // Name of the managing agent; None for agents
+	Liveness      *Liveness              `protobuf:"bytes,9,opt,name=Liveness,proto3,oneof" json:"Liveness,omitempty"`   // Health check statistics; None for unmonitored units
 	unknownFields protoimpl.UnknownFields
 	sizeCache     protoimpl.SizeCache
 }
@@ -701,6 +702,73 @@ func (x *QueryListItem) GetAgentName() string {
 	return ""
 }
 
+func (x *QueryListItem) GetLiveness() *Liveness {
+	if x != nil {
+		return x.Liveness
+	}
+	return nil
+}
+
+type Liveness struct {
+	state               protoimpl.MessageState `protogen:"open.v1"`
+	LastSeen            *uint64                `protobuf:"varint,1,opt,name=LastSeen,proto3,oneof" json:"LastSeen,omitempty"`                 // Unix time (seconds) of last successful health check
+	ConsecutiveFailures uint32                 `protobuf:"varint,2,opt,name=ConsecutiveFailures,proto3" json:"ConsecutiveFailures,omitempty"` // Failed or timed out health checks since last success
+	LatencyMs           *uint64                `protobuf:"varint,3,opt,name=LatencyMs,proto3,oneof" json:"LatencyMs,omitempty"`               // Round trip time of last successful health check
+	unknownFields       protoimpl.UnknownFields
+	sizeCache           protoimpl.SizeCache
+}
+
+func (x *Liveness) Reset() {
+	*x = Liveness{}
+	mi := &file_admin_admin_proto_msgTypes[12]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *Liveness) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*Liveness) ProtoMessage() {}
+
+func (x *Liveness) ProtoReflect() protoreflect.Message {
+	mi := &file_admin_admin_proto_msgTypes[12]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use Liveness.ProtoReflect.Descriptor instead.
+func (*Liveness) Descriptor() ([]byte, []int) {
+	return file_admin_admin_proto_rawDescGZIP(), []int{12}
+}
+
+func (x *Liveness) GetLastSeen() uint64 {
+	if x != nil && x.LastSeen != nil {
+		return *x.LastSeen
+	}
+	return 0
+}
+
+func (x *Liveness) GetConsecutiveFailures() uint32 {
+	if x != nil {
+		return x.ConsecutiveFailures
+	}
+	return 0
+}
+
+func (x *Liveness) GetLatencyMs() uint64 {
+	if x != nil && x.LatencyMs != nil {
+		return *x.LatencyMs
+	}
+	return 0
+}
+
 type QueryListResponse struct {
 	state         protoimpl.MessageState `protogen:"open.v1"`
 	List          []*QueryListItem       `protobuf:"bytes,1,rep,name=List,proto3" json:"List,omitempty"` // List of query responses
@@ -710,7 +778,7 @@ type QueryListResponse struct {
 
 func (x *QueryListResponse) Reset() {
 	*x = QueryListResponse{}
-	mi := &file_admin_admin_proto_msgTypes[12]
+	mi := &file_admin_admin_proto_msgTypes[13]
 	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 	ms.StoreMessageInfo(mi)
 }
@@ -722,7 +790,7 @@ func (x *QueryListResponse) String() string {
 func (*QueryListResponse) ProtoMessage() {}
 
 func (x *QueryListResponse) ProtoReflect() protoreflect.Message {
-	mi := &file_admin_admin_proto_msgTypes[12]
+	mi := &file_admin_admin_proto_msgTypes[13]
 	if x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 		if ms.LoadMessageInfo() == nil {
@@ -735,7 +803,7 @@ func (x *QueryListResponse) ProtoReflect() protoreflect.Message {
 
 // Deprecated: Use QueryListResponse.ProtoReflect.Descriptor instead.
 func (*QueryListResponse) Descriptor() ([]byte, []int) {
-	return file_admin_admin_proto_rawDescGZIP(), []int{12}
+	return file_admin_admin_proto_rawDescGZIP(), []int{13}
 }
 
 func (x *QueryListResponse) GetList() []*QueryListItem {
@@ -753,6 +821,7 @@ type WatchItem struct {
 	//	*WatchItem_Added
 	//	*WatchItem_Updated
 	//	*WatchItem_Removed
+	//	*WatchItem_Failed
 	Status        isWatchItem_Status `protobuf_oneof:"Status"`
 	unknownFields protoimpl.UnknownFields
 	sizeCache     protoimpl.SizeCache
@@ -760,7 +829,7 @@ type WatchItem struct {
 
 func (x *WatchItem) Reset() {
 	*x = WatchItem{}
-	mi := &file_admin_admin_proto_msgTypes[13]
+	mi := &file_admin_admin_proto_msgTypes[14]
 	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 	ms.StoreMessageInfo(mi)
 }
@@ -772,7 +841,7 @@ func (x *WatchItem) String() string {
 func (*WatchItem) ProtoMessage() {}
 
 func (x *WatchItem) ProtoReflect() protoreflect.Message {
-	mi := &file_admin_admin_proto_msgTypes[13]
+	mi := &file_admin_admin_proto_msgTypes[14]
 	if x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 		if ms.LoadMessageInfo() == nil {
@@ -785,7 +854,7 @@ func (x *WatchItem) ProtoReflect() protoreflect.Message {
 
 // Deprecated: Use WatchItem.ProtoReflect.Descriptor instead.
 func (*WatchItem) Descriptor() ([]byte, []int) {
-	return file_admin_admin_proto_rawDescGZIP(), []int{13}
+	return file_admin_admin_proto_rawDescGZIP(), []int{14}
 }
 
 func (x *WatchItem) GetStatus() isWatchItem_Status {
@@ -831,6 +900,15 @@ func (x *WatchItem) GetRemoved() *QueryListItem {
 	return nil
 }
 
+func (x *WatchItem) GetFailed() *QueryListItem {
+	if x != nil {
+		if x, ok := x.Status.(*WatchItem_Failed); ok {
+			return x.Failed
+		}
+	}
+	return nil
+}
+
 type isWatchItem_Status interface {
 	isWatchItem_Status()
 }
@@ -851,6 +929,10 @@ type WatchItem_Removed struct {
 	Removed *QueryListItem `protobuf:"bytes,4,opt,name=Removed,proto3,oneof"`
 }
 
+type WatchItem_Failed struct {
+	Failed *QueryListItem `protobuf:"bytes,5,opt,name=Failed,proto3,oneof"` // Unit failed, and restart policy gave up recovering it
+}
+
 func (*WatchItem_Initial) isWatchItem_Status() {}
 
 func (*WatchItem_Added) isWatchItem_Status() {}
@@ -859,6 +941,8 @@ func (*WatchItem_Updated) isWatchItem_Status() {}
 
 func (*WatchItem_Removed) isWatchItem_Status() {}
 
+func (*WatchItem_Failed) isWatchItem_Status() {}
+
 type LocaleRequest struct {
 	state         protoimpl.MessageState     `protogen:"open.v1"`
 	Assignments   []*locale.LocaleAssignment `protobuf:"bytes,1,rep,name=assignments,proto3" json:"assignments,omitempty"` // List of locale assignments
@@ -868,7 +952,7 @@ type LocaleRequest struct {
 
 func (x *LocaleRequest) Reset() {
 	*x = LocaleRequest{}
-	mi := &file_admin_admin_proto_msgTypes[14]
+	mi := &file_admin_admin_proto_msgTypes[15]
 	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 	ms.StoreMessageInfo(mi)
 }
@@ -880,7 +964,7 @@ func (x *LocaleRequest) String() string {
 func (*LocaleRequest) ProtoMessage() {}
 
 func (x *LocaleRequest) ProtoReflect() protoreflect.Message {
-	mi := &file_admin_admin_proto_msgTypes[14]
+	mi := &file_admin_admin_proto_msgTypes[15]
 	if x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 		if ms.LoadMessageInfo() == nil {
@@ -893,7 +977,7 @@ func (x *LocaleRequest) ProtoReflect() protoreflect.Message {
 
 // Deprecated: Use LocaleRequest.ProtoReflect.Descriptor instead.
 func (*LocaleRequest) Descriptor() ([]byte, []int) {
-	return file_admin_admin_proto_rawDescGZIP(), []int{14}
+	return file_admin_admin_proto_rawDescGZIP(), []int{15}
 }
 
 func (x *LocaleRequest) GetAssignments() []*locale.LocaleAssignment {
@@ -912,7 +996,7 @@ type TimezoneRequest struct {
 
 func (x *TimezoneRequest) Reset() {
 	*x = TimezoneRequest{}
-	mi := &file_admin_admin_proto_msgTypes[15]
+	mi := &file_admin_admin_proto_msgTypes[16]
 	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 	ms.StoreMessageInfo(mi)
 }
@@ -924,7 +1008,7 @@ func (x *TimezoneRequest) String() string {
 func (*TimezoneRequest) ProtoMessage() {}
 
 func (x *TimezoneRequest) ProtoReflect() protoreflect.Message {
-	mi := &file_admin_admin_proto_msgTypes[15]
+	mi := &file_admin_admin_proto_msgTypes[16]
 	if x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 		if ms.LoadMessageInfo() == nil {
@@ -937,7 +1021,7 @@ func (x *TimezoneRequest) ProtoReflect() protoreflect.Message {
 
 // Deprecated: Use TimezoneRequest.ProtoReflect.Descriptor instead.
 func (*TimezoneRequest) Descriptor() ([]byte, []int) {
-	return file_admin_admin_proto_rawDescGZIP(), []int{15}
+	return file_admin_admin_proto_rawDescGZIP(), []int{16}
 }
 
 func (x *TimezoneRequest) GetTimezone() string {
@@ -957,7 +1041,7 @@ type UserNotificationRequest struct {
 
 func (x *UserNotificationRequest) Reset() {
 	*x = UserNotificationRequest{}
-	mi := &file_admin_admin_proto_msgTypes[16]
+	mi := &file_admin_admin_proto_msgTypes[17]
 	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 	ms.StoreMessageInfo(mi)
 }
@@ -969,7 +1053,7 @@ func (x *UserNotificationRequest) String() string {
 func (*UserNotificationRequest) ProtoMessage() {}
 
 func (x *UserNotificationRequest) ProtoReflect() protoreflect.Message {
-	mi := &file_admin_admin_proto_msgTypes[16]
+	mi := &file_admin_admin_proto_msgTypes[17]
 	if x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 		if ms.LoadMessageInfo() == nil {
@@ -982,7 +1066,7 @@ func (x *UserNotificationRequest) ProtoReflect() protoreflect.Message {
 
 // Deprecated: Use UserNotificationRequest.ProtoReflect.Descriptor instead.
 func (*UserNotificationRequest) Descriptor() ([]byte, []int) {
-	return file_admin_admin_proto_rawDescGZIP(), []int{16}
+	return file_admin_admin_proto_rawDescGZIP(), []int{17}
 }
 
 func (x *UserNotificationRequest) GetVmName() string {
@@ -1009,13 +1093,16 @@ type Generation struct {
 	Specialisations       []string               `protobuf:"bytes,6,rep,name=Specialisations,proto3" json:"Specialisations,omitempty"`
 	Current               bool                   `protobuf:"varint,7,opt,name=Current,proto3" json:"Current,omitempty"`
 	StorePath             string                 `protobuf:"bytes,8,opt,name=StorePath,proto3" json:"StorePath,omitempty"`
+	ClosureSize           *uint64                `protobuf:"varint,9,opt,name=ClosureSize,proto3,oneof" json:"ClosureSize,omitempty"` // Bytes, absent if unknown
+	BootEntry             string                 `protobuf:"bytes,10,opt,name=BootEntry,proto3" json:"BootEntry,omitempty"`           // One of "missing", "present", "default"
+	Booted                bool                   `protobuf:"varint,11,opt,name=Booted,proto3" json:"Booted,omitempty"`
 	unknownFields         protoimpl.UnknownFields
 	sizeCache             protoimpl.SizeCache
 }
 
 func (x *Generation) Reset() {
 	*x = Generation{}
-	mi := &file_admin_admin_proto_msgTypes[17]
+	mi := &file_admin_admin_proto_msgTypes[18]
 	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 	ms.StoreMessageInfo(mi)
 }
@@ -1027,7 +1114,7 @@ func (x *Generation) String() string {
 func (*Generation) ProtoMessage() {}
 
 func (x *Generation) ProtoReflect() protoreflect.Message {
-	mi := &file_admin_admin_proto_msgTypes[17]
+	mi := &file_admin_admin_proto_msgTypes[18]
 	if x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 		if ms.LoadMessageInfo() == nil {
@@ -1040,7 +1127,7 @@ func (x *Generation) ProtoReflect() protoreflect.Message {
 
 // Deprecated: Use Generation.ProtoReflect.Descriptor instead.
 func (*Generation) Descriptor() ([]byte, []int) {
-	return file_admin_admin_proto_rawDescGZIP(), []int{17}
+	return file_admin_admin_proto_rawDescGZIP(), []int{18}
 }
 
 func (x *Generation) GetGeneration() int32 {
@@ -1099,6 +1186,27 @@ func (x *Generation) GetStorePath() string {
 	return ""
 }
 
+func (x *Generation) GetClosureSize() uint64 {
+	if x != nil && x.ClosureSize != nil {
+		return *x.ClosureSize
+	}
+	return 0
+}
+
+func (x *Generation) GetBootEntry() string {
+	if x != nil {
+		return x.BootEntry
+	}
+	return ""
+}
+
+func (x *Generation) GetBooted() bool {
+	if x != nil {
+		return x.Booted
+	}
+	return false
+}
+
 type ListGenerationsResponse struct {
 	state         protoimpl.MessageState `protogen:"open.v1"`
 	List          []*Generation          `protobuf:"bytes,1,rep,name=List,proto3" json:"List,omitempty"`
@@ -1108,7 +1216,7 @@ type ListGenerationsResponse struct {
 
 func (x *ListGenerationsResponse) Reset() {
 	*x = ListGenerationsResponse{}
-	mi := &file_admin_admin_proto_msgTypes[18]
+	mi := &file_admin_admin_proto_msgTypes[19]
 	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 	ms.StoreMessageInfo(mi)
 }
@@ -1120,7 +1228,7 @@ func (x *ListGenerationsResponse) String() string {
 func (*ListGenerationsResponse) ProtoMessage() {}
 
 func (x *ListGenerationsResponse) ProtoReflect() protoreflect.Message {
-	mi := &file_admin_admin_proto_msgTypes[18]
+	mi := &file_admin_admin_proto_msgTypes[19]
 	if x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 		if ms.LoadMessageInfo() == nil {
@@ -1133,7 +1241,7 @@ func (x *ListGenerationsResponse) ProtoReflect() protoreflect.Message {
 
 // Deprecated: Use ListGenerationsResponse.ProtoReflect.Descriptor instead.
 func (*ListGenerationsResponse) Descriptor() ([]byte, []int) {
-	return file_admin_admin_proto_rawDescGZIP(), []int{18}
+	return file_admin_admin_proto_rawDescGZIP(), []int{19}
 }
 
 func (x *ListGenerationsResponse) GetList() []*Generation {
@@ -1143,11 +1251,160 @@ func (x *ListGenerationsResponse) GetList() []*Generation {
 	return nil
 }
 
+type RollbackGenerationResponse struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	Generation    *int32                 `protobuf:"varint,1,opt,name=Generation,proto3,oneof" json:"Generation,omitempty"` // Absent on image based systems
+	Target        string                 `protobuf:"bytes,2,opt,name=Target,proto3" json:"Target,omitempty"`                // Store path, or image slot version
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *RollbackGenerationResponse) Reset() {
+	*x = RollbackGenerationResponse{}
+	mi := &file_admin_admin_proto_msgTypes[20]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *RollbackGenerationResponse) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*RollbackGenerationResponse) ProtoMessage() {}
+
+func (x *RollbackGenerationResponse) ProtoReflect() protoreflect.Message {
+	mi := &file_admin_admin_proto_msgTypes[20]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use RollbackGenerationResponse.ProtoReflect.Descriptor instead.
+func (*RollbackGenerationResponse) Descriptor() ([]byte, []int) {
+	return file_admin_admin_proto_rawDescGZIP(), []int{20}
+}
+
+func (x *RollbackGenerationResponse) GetGeneration() int32 {
+	if x != nil && x.Generation != nil {
+		return *x.Generation
+	}
+	return 0
+}
+
+func (x *RollbackGenerationResponse) GetTarget() string {
+	if x != nil {
+		return x.Target
+	}
+	return ""
+}
+
+type CollectGarbageRequest struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	Keep          uint32                 `protobuf:"varint,1,opt,name=Keep,proto3" json:"Keep,omitempty"` // Number of newest generations to keep
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *CollectGarbageRequest) Reset() {
+	*x = CollectGarbageRequest{}
+	mi := &file_admin_admin_proto_msgTypes[21]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *CollectGarbageRequest) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*CollectGarbageRequest) ProtoMessage() {}
+
+func (x *CollectGarbageRequest) ProtoReflect() protoreflect.Message {
+	mi := &file_admin_admin_proto_msgTypes[21]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use CollectGarbageRequest.ProtoReflect.Descriptor instead.
+func (*CollectGarbageRequest) Descriptor() ([]byte, []int) {
+	return file_admin_admin_proto_rawDescGZIP(), []int{21}
+}
+
+func (x *CollectGarbageRequest) GetKeep() uint32 {
+	if x != nil {
+		return x.Keep
+	}
+	return 0
+}
+
+type CollectGarbageResponse struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	Removed       []int32                `protobuf:"varint,1,rep,packed,name=Removed,proto3" json:"Removed,omitempty"`
+	Kept          []int32                `protobuf:"varint,2,rep,packed,name=Kept,proto3" json:"Kept,omitempty"`
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *CollectGarbageResponse) Reset() {
+	*x = CollectGarbageResponse{}
+	mi := &file_admin_admin_proto_msgTypes[22]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *CollectGarbageResponse) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*CollectGarbageResponse) ProtoMessage() {}
+
+func (x *CollectGarbageResponse) ProtoReflect() protoreflect.Message {
+	mi := &file_admin_admin_proto_msgTypes[22]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use CollectGarbageResponse.ProtoReflect.Descriptor instead.
+func (*CollectGarbageResponse) Descriptor() ([]byte, []int) {
+	return file_admin_admin_proto_rawDescGZIP(), []int{22}
+}
+
+func (x *CollectGarbageResponse) GetRemoved() []int32 {
+	if x != nil {
+		return x.Removed
+	}
+	return nil
+}
+
+func (x *CollectGarbageResponse) GetKept() []int32 {
+	if x != nil {
+		return x.Kept
+	}
+	return nil
+}
+
 type SetGenerationRequest struct {
 	state protoimpl.MessageState `protogen:"open.v1"`
 	// Types that are valid to be assigned to Update:
 	//
 	//	*SetGenerationRequest_Cachix
+	//	*SetGenerationRequest_Oci
 	Update        isSetGenerationRequest_Update `protobuf_oneof:"Update"`
 	unknownFields protoimpl.UnknownFields
 	sizeCache     protoimpl.SizeCache
@@ -1155,7 +1412,7 @@ type SetGenerationRequest struct {
 
 func (x *SetGenerationRequest) Reset() {
 	*x = SetGenerationRequest{}
-	mi := &file_admin_admin_proto_msgTypes[19]
+	mi := &file_admin_admin_proto_msgTypes[23]
 	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 	ms.StoreMessageInfo(mi)
 }
@@ -1167,7 +1424,7 @@ func (x *SetGenerationRequest) String() string {
 func (*SetGenerationRequest) ProtoMessage() {}
 
 func (x *SetGenerationRequest) ProtoReflect() protoreflect.Message {
-	mi := &file_admin_admin_proto_msgTypes[19]
+	mi := &file_admin_admin_proto_msgTypes[23]
 	if x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 		if ms.LoadMessageInfo() == nil {
@@ -1180,7 +1437,7 @@ func (x *SetGenerationRequest) ProtoReflect() protoreflect.Message {
 
 // Deprecated: Use SetGenerationRequest.ProtoReflect.Descriptor instead.
 func (*SetGenerationRequest) Descriptor() ([]byte, []int) {
-	return file_admin_admin_proto_rawDescGZIP(), []int{19}
+	return file_admin_admin_proto_rawDescGZIP(), []int{23}
 }
 
 func (x *SetGenerationRequest) GetUpdate() isSetGenerationRequest_Update {
@@ -1199,6 +1456,15 @@ func (x *SetGenerationRequest) GetCachix() *Cachix {
 	return nil
 }
 
+func (x *SetGenerationRequest) GetOci() *Oci {
+	if x != nil {
+		if x, ok := x.Update.(*SetGenerationRequest_Oci); ok {
+			return x.Oci
+		}
+	}
+	return nil
+}
+
 type isSetGenerationRequest_Update interface {
 	isSetGenerationRequest_Update()
 }
@@ -1207,8 +1473,14 @@ type SetGenerationRequest_Cachix struct {
 	Cachix *Cachix `protobuf:"bytes,1,opt,name=Cachix,proto3,oneof"`
 }
 
+type SetGenerationRequest_Oci struct {
+	Oci *Oci `protobuf:"bytes,2,opt,name=Oci,proto3,oneof"`
+}
+
 func (*SetGenerationRequest_Cachix) isSetGenerationRequest_Update() {}
 
+func (*SetGenerationRequest_Oci) isSetGenerationRequest_Update() {}
+
 type Cachix struct {
 	state         protoimpl.MessageState `protogen:"open.v1"`
 	Pin           string                 `protobuf:"bytes,1,opt,name=Pin,proto3" json:"Pin,omitempty"`
@@ -1221,7 +1493,7 @@ type Cachix struct {
 
 func (x *Cachix) Reset() {
 	*x = Cachix{}
-	mi := &file_admin_admin_proto_msgTypes[20]
+	mi := &file_admin_admin_proto_msgTypes[24]
 	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 	ms.StoreMessageInfo(mi)
 }
@@ -1233,7 +1505,7 @@ func (x *Cachix) String() string {
 func (*Cachix) ProtoMessage() {}
 
 func (x *Cachix) ProtoReflect() protoreflect.Message {
-	mi := &file_admin_admin_proto_msgTypes[20]
+	mi := &file_admin_admin_proto_msgTypes[24]
 	if x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 		if ms.LoadMessageInfo() == nil {
@@ -1246,7 +1518,7 @@ func (x *Cachix) ProtoReflect() protoreflect.Message {
 
 // Deprecated: Use Cachix.ProtoReflect.Descriptor instead.
 func (*Cachix) Descriptor() ([]byte, []int) {
-	return file_admin_admin_proto_rawDescGZIP(), []int{20}
+	return file_admin_admin_proto_rawDescGZIP(), []int{24}
 }
 
 func (x *Cachix) GetPin() string {
@@ -1277,30 +1549,35 @@ func (x *Cachix) GetToken() string {
 	return ""
 }
 
-type SetGenerationResponse struct {
+type Oci struct {
 	state         protoimpl.MessageState `protogen:"open.v1"`
-	Finished      bool                   `protobuf:"varint,1,opt,name=Finished,proto3" json:"Finished,omitempty"`
-	Output        *string                `protobuf:"bytes,2,opt,name=Output,proto3,oneof" json:"Output,omitempty"`
-	Error         *string                `protobuf:"bytes,3,opt,name=Error,proto3,oneof" json:"Error,omitempty"`
+	Reference     string                 `protobuf:"bytes,1,opt,name=Reference,proto3" json:"Reference,omitempty"`     // OCI reference with tag (registry/repo:tag)
+	Username      *string                `protobuf:"bytes,2,opt,name=Username,proto3,oneof" json:"Username,omitempty"` // Basic auth, requires Password
+	Password      *string                `protobuf:"bytes,3,opt,name=Password,proto3,oneof" json:"Password,omitempty"`
+	Token         *string                `protobuf:"bytes,4,opt,name=Token,proto3,oneof" json:"Token,omitempty"`             // Bearer auth
+	Insecure      bool                   `protobuf:"varint,5,opt,name=Insecure,proto3" json:"Insecure,omitempty"`            // Use HTTP instead of HTTPS
+	Validate      bool                   `protobuf:"varint,6,opt,name=Validate,proto3" json:"Validate,omitempty"`            // Validate pulled artifacts
+	Install       bool                   `protobuf:"varint,7,opt,name=Install,proto3" json:"Install,omitempty"`              // Install image right after pull
+	Destination   *string                `protobuf:"bytes,8,opt,name=Destination,proto3,oneof" json:"Destination,omitempty"` // Download root on host
 	unknownFields protoimpl.UnknownFields
 	sizeCache     protoimpl.SizeCache
 }
 
-func (x *SetGenerationResponse) Reset() {
-	*x = SetGenerationResponse{}
-	mi := &file_admin_admin_proto_msgTypes[21]
+func (x *Oci) Reset() {
+	*x = Oci{}
+	mi := &file_admin_admin_proto_msgTypes[25]
 	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 	ms.StoreMessageInfo(mi)
 }
 
-func (x *SetGenerationResponse) String() string {
+func (x *Oci) String() string {
 	return protoimpl.X.MessageStringOf(x)
 }
 
-func (*SetGenerationResponse) ProtoMessage() {}
+func (*Oci) ProtoMessage() {}
 
-func (x *SetGenerationResponse) ProtoReflect() protoreflect.Message {
-	mi := &file_admin_admin_proto_msgTypes[21]
+func (x *Oci) ProtoReflect() protoreflect.Message {
+	mi := &file_admin_admin_proto_msgTypes[25]
 	if x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 		if ms.LoadMessageInfo() == nil {
@@ -1311,32 +1588,526 @@ func (x *SetGenerationResponse) ProtoReflect() protoreflect.Message {
 	return mi.MessageOf(x)
 }
 
-// Deprecated: Use SetGenerationResponse.ProtoReflect.Descriptor instead.
-func (*SetGenerationResponse) Descriptor() ([]byte, []int) {
-	return file_admin_admin_proto_rawDescGZIP(), []int{21}
+// Deprecated: Use Oci.ProtoReflect.Descriptor instead.
+func (*Oci) Descriptor() ([]byte, []int) {
+	return file_admin_admin_proto_rawDescGZIP(), []int{25}
 }
 
-func (x *SetGenerationResponse) GetFinished() bool {
+func (x *Oci) GetReference() string {
 	if x != nil {
-		return x.Finished
+		return x.Reference
 	}
-	return false
+	return ""
 }
 
-func (x *SetGenerationResponse) GetOutput() string {
-	if x != nil && x.Output != nil {
-		return *x.Output
+func (x *Oci) GetUsername() string {
+	if x != nil && x.Username != nil {
+		return *x.Username
 	}
 	return ""
 }
 
-func (x *SetGenerationResponse) GetError() string {
+func (x *Oci) GetPassword() string {
+	if x != nil && x.Password != nil {
+		return *x.Password
+	}
+	return ""
+}
+
+func (x *Oci) GetToken() string {
+	if x != nil && x.Token != nil {
+		return *x.Token
+	}
+	return ""
+}
+
+func (x *Oci) GetInsecure() bool {
+	if x != nil {
+		return x.Insecure
+	}
+	return false
+}
+
+func (x *Oci) GetValidate() bool {
+	if x != nil {
+		return x.Validate
+	}
+	return false
+}
+
+func (x *Oci) GetInstall() bool {
+	if x != nil {
+		return x.Install
+	}
+	return false
+}
+
+func (x *Oci) GetDestination() string {
+	if x != nil && x.Destination != nil {
+		return *x.Destination
+	}
+	return ""
+}
+
+// Progress of OCI registry operation, mirrors `ota_update::registry::progress::RegistryEvent`
+type RegistryProgress struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	Event         string                 `protobuf:"bytes,1,opt,name=Event,proto3" json:"Event,omitempty"` // Event name, like `blob_downloading`
+	Reference     *string                `protobuf:"bytes,2,opt,name=Reference,proto3,oneof" json:"Reference,omitempty"`
+	Digest        *string                `protobuf:"bytes,3,opt,name=Digest,proto3,oneof" json:"Digest,omitempty"`
+	Current       *uint64                `protobuf:"varint,4,opt,name=Current,proto3,oneof" json:"Current,omitempty"` // Bytes transferred, or items processed
+	Total         *uint64                `protobuf:"varint,5,opt,name=Total,proto3,oneof" json:"Total,omitempty"`
+	Path          *string                `protobuf:"bytes,6,opt,name=Path,proto3,oneof" json:"Path,omitempty"`
+	Detail        *string                `protobuf:"bytes,7,opt,name=Detail,proto3,oneof" json:"Detail,omitempty"`
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *RegistryProgress) Reset() {
+	*x = RegistryProgress{}
+	mi := &file_admin_admin_proto_msgTypes[26]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *RegistryProgress) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*RegistryProgress) ProtoMessage() {}
+
+func (x *RegistryProgress) ProtoReflect() protoreflect.Message {
+	mi := &file_admin_admin_proto_msgTypes[26]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use RegistryProgress.ProtoReflect.Descriptor instead.
+func (*RegistryProgress) Descriptor() ([]byte, []int) {
+	return file_admin_admin_proto_rawDescGZIP(), []int{26}
+}
+
+func (x *RegistryProgress) GetEvent() string {
+	if x != nil {
+		return x.Event
+	}
+	return ""
+}
+
+func (x *RegistryProgress) GetReference() string {
+	if x != nil && x.Reference != nil {
+		return *x.Reference
+	}
+	return ""
+}
+
+func (x *RegistryProgress) GetDigest() string {
+	if x != nil && x.Digest != nil {
+		return *x.Digest
+	}
+	return ""
+}
+
+func (x *RegistryProgress) GetCurrent() uint64 {
+	if x != nil && x.Current != nil {
+		return *x.Current
+	}
+	return 0
+}
+
+func (x *RegistryProgress) GetTotal() uint64 {
+	if x != nil && x.Total != nil {
+		return *x.Total
+	}
+	return 0
+}
+
+func (x *RegistryProgress) GetPath() string {
+	if x != nil && x.Path != nil {
+		return *x.Path
+	}
+	return ""
+}
+
+func (x *RegistryProgress) GetDetail() string {
+	if x != nil && x.Detail != nil {
+		return *x.Detail
+	}
+	return ""
+}
+
+// Progress of system update, mirrors `ota_update::progress::UpdateEvent`
+type UpdateProgress struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	Phase         string                 `protobuf:"bytes,1,opt,name=Phase,proto3" json:"Phase,omitempty"` // `resolved`, `fetching`, `activating` or `done`
+	PathsDone     uint64                 `protobuf:"varint,2,opt,name=PathsDone,proto3" json:"PathsDone,omitempty"`
+	PathsTotal    uint64                 `protobuf:"varint,3,opt,name=PathsTotal,proto3" json:"PathsTotal,omitempty"`
+	BytesDone     uint64                 `protobuf:"varint,4,opt,name=BytesDone,proto3" json:"BytesDone,omitempty"`
+	BytesTotal    uint64                 `protobuf:"varint,5,opt,name=BytesTotal,proto3" json:"BytesTotal,omitempty"`
+	StorePath     *string                `protobuf:"bytes,6,opt,name=StorePath,proto3,oneof" json:"StorePath,omitempty"` // Path being fetched, or update target
+	Step          *string                `protobuf:"bytes,7,opt,name=Step,proto3,oneof" json:"Step,omitempty"`           // Activation step, like `install_bootloader`
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *UpdateProgress) Reset() {
+	*x = UpdateProgress{}
+	mi := &file_admin_admin_proto_msgTypes[27]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *UpdateProgress) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*UpdateProgress) ProtoMessage() {}
+
+func (x *UpdateProgress) ProtoReflect() protoreflect.Message {
+	mi := &file_admin_admin_proto_msgTypes[27]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use UpdateProgress.ProtoReflect.Descriptor instead.
+func (*UpdateProgress) Descriptor() ([]byte, []int) {
+	return file_admin_admin_proto_rawDescGZIP(), []int{27}
+}
+
+func (x *UpdateProgress) GetPhase() string {
+	if x != nil {
+		return x.Phase
+	}
+	return ""
+}
+
+func (x *UpdateProgress) GetPathsDone() uint64 {
+	if x != nil {
+		return x.PathsDone
+	}
+	return 0
+}
+
+func (x *UpdateProgress) GetPathsTotal() uint64 {
+	if x != nil {
+		return x.PathsTotal
+	}
+	return 0
+}
+
+func (x *UpdateProgress) GetBytesDone() uint64 {
+	if x != nil {
+		return x.BytesDone
+	}
+	return 0
+}
+
+func (x *UpdateProgress) GetBytesTotal() uint64 {
+	if x != nil {
+		return x.BytesTotal
+	}
+	return 0
+}
+
+func (x *UpdateProgress) GetStorePath() string {
+	if x != nil && x.StorePath != nil {
+		return *x.StorePath
+	}
+	return ""
+}
+
+func (x *UpdateProgress) GetStep() string {
+	if x != nil && x.Step != nil {
+		return *x.Step
+	}
+	return ""
+}
+
+type SetGenerationResponse struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	Finished      bool                   `protobuf:"varint,1,opt,name=Finished,proto3" json:"Finished,omitempty"`
+	Output        *string                `protobuf:"bytes,2,opt,name=Output,proto3,oneof" json:"Output,omitempty"`
+	Error         *string                `protobuf:"bytes,3,opt,name=Error,proto3,oneof" json:"Error,omitempty"`
+	Registry      *RegistryProgress      `protobuf:"bytes,4,opt,name=Registry,proto3,oneof" json:"Registry,omitempty"`
+	Progress      *UpdateProgress        `protobuf:"bytes,5,opt,name=Progress,proto3,oneof" json:"Progress,omitempty"`
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *SetGenerationResponse) Reset() {
+	*x = SetGenerationResponse{}
+	mi := &file_admin_admin_proto_msgTypes[28]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *SetGenerationResponse) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*SetGenerationResponse) ProtoMessage() {}
+
+func (x *SetGenerationResponse) ProtoReflect() protoreflect.Message {
+	mi := &file_admin_admin_proto_msgTypes[28]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use SetGenerationResponse.ProtoReflect.Descriptor instead.
+func (*SetGenerationResponse) Descriptor() ([]byte, []int) {
+	return file_admin_admin_proto_rawDescGZIP(), []int{28}
+}
+
+func (x *SetGenerationResponse) GetFinished() bool {
+	if x != nil {
+		return x.Finished
+	}
+	return false
+}
+
+func (x *SetGenerationResponse) GetOutput() string {
+	if x != nil && x.Output != nil {
+		return *x.Output
+	}
+	return ""
+}
+
+func (x *SetGenerationResponse) GetError() string {
 	if x != nil && x.Error != nil {
 		return *x.Error
 	}
 	return ""
 }
 
+func (x *SetGenerationResponse) GetRegistry() *RegistryProgress {
+	if x != nil {
+		return x.Registry
+	}
+	return nil
+}
+
+func (x *SetGenerationResponse) GetProgress() *UpdateProgress {
+	if x != nil {
+		return x.Progress
+	}
+	return nil
+}
+
+type PolicyStatusRequest struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	VmName        *string                `protobuf:"bytes,1,opt,name=VmName,proto3,oneof" json:"VmName,omitempty"` // Deliveries to this VM only, all if absent
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *PolicyStatusRequest) Reset() {
+	*x = PolicyStatusRequest{}
+	mi := &file_admin_admin_proto_msgTypes[29]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *PolicyStatusRequest) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*PolicyStatusRequest) ProtoMessage() {}
+
+func (x *PolicyStatusRequest) ProtoReflect() protoreflect.Message {
+	mi := &file_admin_admin_proto_msgTypes[29]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use PolicyStatusRequest.ProtoReflect.Descriptor instead.
+func (*PolicyStatusRequest) Descriptor() ([]byte, []int) {
+	return file_admin_admin_proto_rawDescGZIP(), []int{29}
+}
+
+func (x *PolicyStatusRequest) GetVmName() string {
+	if x != nil && x.VmName != nil {
+		return *x.VmName
+	}
+	return ""
+}
+
+// Delivery of policy to VM, as of last attempt
+type PolicyDeliveryStatus struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	VmName        string                 `protobuf:"bytes,1,opt,name=VmName,proto3" json:"VmName,omitempty"`
+	Policy        string                 `protobuf:"bytes,2,opt,name=Policy,proto3" json:"Policy,omitempty"`
+	Revision      *string                `protobuf:"bytes,3,opt,name=Revision,proto3,oneof" json:"Revision,omitempty"`        // Head of git repo or of per-policy URL
+	File          *string                `protobuf:"bytes,4,opt,name=File,proto3,oneof" json:"File,omitempty"`                // Policy file last sent
+	Change        *string                `protobuf:"bytes,5,opt,name=Change,proto3,oneof" json:"Change,omitempty"`            // `update`, `delete` or `rename`
+	Sha256        *string                `protobuf:"bytes,6,opt,name=Sha256,proto3,oneof" json:"Sha256,omitempty"`            // Checksum of policy file last sent
+	LastAttempt   *uint64                `protobuf:"varint,7,opt,name=LastAttempt,proto3,oneof" json:"LastAttempt,omitempty"` // Unix time (seconds)
+	LastSuccess   *uint64                `protobuf:"varint,8,opt,name=LastSuccess,proto3,oneof" json:"LastSuccess,omitempty"`
+	Error         *string                `protobuf:"bytes,9,opt,name=Error,proto3,oneof" json:"Error,omitempty"` // Error of last attempt, absent if it succeeded
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *PolicyDeliveryStatus) Reset() {
+	*x = PolicyDeliveryStatus{}
+	mi := &file_admin_admin_proto_msgTypes[30]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *PolicyDeliveryStatus) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*PolicyDeliveryStatus) ProtoMessage() {}
+
+func (x *PolicyDeliveryStatus) ProtoReflect() protoreflect.Message {
+	mi := &file_admin_admin_proto_msgTypes[30]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use PolicyDeliveryStatus.ProtoReflect.Descriptor instead.
+func (*PolicyDeliveryStatus) Descriptor() ([]byte, []int) {
+	return file_admin_admin_proto_rawDescGZIP(), []int{30}
+}
+
+func (x *PolicyDeliveryStatus) GetVmName() string {
+	if x != nil {
+		return x.VmName
+	}
+	return ""
+}
+
+func (x *PolicyDeliveryStatus) GetPolicy() string {
+	if x != nil {
+		return x.Policy
+	}
+	return ""
+}
+
+func (x *PolicyDeliveryStatus) GetRevision() string {
+	if x != nil && x.Revision != nil {
+		return *x.Revision
+	}
+	return ""
+}
+
+func (x *PolicyDeliveryStatus) GetFile() string {
+	if x != nil && x.File != nil {
+		return *x.File
+	}
+	return ""
+}
+
+func (x *PolicyDeliveryStatus) GetChange() string {
+	if x != nil && x.Change != nil {
+		return *x.Change
+	}
+	return ""
+}
+
+func (x *PolicyDeliveryStatus) GetSha256() string {
+	if x != nil && x.Sha256 != nil {
+		return *x.Sha256
+	}
+	return ""
+}
+
+func (x *PolicyDeliveryStatus) GetLastAttempt() uint64 {
+	if x != nil && x.LastAttempt != nil {
+		return *x.LastAttempt
+	}
+	return 0
+}
+
+func (x *PolicyDeliveryStatus) GetLastSuccess() uint64 {
+	if x != nil && x.LastSuccess != nil {
+		return *x.LastSuccess
+	}
+	return 0
+}
+
+func (x *PolicyDeliveryStatus) GetError() string {
+	if x != nil && x.Error != nil {
+		return *x.Error
+	}
+	return ""
+}
+
+type PolicyStatusResponse struct {
+	state         protoimpl.MessageState  `protogen:"open.v1"`
+	List          []*PolicyDeliveryStatus `protobuf:"bytes,1,rep,name=List,proto3" json:"List,omitempty"`
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *PolicyStatusResponse) Reset() {
+	*x = PolicyStatusResponse{}
+	mi := &file_admin_admin_proto_msgTypes[31]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *PolicyStatusResponse) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*PolicyStatusResponse) ProtoMessage() {}
+
+func (x *PolicyStatusResponse) ProtoReflect() protoreflect.Message {
+	mi := &file_admin_admin_proto_msgTypes[31]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use PolicyStatusResponse.ProtoReflect.Descriptor instead.
+func (*PolicyStatusResponse) Descriptor() ([]byte, []int) {
+	return file_admin_admin_proto_rawDescGZIP(), []int{31}
+}
+
+func (x *PolicyStatusResponse) GetList() []*PolicyDeliveryStatus {
+	if x != nil {
+		return x.List
+	}
+	return nil
+}
+
 type CtapRequest struct {
 	state         protoimpl.MessageState `protogen:"open.v1"`
 	Req           string                 `protobuf:"bytes,1,opt,name=Req,proto3" json:"Req,omitempty"`
@@ -1348,7 +2119,7 @@ type CtapRequest struct {
 
 func (x *CtapRequest) Reset() {
 	*x = CtapRequest{}
-	mi := &file_admin_admin_proto_msgTypes[22]
+	mi := &file_admin_admin_proto_msgTypes[32]
 	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 	ms.StoreMessageInfo(mi)
 }
@@ -1360,7 +2131,7 @@ func (x *CtapRequest) String() string {
 func (*CtapRequest) ProtoMessage() {}
 
 func (x *CtapRequest) ProtoReflect() protoreflect.Message {
-	mi := &file_admin_admin_proto_msgTypes[22]
+	mi := &file_admin_admin_proto_msgTypes[32]
 	if x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 		if ms.LoadMessageInfo() == nil {
@@ -1373,7 +2144,7 @@ func (x *CtapRequest) ProtoReflect() protoreflect.Message {
 
 // Deprecated: Use CtapRequest.ProtoReflect.Descriptor instead.
 func (*CtapRequest) Descriptor() ([]byte, []int) {
-	return file_admin_admin_proto_rawDescGZIP(), []int{22}
+	return file_admin_admin_proto_rawDescGZIP(), []int{32}
 }
 
 func (x *CtapRequest) GetReq() string {
@@ -1406,7 +2177,7 @@ type CtapResponse struct {
 
 func (x *CtapResponse) Reset() {
 	*x = CtapResponse{}
-	mi := &file_admin_admin_proto_msgTypes[23]
+	mi := &file_admin_admin_proto_msgTypes[33]
 	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 	ms.StoreMessageInfo(mi)
 }
@@ -1418,7 +2189,7 @@ func (x *CtapResponse) String() string {
 func (*CtapResponse) ProtoMessage() {}
 
 func (x *CtapResponse) ProtoReflect() protoreflect.Message {
-	mi := &file_admin_admin_proto_msgTypes[23]
+	mi := &file_admin_admin_proto_msgTypes[33]
 	if x != nil {
 		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
 		if ms.LoadMessageInfo() == nil {
@@ -1431,7 +2202,7 @@ func (x *CtapResponse) ProtoReflect() protoreflect.Message {
 
 // Deprecated: Use CtapResponse.ProtoReflect.Descriptor instead.
 func (*CtapResponse) Descriptor() ([]byte, []int) {
-	return file_admin_admin_proto_rawDescGZIP(), []int{23}
+	return file_admin_admin_proto_rawDescGZIP(), []int{33}
 }
 
 func (x *CtapResponse) GetOutput() []byte {
@@ -1482,7 +2253,7 @@ const file_admin_admin_proto_rawDesc = "" +
 	"\x11UnitStatusRequest\x12\x16\n" +
 	"\x06VmName\x18\x01 \x01(\tR\x06VmName\x12\x1a\n" +
 	"\bUnitName\x18\x02 \x01(\tR\bUnitName\"\a\n" +
-	"\x05Empty\"\x94\x02\n" +
+	"\x05Empty\"\xd3\x02\n" +
 	"\rQueryListItem\x12\x12\n" +
 	"\x04Name\x18\x01 \x01(\tR\x04Name\x12 \n" +
 	"\vDescription\x18\x02 \x01(\tR\vDescription\x12\x1a\n" +
@@ -1493,17 +2264,27 @@ const file_admin_admin_proto_rawDesc = "" +
 	"\x06VmType\x18\x05 \x01(\tR\x06VmType\x12 \n" +
 	"\vServiceType\x18\x06 \x01(\tR\vServiceType\x12\x1b\n" +
 	"\x06VmName\x18\a \x01(\tH\x00R\x06VmName\x88\x01\x01\x12!\n" +
-	"\tAgentName\x18\b \x01(\tH\x01R\tAgentName\x88\x01\x01B\t\n" +
+	"\tAgentName\x18\b \x01(\tH\x01R\tAgentName\x88\x01\x01\x120\n" +
+	"\bLiveness\x18\t \x01(\v2\x0f.admin.LivenessH\x02R\bLiveness\x88\x01\x01B\t\n" +
 	"\a_VmNameB\f\n" +
 	"\n" +
-	"_AgentName\"=\n" +
+	"_AgentNameB\v\n" +
+	"\t_Liveness\"\x9b\x01\n" +
+	"\bLiveness\x12\x1f\n" +
+	"\bLastSeen\x18\x01 \x01(\x04H\x00R\bLastSeen\x88\x01\x01\x120\n" +
+	"\x13ConsecutiveFailures\x18\x02 \x01(\rR\x13ConsecutiveFailures\x12!\n" +
+	"\tLatencyMs\x18\x03 \x01(\x04H\x01R\tLatencyMs\x88\x01\x01B\v\n" +
+	"\t_LastSeenB\f\n" +
+	"\n" +
+	"_LatencyMs\"=\n" +
 	"\x11QueryListResponse\x12(\n" +
-	"\x04List\x18\x01 \x03(\v2\x14.admin.QueryListItemR\x04List\"\xdd\x01\n" +
+	"\x04List\x18\x01 \x03(\v2\x14.admin.QueryListItemR\x04List\"\x8d\x02\n" +
 	"\tWatchItem\x124\n" +
 	"\aInitial\x18\x01 \x01(\v2\x18.admin.QueryListResponseH\x00R\aInitial\x12,\n" +
 	"\x05Added\x18\x02 \x01(\v2\x14.admin.QueryListItemH\x00R\x05Added\x120\n" +
 	"\aUpdated\x18\x03 \x01(\v2\x14.admin.QueryListItemH\x00R\aUpdated\x120\n" +
-	"\aRemoved\x18\x04 \x01(\v2\x14.admin.QueryListItemH\x00R\aRemovedB\b\n" +
+	"\aRemoved\x18\x04 \x01(\v2\x14.admin.QueryListItemH\x00R\aRemoved\x12.\n" +
+	"\x06Failed\x18\x05 \x01(\v2\x14.admin.QueryListItemH\x00R\x06FailedB\b\n" +
 	"\x06Status\"K\n" +
 	"\rLocaleRequest\x12:\n" +
 	"\vassignments\x18\x01 \x03(\v2\x18.locale.LocaleAssignmentR\vassignments\"-\n" +
@@ -1511,7 +2292,7 @@ const file_admin_admin_proto_rawDesc = "" +
 	"\bTimezone\x18\x01 \x01(\tR\bTimezone\"o\n" +
 	"\x17UserNotificationRequest\x12\x16\n" +
 	"\x06VmName\x18\x01 \x01(\tR\x06VmName\x12<\n" +
-	"\fNotification\x18\x02 \x01(\v2\x18.notify.UserNotificationR\fNotification\"\xa2\x02\n" +
+	"\fNotification\x18\x02 \x01(\v2\x18.notify.UserNotificationR\fNotification\"\x8f\x03\n" +
 	"\n" +
 	"Generation\x12\x1e\n" +
 	"\n" +
@@ -1523,11 +2304,29 @@ const file_admin_admin_proto_rawDesc = "" +
 	"\x15ConfigurationRevision\x18\x05 \x01(\tR\x15ConfigurationRevision\x12(\n" +
 	"\x0fSpecialisations\x18\x06 \x03(\tR\x0fSpecialisations\x12\x18\n" +
 	"\aCurrent\x18\a \x01(\bR\aCurrent\x12\x1c\n" +
-	"\tStorePath\x18\b \x01(\tR\tStorePath\"@\n" +
+	"\tStorePath\x18\b \x01(\tR\tStorePath\x12%\n" +
+	"\vClosureSize\x18\t \x01(\x04H\x00R\vClosureSize\x88\x01\x01\x12\x1c\n" +
+	"\tBootEntry\x18\n" +
+	" \x01(\tR\tBootEntry\x12\x16\n" +
+	"\x06Booted\x18\v \x01(\bR\x06BootedB\x0e\n" +
+	"\f_ClosureSize\"@\n" +
 	"\x17ListGenerationsResponse\x12%\n" +
-	"\x04List\x18\x01 \x03(\v2\x11.admin.GenerationR\x04List\"I\n" +
+	"\x04List\x18\x01 \x03(\v2\x11.admin.GenerationR\x04List\"h\n" +
+	"\x1aRollbackGenerationResponse\x12#\n" +
+	"\n" +
+	"Generation\x18\x01 \x01(\x05H\x00R\n" +
+	"Generation\x88\x01\x01\x12\x16\n" +
+	"\x06Target\x18\x02 \x01(\tR\x06TargetB\r\n" +
+	"\v_Generation\"+\n" +
+	"\x15CollectGarbageRequest\x12\x12\n" +
+	"\x04Keep\x18\x01 \x01(\rR\x04Keep\"F\n" +
+	"\x16CollectGarbageResponse\x12\x18\n" +
+	"\aRemoved\x18\x01 \x03(\x05R\aRemoved\x12\x12\n" +
+	"\x04Kept\x18\x02 \x03(\x05R\x04Kept\"i\n" +
 	"\x14SetGenerationRequest\x12'\n" +
-	"\x06Cachix\x18\x01 \x01(\v2\r.admin.CachixH\x00R\x06CachixB\b\n" +
+	"\x06Cachix\x18\x01 \x01(\v2\r.admin.CachixH\x00R\x06Cachix\x12\x1e\n" +
+	"\x03Oci\x18\x02 \x01(\v2\n" +
+	".admin.OciH\x00R\x03OciB\b\n" +
 	"\x06Update\"\x89\x01\n" +
 	"\x06Cachix\x12\x10\n" +
 	"\x03Pin\x18\x01 \x01(\tR\x03Pin\x12#\n" +
@@ -1537,20 +2336,89 @@ const file_admin_admin_proto_rawDesc = "" +
 	"\x05Cache\x18\x03 \x01(\tR\x05Cache\x12\x19\n" +
 	"\x05Token\x18\x04 \x01(\tH\x01R\x05Token\x88\x01\x01B\r\n" +
 	"\v_CachixHostB\b\n" +
-	"\x06_Token\"\x80\x01\n" +
+	"\x06_Token\"\xad\x02\n" +
+	"\x03Oci\x12\x1c\n" +
+	"\tReference\x18\x01 \x01(\tR\tReference\x12\x1f\n" +
+	"\bUsername\x18\x02 \x01(\tH\x00R\bUsername\x88\x01\x01\x12\x1f\n" +
+	"\bPassword\x18\x03 \x01(\tH\x01R\bPassword\x88\x01\x01\x12\x19\n" +
+	"\x05Token\x18\x04 \x01(\tH\x02R\x05Token\x88\x01\x01\x12\x1a\n" +
+	"\bInsecure\x18\x05 \x01(\bR\bInsecure\x12\x1a\n" +
+	"\bValidate\x18\x06 \x01(\bR\bValidate\x12\x18\n" +
+	"\aInstall\x18\a \x01(\bR\aInstall\x12%\n" +
+	"\vDestination\x18\b \x01(\tH\x03R\vDestination\x88\x01\x01B\v\n" +
+	"\t_UsernameB\v\n" +
+	"\t_PasswordB\b\n" +
+	"\x06_TokenB\x0e\n" +
+	"\f_Destination\"\x9b\x02\n" +
+	"\x10RegistryProgress\x12\x14\n" +
+	"\x05Event\x18\x01 \x01(\tR\x05Event\x12!\n" +
+	"\tReference\x18\x02 \x01(\tH\x00R\tReference\x88\x01\x01\x12\x1b\n" +
+	"\x06Digest\x18\x03 \x01(\tH\x01R\x06Digest\x88\x01\x01\x12\x1d\n" +
+	"\aCurrent\x18\x04 \x01(\x04H\x02R\aCurrent\x88\x01\x01\x12\x19\n" +
+	"\x05Total\x18\x05 \x01(\x04H\x03R\x05Total\x88\x01\x01\x12\x17\n" +
+	"\x04Path\x18\x06 \x01(\tH\x04R\x04Path\x88\x01\x01\x12\x1b\n" +
+	"\x06Detail\x18\a \x01(\tH\x05R\x06Detail\x88\x01\x01B\f\n" +
+	"\n" +
+	"_ReferenceB\t\n" +
+	"\a_DigestB\n" +
+	"\n" +
+	"\b_CurrentB\b\n" +
+	"\x06_TotalB\a\n" +
+	"\x05_PathB\t\n" +
+	"\a_Detail\"\xf5\x01\n" +
+	"\x0eUpdateProgress\x12\x14\n" +
+	"\x05Phase\x18\x01 \x01(\tR\x05Phase\x12\x1c\n" +
+	"\tPathsDone\x18\x02 \x01(\x04R\tPathsDone\x12\x1e\n" +
+	"\n" +
+	"PathsTotal\x18\x03 \x01(\x04R\n" +
+	"PathsTotal\x12\x1c\n" +
+	"\tBytesDone\x18\x04 \x01(\x04R\tBytesDone\x12\x1e\n" +
+	"\n" +
+	"BytesTotal\x18\x05 \x01(\x04R\n" +
+	"BytesTotal\x12!\n" +
+	"\tStorePath\x18\x06 \x01(\tH\x00R\tStorePath\x88\x01\x01\x12\x17\n" +
+	"\x04Step\x18\a \x01(\tH\x01R\x04Step\x88\x01\x01B\f\n" +
+	"\n" +
+	"_StorePathB\a\n" +
+	"\x05_Step\"\x8c\x02\n" +
 	"\x15SetGenerationResponse\x12\x1a\n" +
 	"\bFinished\x18\x01 \x01(\bR\bFinished\x12\x1b\n" +
 	"\x06Output\x18\x02 \x01(\tH\x00R\x06Output\x88\x01\x01\x12\x19\n" +
-	"\x05Error\x18\x03 \x01(\tH\x01R\x05Error\x88\x01\x01B\t\n" +
+	"\x05Error\x18\x03 \x01(\tH\x01R\x05Error\x88\x01\x01\x128\n" +
+	"\bRegistry\x18\x04 \x01(\v2\x17.admin.RegistryProgressH\x02R\bRegistry\x88\x01\x01\x126\n" +
+	"\bProgress\x18\x05 \x01(\v2\x15.admin.UpdateProgressH\x03R\bProgress\x88\x01\x01B\t\n" +
 	"\a_OutputB\b\n" +
-	"\x06_Error\"M\n" +
+	"\x06_ErrorB\v\n" +
+	"\t_RegistryB\v\n" +
+	"\t_Progress\"=\n" +
+	"\x13PolicyStatusRequest\x12\x1b\n" +
+	"\x06VmName\x18\x01 \x01(\tH\x00R\x06VmName\x88\x01\x01B\t\n" +
+	"\a_VmName\"\xf9\x02\n" +
+	"\x14PolicyDeliveryStatus\x12\x16\n" +
+	"\x06VmName\x18\x01 \x01(\tR\x06VmName\x12\x16\n" +
+	"\x06Policy\x18\x02 \x01(\tR\x06Policy\x12\x1f\n" +
+	"\bRevision\x18\x03 \x01(\tH\x00R\bRevision\x88\x01\x01\x12\x17\n" +
+	"\x04File\x18\x04 \x01(\tH\x01R\x04File\x88\x01\x01\x12\x1b\n" +
+	"\x06Change\x18\x05 \x01(\tH\x02R\x06Change\x88\x01\x01\x12\x1b\n" +
+	"\x06Sha256\x18\x06 \x01(\tH\x03R\x06Sha256\x88\x01\x01\x12%\n" +
+	"\vLastAttempt\x18\a \x01(\x04H\x04R\vLastAttempt\x88\x01\x01\x12%\n" +
+	"\vLastSuccess\x18\b \x01(\x04H\x05R\vLastSuccess\x88\x01\x01\x12\x19\n" +
+	"\x05Error\x18\t \x01(\tH\x06R\x05Error\x88\x01\x01B\v\n" +
+	"\t_RevisionB\a\n" +
+	"\x05_FileB\t\n" +
+	"\a_ChangeB\t\n" +
+	"\a_Sha256B\x0e\n" +
+	"\f_LastAttemptB\x0e\n" +
+	"\f_LastSuccessB\b\n" +
+	"\x06_Error\"G\n" +
+	"\x14PolicyStatusResponse\x12/\n" +
+	"\x04List\x18\x01 \x03(\v2\x1b.admin.PolicyDeliveryStatusR\x04List\"M\n" +
 	"\vCtapRequest\x12\x10\n" +
 	"\x03Req\x18\x01 \x01(\tR\x03Req\x12\x12\n" +
 	"\x04Args\x18\x02 \x03(\tR\x04Args\x12\x18\n" +
 	"\aPayload\x18\x03 \x01(\fR\aPayload\"&\n" +
 	"\fCtapResponse\x12\x16\n" +
-	"\x06Output\x18\x01 \x01(\fR\x06Output2\xe2\n" +
-	"\n" +
+	"\x06Output\x18\x01 \x01(\fR\x06Output2\xc7\f\n" +
 	"\fAdminService\x12D\n" +
 	"\x0fRegisterService\x12\x16.admin.RegistryRequest\x1a\x17.admin.RegistryResponse\"\x00\x12E\n" +
 	"\x10StartApplication\x12\x19.admin.ApplicationRequest\x1a\x14.admin.StartResponse\"\x00\x128\n" +
@@ -1571,11 +2439,14 @@ const file_admin_admin_proto_rawDesc = "" +
 	"\tQueryList\x12\f.admin.Empty\x1a\x18.admin.QueryListResponse\"\x00\x12+\n" +
 	"\x05Watch\x12\f.admin.Empty\x1a\x10.admin.WatchItem\"\x000\x01\x12A\n" +
 	"\x0fListGenerations\x12\f.admin.Empty\x1a\x1e.admin.ListGenerationsResponse\"\x00\x12N\n" +
-	"\rSetGeneration\x12\x1b.admin.SetGenerationRequest\x1a\x1c.admin.SetGenerationResponse\"\x000\x01\x12>\n" +
+	"\rSetGeneration\x12\x1b.admin.SetGenerationRequest\x1a\x1c.admin.SetGenerationResponse\"\x000\x01\x12G\n" +
+	"\x12RollbackGeneration\x12\f.admin.Empty\x1a!.admin.RollbackGenerationResponse\"\x00\x12O\n" +
+	"\x0eCollectGarbage\x12\x1c.admin.CollectGarbageRequest\x1a\x1d.admin.CollectGarbageResponse\"\x00\x12>\n" +
 	"\n" +
 	"NotifyUser\x12\x1e.admin.UserNotificationRequest\x1a\x0e.notify.Status\"\x00\x12/\n" +
 	"\x04Ctap\x12\x11.ctap.CtapRequest\x1a\x12.ctap.CtapResponse\"\x00\x121\n" +
-	"\aSysinfo\x12\f.admin.Empty\x1a\x16.stats.SysinfoResponse\"\x00B\x18Z\x16givc/modules/api/adminb\x06proto3"
+	"\aSysinfo\x12\f.admin.Empty\x1a\x16.stats.SysinfoResponse\"\x00\x12I\n" +
+	"\fPolicyStatus\x12\x1a.admin.PolicyStatusRequest\x1a\x1b.admin.PolicyStatusResponse\"\x00B\x18Z\x16givc/modules/api/adminb\x06proto3"
 
 var (
 	file_admin_admin_proto_rawDescOnce sync.Once
@@ -1589,104 +2460,126 @@ func file_admin_admin_proto_rawDescGZIP() []byte {
 	return file_admin_admin_proto_rawDescData
 }
 
-var file_admin_admin_proto_msgTypes = make([]protoimpl.MessageInfo, 24)
+var file_admin_admin_proto_msgTypes = make([]protoimpl.MessageInfo, 34)
 var file_admin_admin_proto_goTypes = []any{
-	(*TransportConfig)(nil),         // 0: admin.TransportConfig
-	(*RegistryRequest)(nil),         // 1: admin.RegistryRequest
-	(*RegistryResponse)(nil),        // 2: admin.RegistryResponse
-	(*ApplicationRequest)(nil),      // 3: admin.ApplicationRequest
-	(*StatsRequest)(nil),            // 4: admin.StatsRequest
-	(*StartVMRequest)(nil),          // 5: admin.StartVMRequest
-	(*ServiceRequest)(nil),          // 6: admin.ServiceRequest
-	(*StartResponse)(nil),           // 7: admin.StartResponse
-	(*ApplicationResponse)(nil),     // 8: admin.ApplicationResponse
-	(*UnitStatusRequest)(nil),       // 9: admin.UnitStatusRequest
-	(*Empty)(nil),                   // 10: admin.Empty
-	(*QueryListItem)(nil),           // 11: admin.QueryListItem
-	(*QueryListResponse)(nil),       // 12: admin.QueryListResponse
-	(*WatchItem)(nil),               // 13: admin.WatchItem
-	(*LocaleRequest)(nil),           // 14: admin.LocaleRequest
-	(*TimezoneRequest)(nil),         // 15: admin.TimezoneRequest
-	(*UserNotificationRequest)(nil), // 16: admin.UserNotificationRequest
-	(*Generation)(nil),              // 17: admin.Generation
-	(*ListGenerationsResponse)(nil), // 18: admin.ListGenerationsResponse
-	(*SetGenerationRequest)(nil),    // 19: admin.SetGenerationRequest
-	(*Cachix)(nil),                  // 20: admin.Cachix
-	(*SetGenerationResponse)(nil),   // 21: admin.SetGenerationResponse
-	(*CtapRequest)(nil),             // 22: admin.CtapRequest
-	(*CtapResponse)(nil),            // 23: admin.CtapResponse
-	(*systemd.UnitStatus)(nil),      // 24: systemd.UnitStatus
-	(*locale.LocaleAssignment)(nil), // 25: locale.LocaleAssignment
-	(*notify.UserNotification)(nil), // 26: notify.UserNotification
-	(*ctap.CtapRequest)(nil),        // 27: ctap.CtapRequest
-	(*stats.StatsResponse)(nil),     // 28: stats.StatsResponse
-	(*notify.Status)(nil),           // 29: notify.Status
-	(*ctap.CtapResponse)(nil),       // 30: ctap.CtapResponse
-	(*stats.SysinfoResponse)(nil),   // 31: stats.SysinfoResponse
+	(*TransportConfig)(nil),            // 0: admin.TransportConfig
+	(*RegistryRequest)(nil),            // 1: admin.RegistryRequest
+	(*RegistryResponse)(nil),           // 2: admin.RegistryResponse
+	(*ApplicationRequest)(nil),         // 3: admin.ApplicationRequest
+	(*StatsRequest)(nil),               // 4: admin.StatsRequest
+	(*StartVMRequest)(nil),             // 5: admin.StartVMRequest
+	(*ServiceRequest)(nil),             // 6: admin.ServiceRequest
+	(*StartResponse)(nil),              // 7: admin.StartResponse
+	(*ApplicationResponse)(nil),        // 8: admin.ApplicationResponse
+	(*UnitStatusRequest)(nil),          // 9: admin.UnitStatusRequest
+	(*Empty)(nil),                      // 10: admin.Empty
+	(*QueryListItem)(nil),              // 11: admin.QueryListItem
+	(*Liveness)(nil),                   // 12: admin.Liveness
+	(*QueryListResponse)(nil),          // 13: admin.QueryListResponse
+	(*WatchItem)(nil),                  // 14: admin.WatchItem
+	(*LocaleRequest)(nil),              // 15: admin.LocaleRequest
+	(*TimezoneRequest)(nil),            // 16: admin.TimezoneRequest
+	(*UserNotificationRequest)(nil),    // 17: admin.UserNotificationRequest
+	(*Generation)(nil),                 // 18: admin.Generation
+	(*ListGenerationsResponse)(nil),    // 19: admin.ListGenerationsResponse
+	(*RollbackGenerationResponse)(nil), // 20: admin.RollbackGenerationResponse
+	(*CollectGarbageRequest)(nil),      // 21: admin.CollectGarbageRequest
+	(*CollectGarbageResponse)(nil),     // 22: admin.CollectGarbageResponse
+	(*SetGenerationRequest)(nil),       // 23: admin.SetGenerationRequest
+	(*Cachix)(nil),                     // 24: admin.Cachix
+	(*Oci)(nil),                        // 25: admin.Oci
+	(*RegistryProgress)(nil),           // 26: admin.RegistryProgress
+	(*UpdateProgress)(nil),             // 27: admin.UpdateProgress
+	(*SetGenerationResponse)(nil),      // 28: admin.SetGenerationResponse
+	(*PolicyStatusRequest)(nil),        // 29: admin.PolicyStatusRequest
+	(*PolicyDeliveryStatus)(nil),       // 30: admin.PolicyDeliveryStatus
+	(*PolicyStatusResponse)(nil),       // 31: admin.PolicyStatusResponse
+	(*CtapRequest)(nil),                // 32: admin.CtapRequest
+	(*CtapResponse)(nil),               // 33: admin.CtapResponse
+	(*systemd.UnitStatus)(nil),         // 34: systemd.UnitStatus
+	(*locale.LocaleAssignment)(nil),    // 35: locale.LocaleAssignment
+	(*notify.UserNotification)(nil),    // 36: notify.UserNotification
+	(*ctap.CtapRequest)(nil),           // 37: ctap.CtapRequest
+	(*stats.StatsResponse)(nil),        // 38: stats.StatsResponse
+	(*notify.Status)(nil),              // 39: notify.Status
+	(*ctap.CtapResponse)(nil),          // 40: ctap.CtapResponse
+	(*stats.SysinfoResponse)(nil),      // 41: stats.SysinfoResponse
 }
 var file_admin_admin_proto_depIdxs = []int32{
 	0,  // 0: admin.RegistryRequest.Transport:type_name -> admin.TransportConfig
-	24, // 1: admin.RegistryRequest.State:type_name -> systemd.UnitStatus
-	11, // 2: admin.QueryListResponse.List:type_name -> admin.QueryListItem
-	12, // 3: admin.WatchItem.Initial:type_name -> admin.QueryListResponse
-	11, // 4: admin.WatchItem.Added:type_name -> admin.QueryListItem
-	11, // 5: admin.WatchItem.Updated:type_name -> admin.QueryListItem
-	11, // 6: admin.WatchItem.Removed:type_name -> admin.QueryListItem
-	25, // 7: admin.LocaleRequest.assignments:type_name -> locale.LocaleAssignment
-	26, // 8: admin.UserNotificationRequest.Notification:type_name -> notify.UserNotification
-	17, // 9: admin.ListGenerationsResponse.List:type_name -> admin.Generation
-	20, // 10: admin.SetGenerationRequest.Cachix:type_name -> admin.Cachix
-	1,  // 11: admin.AdminService.RegisterService:input_type -> admin.RegistryRequest
-	3,  // 12: admin.AdminService.StartApplication:input_type -> admin.ApplicationRequest
-	5,  // 13: admin.AdminService.StartVM:input_type -> admin.StartVMRequest
-	6,  // 14: admin.AdminService.StartService:input_type -> admin.ServiceRequest
-	6,  // 15: admin.AdminService.StopService:input_type -> admin.ServiceRequest
-	3,  // 16: admin.AdminService.PauseApplication:input_type -> admin.ApplicationRequest
-	3,  // 17: admin.AdminService.ResumeApplication:input_type -> admin.ApplicationRequest
-	3,  // 18: admin.AdminService.StopApplication:input_type -> admin.ApplicationRequest
-	14, // 19: admin.AdminService.SetLocale:input_type -> admin.LocaleRequest
-	15, // 20: admin.AdminService.SetTimezone:input_type -> admin.TimezoneRequest
-	10, // 21: admin.AdminService.Poweroff:input_type -> admin.Empty
-	10, // 22: admin.AdminService.Reboot:input_type -> admin.Empty
-	10, // 23: admin.AdminService.Suspend:input_type -> admin.Empty
-	10, // 24: admin.AdminService.Wakeup:input_type -> admin.Empty
-	9,  // 25: admin.AdminService.GetUnitStatus:input_type -> admin.UnitStatusRequest
-	4,  // 26: admin.AdminService.GetStats:input_type -> admin.StatsRequest
-	10, // 27: admin.AdminService.QueryList:input_type -> admin.Empty
-	10, // 28: admin.AdminService.Watch:input_type -> admin.Empty
-	10, // 29: admin.AdminService.ListGenerations:input_type -> admin.Empty
-	19, // 30: admin.AdminService.SetGeneration:input_type -> admin.SetGenerationRequest
-	16, // 31: admin.AdminService.NotifyUser:input_type -> admin.UserNotificationRequest
-	27, // 32: admin.AdminService.Ctap:input_type -> ctap.CtapRequest
-	10, // 33: admin.AdminService.Sysinfo:input_type -> admin.Empty
-	2,  // 34: admin.AdminService.RegisterService:output_type -> admin.RegistryResponse
-	7,  // 35: admin.AdminService.StartApplication:output_type -> admin.StartResponse
-	7,  // 36: admin.AdminService.StartVM:output_type -> admin.StartResponse
-	7,  // 37: admin.AdminService.StartService:output_type -> admin.StartResponse
-	8,  // 38: admin.AdminService.StopService:output_type -> admin.ApplicationResponse
-	8,  // 39: admin.AdminService.PauseApplication:output_type -> admin.ApplicationResponse
-	8,  // 40: admin.AdminService.ResumeApplication:output_type -> admin.ApplicationResponse
-	8,  // 41: admin.AdminService.StopApplication:output_type -> admin.ApplicationResponse
-	10, // 42: admin.AdminService.SetLocale:output_type -> admin.Empty
-	10, // 43: admin.AdminService.SetTimezone:output_type -> admin.Empty
-	10, // 44: admin.AdminService.Poweroff:output_type -> admin.Empty
-	10, // 45: admin.AdminService.Reboot:output_type -> admin.Empty
-	10, // 46: admin.AdminService.Suspend:output_type -> admin.Empty
-	10, // 47: admin.AdminService.Wakeup:output_type -> admin.Empty
-	24, // 48: admin.AdminService.GetUnitStatus:output_type -> systemd.UnitStatus
-	28, // 49: admin.AdminService.GetStats:output_type -> stats.StatsResponse
-	12, // 50: admin.AdminService.QueryList:output_type -> admin.QueryListResponse
-	13, // 51: admin.AdminService.Watch:output_type -> admin.WatchItem
-	18, // 52: admin.AdminService.ListGenerations:output_type -> admin.ListGenerationsResponse
-	21, // 53: admin.AdminService.SetGeneration:output_type -> admin.SetGenerationResponse
-	29, // 54: admin.AdminService.NotifyUser:output_type -> notify.Status
-	30, // 55: admin.AdminService.Ctap:output_type -> ctap.CtapResponse
-	31, // 56: admin.AdminService.Sysinfo:output_type -> stats.SysinfoResponse
-	34, // [34:57] is the sub-list for method output_type
-	11, // [11:34] is the sub-list for method input_type
-	11, // [11:11] is the sub-list for extension type_name
-	11, // [11:11] is the sub-list for extension extendee
-	0,  // [0:11] is the sub-list for field type_name
+	34, // 1: admin.RegistryRequest.State:type_name -> systemd.UnitStatus
+	12, // 2: admin.QueryListItem.Liveness:type_name -> admin.Liveness
+	11, // 3: admin.QueryListResponse.List:type_name -> admin.QueryListItem
+	13, // 4: admin.WatchItem.Initial:type_name -> admin.QueryListResponse
+	11, // 5: admin.WatchItem.Added:type_name -> admin.QueryListItem
+	11, // 6: admin.WatchItem.Updated:type_name -> admin.QueryListItem
+	11, // 7: admin.WatchItem.Removed:type_name -> admin.QueryListItem
+	11, // 8: admin.WatchItem.Failed:type_name -> admin.QueryListItem
+	35, // 9: admin.LocaleRequest.assignments:type_name -> locale.LocaleAssignment
+	36, // 10: admin.UserNotificationRequest.Notification:type_name -> notify.UserNotification
+	18, // 11: admin.ListGenerationsResponse.List:type_name -> admin.Generation
+	24, // 12: admin.SetGenerationRequest.Cachix:type_name -> admin.Cachix
+	25, // 13: admin.SetGenerationRequest.Oci:type_name -> admin.Oci
+	26, // 14: admin.SetGenerationResponse.Registry:type_name -> admin.RegistryProgress
+	27, // 15: admin.SetGenerationResponse.Progress:type_name -> admin.UpdateProgress
+	30, // 16: admin.PolicyStatusResponse.List:type_name -> admin.PolicyDeliveryStatus
+	1,  // 17: admin.AdminService.RegisterService:input_type -> admin.RegistryRequest
+	3,  // 18: admin.AdminService.StartApplication:input_type -> admin.ApplicationRequest
+	5,  // 19: admin.AdminService.StartVM:input_type -> admin.StartVMRequest
+	6,  // 20: admin.AdminService.StartService:input_type -> admin.ServiceRequest
+	6,  // 21: admin.AdminService.StopService:input_type -> admin.ServiceRequest
+	3,  // 22: admin.AdminService.PauseApplication:input_type -> admin.ApplicationRequest
+	3,  // 23: admin.AdminService.ResumeApplication:input_type -> admin.ApplicationRequest
+	3,  // 24: admin.AdminService.StopApplication:input_type -> admin.ApplicationRequest
+	15, // 25: admin.AdminService.SetLocale:input_type -> admin.LocaleRequest
+	16, // 26: admin.AdminService.SetTimezone:input_type -> admin.TimezoneRequest
+	10, // 27: admin.AdminService.Poweroff:input_type -> admin.Empty
+	10, // 28: admin.AdminService.Reboot:input_type -> admin.Empty
+	10, // 29: admin.AdminService.Suspend:input_type -> admin.Empty
+	10, // 30: admin.AdminService.Wakeup:input_type -> admin.Empty
+	9,  // 31: admin.AdminService.GetUnitStatus:input_type -> admin.UnitStatusRequest
+	4,  // 32: admin.AdminService.GetStats:input_type -> admin.StatsRequest
+	10, // 33: admin.AdminService.QueryList:input_type -> admin.Empty
+	10, // 34: admin.AdminService.Watch:input_type -> admin.Empty
+	10, // 35: admin.AdminService.ListGenerations:input_type -> admin.Empty
+	23, // 36: admin.AdminService.SetGeneration:input_type -> admin.SetGenerationRequest
+	10, // 37: admin.AdminService.RollbackGeneration:input_type -> admin.Empty
+	21, // 38: admin.AdminService.CollectGarbage:input_type -> admin.CollectGarbageRequest
+	17, // 39: admin.AdminService.NotifyUser:input_type -> admin.UserNotificationRequest
+	37, // 40: admin.AdminService.Ctap:input_type -> ctap.CtapRequest
+	10, // 41: admin.AdminService.Sysinfo:input_type -> admin.Empty
+	29, // 42: admin.AdminService.PolicyStatus:input_type -> admin.PolicyStatusRequest
+	2,  // 43: admin.AdminService.RegisterService:output_type -> admin.RegistryResponse
+	7,  // 44: admin.AdminService.StartApplication:output_type -> admin.StartResponse
+	7,  // 45: admin.AdminService.StartVM:output_type -> admin.StartResponse
+	7,  // 46: admin.AdminService.StartService:output_type -> admin.StartResponse
+	8,  // 47: admin.AdminService.StopService:output_type -> admin.ApplicationResponse
+	8,  // 48: admin.AdminService.PauseApplication:output_type -> admin.ApplicationResponse
+	8,  // 49: admin.AdminService.ResumeApplication:output_type -> admin.ApplicationResponse
+	8,  // 50: admin.AdminService.StopApplication:output_type -> admin.ApplicationResponse
+	10, // 51: admin.AdminService.SetLocale:output_type -> admin.Empty
+	10, // 52: admin.AdminService.SetTimezone:output_type -> admin.Empty
+	10, // 53: admin.AdminService.Poweroff:output_type -> admin.Empty
+	10, // 54: admin.AdminService.Reboot:output_type -> admin.Empty
+	10, // 55: admin.AdminService.Suspend:output_type -> admin.Empty
+	10, // 56: admin.AdminService.Wakeup:output_type -> admin.Empty
+	34, // 57: admin.AdminService.GetUnitStatus:output_type -> systemd.UnitStatus
+	38, // 58: admin.AdminService.GetStats:output_type -> stats.StatsResponse
+	13, // 59: admin.AdminService.QueryList:output_type -> admin.QueryListResponse
+	14, // 60: admin.AdminService.Watch:output_type -> admin.WatchItem
+	19, // 61: admin.AdminService.ListGenerations:output_type -> admin.ListGenerationsResponse
+	28, // 62: admin.AdminService.SetGeneration:output_type -> admin.SetGenerationResponse
+	20, // 63: admin.AdminService.RollbackGeneration:output_type -> admin.RollbackGenerationResponse
+	22, // 64: admin.AdminService.CollectGarbage:output_type -> admin.CollectGarbageResponse
+	39, // 65: admin.AdminService.NotifyUser:output_type -> notify.Status
+	40, // 66: admin.AdminService.Ctap:output_type -> ctap.CtapResponse
+	41, // 67: admin.AdminService.Sysinfo:output_type -> stats.SysinfoResponse
+	31, // 68: admin.AdminService.PolicyStatus:output_type -> admin.PolicyStatusResponse
+	43, // [43:69] is the sub-list for method output_type
+	17, // [17:43] is the sub-list for method input_type
+	17, // [17:17] is the sub-list for extension type_name
+	17, // [17:17] is the sub-list for extension extendee
+	0,  // [0:17] is the sub-list for field type_name
 }
 
 func init() { file_admin_admin_proto_init() }
@@ -1697,24 +2590,34 @@ func file_admin_admin_proto_init() {
 	file_admin_admin_proto_msgTypes[2].OneofWrappers = []any{}
 	file_admin_admin_proto_msgTypes[3].OneofWrappers = []any{}
 	file_admin_admin_proto_msgTypes[11].OneofWrappers = []any{}
-	file_admin_admin_proto_msgTypes[13].OneofWrappers = []any{
+	file_admin_admin_proto_msgTypes[12].OneofWrappers = []any{}
+	file_admin_admin_proto_msgTypes[14].OneofWrappers = []any{
 		(*WatchItem_Initial)(nil),
 		(*WatchItem_Added)(nil),
 		(*WatchItem_Updated)(nil),
 		(*WatchItem_Removed)(nil),
+		(*WatchItem_Failed)(nil),
 	}
-	file_admin_admin_proto_msgTypes[19].OneofWrappers = []any{
-		(*SetGenerationRequest_Cachix)(nil),
-	}
+	file_admin_admin_proto_msgTypes[18].OneofWrappers = []any{}
 	file_admin_admin_proto_msgTypes[20].OneofWrappers = []any{}
-	file_admin_admin_proto_msgTypes[21].OneofWrappers = []any{}
+	file_admin_admin_proto_msgTypes[23].OneofWrappers = []any{
+		(*SetGenerationRequest_Cachix)(nil),
+		(*SetGenerationRequest_Oci)(nil),
+	}
+	file_admin_admin_proto_msgTypes[24].OneofWrappers = []any{}
+	file_admin_admin_proto_msgTypes[25].OneofWrappers = []any{}
+	file_admin_admin_proto_msgTypes[26].OneofWrappers = []any{}
+	file_admin_admin_proto_msgTypes[27].OneofWrappers = []any{}
+	file_admin_admin_proto_msgTypes[28].OneofWrappers = []any{}
+	file_admin_admin_proto_msgTypes[29].OneofWrappers = []any{}
+	file_admin_admin_proto_msgTypes[30].OneofWrappers = []any{}
 	type x struct{}
 	out := protoimpl.TypeBuilder{
 		File: protoimpl.DescBuilder{
 			GoPackagePath: reflect.TypeOf(x{}).PkgPath(),
 			RawDescriptor: unsafe.Slice(unsafe.StringData(file_admin_admin_proto_rawDesc), len(file_admin_admin_proto_rawDesc)),
 			NumEnums:      0,
-			NumMessages:   24,
+			NumMessages:   34,
 			NumExtensions: 0,
 			NumServices:   1,
 		},
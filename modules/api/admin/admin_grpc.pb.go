@@ -25,29 +25,32 @@ import (
 const _ = grpc.SupportPackageIsVersion9
 
 const (
-	AdminService_RegisterService_FullMethodName   = "/admin.AdminService/RegisterService"
-	AdminService_StartApplication_FullMethodName  = "/admin.AdminService/StartApplication"
-	AdminService_StartVM_FullMethodName           = "/admin.AdminService/StartVM"
-	AdminService_StartService_FullMethodName      = "/admin.AdminService/StartService"
-	AdminService_StopService_FullMethodName       = "/admin.AdminService/StopService"
-	AdminService_PauseApplication_FullMethodName  = "/admin.AdminService/PauseApplication"
-	AdminService_ResumeApplication_FullMethodName = "/admin.AdminService/ResumeApplication"
-	AdminService_StopApplication_FullMethodName   = "/admin.AdminService/StopApplication"
-	AdminService_SetLocale_FullMethodName         = "/admin.AdminService/SetLocale"
-	AdminService_SetTimezone_FullMethodName       = "/admin.AdminService/SetTimezone"
-	AdminService_Poweroff_FullMethodName          = "/admin.AdminService/Poweroff"
-	AdminService_Reboot_FullMethodName            = "/admin.AdminService/Reboot"
-	AdminService_Suspend_FullMethodName           = "/admin.AdminService/Suspend"
-	AdminService_Wakeup_FullMethodName            = "/admin.AdminService/Wakeup"
-	AdminService_GetUnitStatus_FullMethodName     = "/admin.AdminService/GetUnitStatus"
-	AdminService_GetStats_FullMethodName          = "/admin.AdminService/GetStats"
-	AdminService_QueryList_FullMethodName         = "/admin.AdminService/QueryList"
-	AdminService_Watch_FullMethodName             = "/admin.AdminService/Watch"
-	AdminService_ListGenerations_FullMethodName   = "/admin.AdminService/ListGenerations"
-	AdminService_SetGeneration_FullMethodName     = "/admin.AdminService/SetGeneration"
-	AdminService_NotifyUser_FullMethodName        = "/admin.AdminService/NotifyUser"
-	AdminService_Ctap_FullMethodName              = "/admin.AdminService/Ctap"
-	AdminService_Sysinfo_FullMethodName           = "/admin.AdminService/Sysinfo"
+	AdminService_RegisterService_FullMethodName    = "/admin.AdminService/RegisterService"
+	AdminService_StartApplication_FullMethodName   = "/admin.AdminService/StartApplication"
+	AdminService_StartVM_FullMethodName            = "/admin.AdminService/StartVM"
+	AdminService_StartService_FullMethodName       = "/admin.AdminService/StartService"
+	AdminService_StopService_FullMethodName        = "/admin.AdminService/StopService"
+	AdminService_PauseApplication_FullMethodName   = "/admin.AdminService/PauseApplication"
+	AdminService_ResumeApplication_FullMethodName  = "/admin.AdminService/ResumeApplication"
+	AdminService_StopApplication_FullMethodName    = "/admin.AdminService/StopApplication"
+	AdminService_SetLocale_FullMethodName          = "/admin.AdminService/SetLocale"
+	AdminService_SetTimezone_FullMethodName        = "/admin.AdminService/SetTimezone"
+	AdminService_Poweroff_FullMethodName           = "/admin.AdminService/Poweroff"
+	AdminService_Reboot_FullMethodName             = "/admin.AdminService/Reboot"
+	AdminService_Suspend_FullMethodName            = "/admin.AdminService/Suspend"
+	AdminService_Wakeup_FullMethodName             = "/admin.AdminService/Wakeup"
+	AdminService_GetUnitStatus_FullMethodName      = "/admin.AdminService/GetUnitStatus"
+	AdminService_GetStats_FullMethodName           = "/admin.AdminService/GetStats"
+	AdminService_QueryList_FullMethodName          = "/admin.AdminService/QueryList"
+	AdminService_Watch_FullMethodName              = "/admin.AdminService/Watch"
+	AdminService_ListGenerations_FullMethodName    = "/admin.AdminService/ListGenerations"
+	AdminService_SetGeneration_FullMethodName      = "/admin.AdminService/SetGeneration"
+	AdminService_RollbackGeneration_FullMethodName = "/admin.AdminService/RollbackGeneration"
+	AdminService_CollectGarbage_FullMethodName     = "/admin.AdminService/CollectGarbage"
+	AdminService_NotifyUser_FullMethodName         = "/admin.AdminService/NotifyUser"
+	AdminService_Ctap_FullMethodName               = "/admin.AdminService/Ctap"
+	AdminService_Sysinfo_FullMethodName            = "/admin.AdminService/Sysinfo"
+	AdminService_PolicyStatus_FullMethodName       = "/admin.AdminService/PolicyStatus"
 )
 
 // AdminServiceClient is the client API for AdminService service.
@@ -93,12 +96,16 @@ type AdminServiceClient interface {
 	// OTA
 	ListGenerations(ctx context.Context, in *Empty, opts ...grpc.CallOption) (*ListGenerationsResponse, error)
 	SetGeneration(ctx context.Context, in *SetGenerationRequest, opts ...grpc.CallOption) (grpc.ServerStreamingClient[SetGenerationResponse], error)
+	RollbackGeneration(ctx context.Context, in *Empty, opts ...grpc.CallOption) (*RollbackGenerationResponse, error)
+	CollectGarbage(ctx context.Context, in *CollectGarbageRequest, opts ...grpc.CallOption) (*CollectGarbageResponse, error)
 	// Send user notification
 	NotifyUser(ctx context.Context, in *UserNotificationRequest, opts ...grpc.CallOption) (*notify.Status, error)
 	// Perform CTAP action
 	Ctap(ctx context.Context, in *ctap.CtapRequest, opts ...grpc.CallOption) (*ctap.CtapResponse, error)
 	// Get host sysinfo values in one call
 	Sysinfo(ctx context.Context, in *Empty, opts ...grpc.CallOption) (*stats.SysinfoResponse, error)
+	// Get delivery status of policies to VMs
+	PolicyStatus(ctx context.Context, in *PolicyStatusRequest, opts ...grpc.CallOption) (*PolicyStatusResponse, error)
 }
 
 type adminServiceClient struct {
@@ -327,6 +334,26 @@ func (c *adminServiceClient) SetGeneration(ctx context.Context, in *SetGeneratio
 // This type alias is provided for backwards compatibility with existing code that references the prior non-generic stream type by name.
 type AdminService_SetGenerationClient = grpc.ServerStreamingClient[SetGenerationResponse]
 
+func (c *adminServiceClient) RollbackGeneration(ctx context.Context, in *Empty, opts ...grpc.CallOption) (*RollbackGenerationResponse, error) {
+	cOpts := append([]grpc.CallOption{grpc.StaticMethod()}, opts...)
+	out := new(RollbackGenerationResponse)
+	err := c.cc.Invoke(ctx, AdminService_RollbackGeneration_FullMethodName, in, out, cOpts...)
+	if err != nil {
+		return nil, err
+	}
+	return out, nil
+}
+
+func (c *adminServiceClient) CollectGarbage(ctx context.Context, in *CollectGarbageRequest, opts ...grpc.CallOption) (*CollectGarbageResponse, error) {
+	cOpts := append([]grpc.CallOption{grpc.StaticMethod()}, opts...)
+	out := new(CollectGarbageResponse)
+	err := c.cc.Invoke(ctx, AdminService_CollectGarbage_FullMethodName, in, out, cOpts...)
+	if err != nil {
+		return nil, err
+	}
+	return out, nil
+}
+
 func (c *adminServiceClient) NotifyUser(ctx context.Context, in *UserNotificationRequest, opts ...grpc.CallOption) (*notify.Status, error) {
 	cOpts := append([]grpc.CallOption{grpc.StaticMethod()}, opts...)
 	out := new(notify.Status)
@@ -357,6 +384,16 @@ func (c *adminServiceClient) Sysinfo(ctx context.Context, in *Empty, opts ...grp
 	return out, nil
 }
 
+func (c *adminServiceClient) PolicyStatus(ctx context.Context, in *PolicyStatusRequest, opts ...grpc.CallOption) (*PolicyStatusResponse, error) {
+	cOpts := append([]grpc.CallOption{grpc.StaticMethod()}, opts...)
+	out := new(PolicyStatusResponse)
+	err := c.cc.Invoke(ctx, AdminService_PolicyStatus_FullMethodName, in, out, cOpts...)
+	if err != nil {
+		return nil, err
+	}
+	return out, nil
+}
+
 // AdminServiceServer is the server API for AdminService service.
 // All implementations must embed UnimplementedAdminServiceServer
 // for forward compatibility.
@@ -400,12 +437,16 @@ type AdminServiceServer interface {
 	// OTA
 	ListGenerations(context.Context, *Empty) (*ListGenerationsResponse, error)
 	SetGeneration(*SetGenerationRequest, grpc.ServerStreamingServer[SetGenerationResponse]) error
+	RollbackGeneration(context.Context, *Empty) (*RollbackGenerationResponse, error)
+	CollectGarbage(context.Context, *CollectGarbageRequest) (*CollectGarbageResponse, error)
 	// Send user notification
 	NotifyUser(context.Context, *UserNotificationRequest) (*notify.Status, error)
 	// Perform CTAP action
 	Ctap(context.Context, *ctap.CtapRequest) (*ctap.CtapResponse, error)
 	// Get host sysinfo values in one call
 	Sysinfo(context.Context, *Empty) (*stats.SysinfoResponse, error)
+	// Get delivery status of policies to VMs
+	PolicyStatus(context.Context, *PolicyStatusRequest) (*PolicyStatusResponse, error)
 	mustEmbedUnimplementedAdminServiceServer()
 }
 
@@ -476,6 +517,12 @@ func (UnimplementedAdminServiceServer) ListGenerations(context.Context, *Empty)
 func (UnimplementedAdminServiceServer) SetGeneration(*SetGenerationRequest, grpc.ServerStreamingServer[SetGenerationResponse]) error {
 	return status.Error(codes.Unimplemented, "method SetGeneration not implemented")
 }
+func (UnimplementedAdminServiceServer) RollbackGeneration(context.Context, *Empty) (*RollbackGenerationResponse, error) {
+	return nil, status.Error(codes.Unimplemented, "method RollbackGeneration not implemented")
+}
+func (UnimplementedAdminServiceServer) CollectGarbage(context.Context, *CollectGarbageRequest) (*CollectGarbageResponse, error) {
+	return nil, status.Error(codes.Unimplemented, "method CollectGarbage not implemented")
+}
 func (UnimplementedAdminServiceServer) NotifyUser(context.Context, *UserNotificationRequest) (*notify.Status, error) {
 	return nil, status.Error(codes.Unimplemented, "method NotifyUser not implemented")
 }
@@ -485,6 +532,9 @@ func (UnimplementedAdminServiceServer) Ctap(context.Context, *ctap.CtapRequest)
 func (UnimplementedAdminServiceServer) Sysinfo(context.Context, *Empty) (*stats.SysinfoResponse, error) {
 	return nil, status.Error(codes.Unimplemented, "method Sysinfo not implemented")
 }
+func (UnimplementedAdminServiceServer) PolicyStatus(context.Context, *PolicyStatusRequest) (*PolicyStatusResponse, error) {
+	return nil, status.Error(codes.Unimplemented, "method PolicyStatus not implemented")
+}
 func (UnimplementedAdminServiceServer) mustEmbedUnimplementedAdminServiceServer() {}
 func (UnimplementedAdminServiceServer) testEmbeddedByValue()                      {}
 
@@ -852,6 +902,42 @@ func _AdminService_SetGeneration_Handler(srv interface{}, stream grpc.ServerStre
 // This type alias is provided for backwards compatibility with existing code that references the prior non-generic stream type by name.
 type AdminService_SetGenerationServer = grpc.ServerStreamingServer[SetGenerationResponse]
 
+func _AdminService_RollbackGeneration_Handler(srv interface{}, ctx context.Context, dec func(interface{}) error, interceptor grpc.UnaryServerInterceptor) (interface{}, error) {
+	in := new(Empty)
+	if err := dec(in); err != nil {
+		return nil, err
+	}
+	if interceptor == nil {
+		return srv.(AdminServiceServer).RollbackGeneration(ctx, in)
+	}
+	info := &grpc.UnaryServerInfo{
+		Server:     srv,
+		FullMethod: AdminService_RollbackGeneration_FullMethodName,
+	}
+	handler := func(ctx context.Context, req interface{}) (interface{}, error) {
+		return srv.(AdminServiceServer).RollbackGeneration(ctx, req.(*Empty))
+	}
+	return interceptor(ctx, in, info, handler)
+}
+
+func _AdminService_CollectGarbage_Handler(srv interface{}, ctx context.Context, dec func(interface{}) error, interceptor grpc.UnaryServerInterceptor) (interface{}, error) {
+	in := new(CollectGarbageRequest)
+	if err := dec(in); err != nil {
+		return nil, err
+	}
+	if interceptor == nil {
+		return srv.(AdminServiceServer).CollectGarbage(ctx, in)
+	}
+	info := &grpc.UnaryServerInfo{
+		Server:     srv,
+		FullMethod: AdminService_CollectGarbage_FullMethodName,
+	}
+	handler := func(ctx context.Context, req interface{}) (interface{}, error) {
+		return srv.(AdminServiceServer).CollectGarbage(ctx, req.(*CollectGarbageRequest))
+	}
+	return interceptor(ctx, in, info, handler)
+}
+
 func _AdminService_NotifyUser_Handler(srv interface{}, ctx context.Context, dec func(interface{}) error, interceptor grpc.UnaryServerInterceptor) (interface{}, error) {
 	in := new(UserNotificationRequest)
 	if err := dec(in); err != nil {
@@ -906,6 +992,24 @@ func _AdminService_Sysinfo_Handler(srv interface{}, ctx context.Context, dec fun
 	return interceptor(ctx, in, info, handler)
 }
 
+func _AdminService_PolicyStatus_Handler(srv interface{}, ctx context.Context, dec func(interface{}) error, interceptor grpc.UnaryServerInterceptor) (interface{}, error) {
+	in := new(PolicyStatusRequest)
+	if err := dec(in); err != nil {
+		return nil, err
+	}
+	if interceptor == nil {
+		return srv.(AdminServiceServer).PolicyStatus(ctx, in)
+	}
+	info := &grpc.UnaryServerInfo{
+		Server:     srv,
+		FullMethod: AdminService_PolicyStatus_FullMethodName,
+	}
+	handler := func(ctx context.Context, req interface{}) (interface{}, error) {
+		return srv.(AdminServiceServer).PolicyStatus(ctx, req.(*PolicyStatusRequest))
+	}
+	return interceptor(ctx, in, info, handler)
+}
+
 // AdminService_ServiceDesc is the grpc.ServiceDesc for AdminService service.
 // It's only intended for direct use with grpc.RegisterService,
 // and not to be introspected or modified (even as a copy)
@@ -985,6 +1089,14 @@ var AdminService_ServiceDesc = grpc.ServiceDesc{
 			MethodName: "ListGenerations",
 			Handler:    _AdminService_ListGenerations_Handler,
 		},
+		{
+			MethodName: "RollbackGeneration",
+			Handler:    _AdminService_RollbackGeneration_Handler,
+		},
+		{
+			MethodName: "CollectGarbage",
+			Handler:    _AdminService_CollectGarbage_Handler,
+		},
 		{
 			MethodName: "NotifyUser",
 			Handler:    _AdminService_NotifyUser_Handler,
@@ -997,6 +1109,10 @@ var AdminService_ServiceDesc = grpc.ServiceDesc{
 			MethodName: "Sysinfo",
 			Handler:    _AdminService_Sysinfo_Handler,
 		},
+		{
+			MethodName: "PolicyStatus",
+			Handler:    _AdminService_PolicyStatus_Handler,
+		},
 	},
 	Streams: []grpc.StreamDesc{
 		{
@@ -24,10 +24,65 @@ const (
 	_ = protoimpl.EnforceVersion(protoimpl.MaxVersion - 20)
 )
 
+// Kind of change of policy file
+type PolicyChange int32
+
+const (
+	PolicyChange_UPDATE PolicyChange = 0
+	PolicyChange_DELETE PolicyChange = 1
+	PolicyChange_RENAME PolicyChange = 2
+)
+
+// Enum value maps for PolicyChange.
+var (
+	PolicyChange_name = map[int32]string{
+		0: "UPDATE",
+		1: "DELETE",
+		2: "RENAME",
+	}
+	PolicyChange_value = map[string]int32{
+		"UPDATE": 0,
+		"DELETE": 1,
+		"RENAME": 2,
+	}
+)
+
+func (x PolicyChange) Enum() *PolicyChange {
+	p := new(PolicyChange)
+	*p = x
+	return p
+}
+
+func (x PolicyChange) String() string {
+	return protoimpl.X.EnumStringOf(x.Descriptor(), protoreflect.EnumNumber(x))
+}
+
+func (PolicyChange) Descriptor() protoreflect.EnumDescriptor {
+	return file_policyadmin_policyadmin_proto_enumTypes[0].Descriptor()
+}
+
+func (PolicyChange) Type() protoreflect.EnumType {
+	return &file_policyadmin_policyadmin_proto_enumTypes[0]
+}
+
+func (x PolicyChange) Number() protoreflect.EnumNumber {
+	return protoreflect.EnumNumber(x)
+}
+
+// Deprecated: Use PolicyChange.Descriptor instead.
+func (PolicyChange) EnumDescriptor() ([]byte, []int) {
+	return file_policyadmin_policyadmin_proto_rawDescGZIP(), []int{0}
+}
+
+// Kind and file names are only set in first message of stream,
+// DELETE and RENAME are sent without policy chunks.
 type StreamPolicyRequest struct {
 	state         protoimpl.MessageState `protogen:"open.v1"`
 	PolicyName    string                 `protobuf:"bytes,1,opt,name=policy_name,json=policyName,proto3" json:"policy_name,omitempty"`
 	PolicyChunk   []byte                 `protobuf:"bytes,2,opt,name=policy_chunk,json=policyChunk,proto3" json:"policy_chunk,omitempty"`
+	Change        PolicyChange           `protobuf:"varint,3,opt,name=change,proto3,enum=policyadmin.PolicyChange" json:"change,omitempty"`
+	FileName      string                 `protobuf:"bytes,4,opt,name=file_name,json=fileName,proto3" json:"file_name,omitempty"`
+	NewFileName   string                 `protobuf:"bytes,5,opt,name=new_file_name,json=newFileName,proto3" json:"new_file_name,omitempty"`
 	unknownFields protoimpl.UnknownFields
 	sizeCache     protoimpl.SizeCache
 }
@@ -76,6 +131,27 @@ func (x *StreamPolicyRequest) GetPolicyChunk() []byte {
 	return nil
 }
 
+func (x *StreamPolicyRequest) GetChange() PolicyChange {
+	if x != nil {
+		return x.Change
+	}
+	return PolicyChange_UPDATE
+}
+
+func (x *StreamPolicyRequest) GetFileName() string {
+	if x != nil {
+		return x.FileName
+	}
+	return ""
+}
+
+func (x *StreamPolicyRequest) GetNewFileName() string {
+	if x != nil {
+		return x.NewFileName
+	}
+	return ""
+}
+
 type Status struct {
 	state         protoimpl.MessageState `protogen:"open.v1"`
 	Status        string                 `protobuf:"bytes,1,opt,name=Status,proto3" json:"Status,omitempty"`
@@ -124,13 +200,23 @@ var File_policyadmin_policyadmin_proto protoreflect.FileDescriptor
 
 const file_policyadmin_policyadmin_proto_rawDesc = "" +
 	"\n" +
-	"\x1dpolicyadmin/policyadmin.proto\x12\vpolicyadmin\"Y\n" +
+	"\x1dpolicyadmin/policyadmin.proto\x12\vpolicyadmin\"\xcd\x01\n" +
 	"\x13StreamPolicyRequest\x12\x1f\n" +
 	"\vpolicy_name\x18\x01 \x01(\tR\n" +
 	"policyName\x12!\n" +
-	"\fpolicy_chunk\x18\x02 \x01(\fR\vpolicyChunk\" \n" +
+	"\fpolicy_chunk\x18\x02 \x01(\fR\vpolicyChunk\x121\n" +
+	"\x06change\x18\x03 \x01(\x0e2\x19.policyadmin.PolicyChangeR\x06change\x12\x1b\n" +
+	"\tfile_name\x18\x04 \x01(\tR\bfileName\x12\"\n" +
+	"\rnew_file_name\x18\x05 \x01(\tR\vnewFileName\" \n" +
 	"\x06Status\x12\x16\n" +
-	"\x06Status\x18\x01 \x01(\tR\x06Status2V\n" +
+	"\x06Status\x18\x01 \x01(\tR\x06Status*2\n" +
+	"\fPolicyChange\x12\n" +
+	"\n" +
+	"\x06UPDATE\x10\x00\x12\n" +
+	"\n" +
+	"\x06DELETE\x10\x01\x12\n" +
+	"\n" +
+	"\x06RENAME\x10\x022V\n" +
 	"\vPolicyAdmin\x12G\n" +
 	"\fStreamPolicy\x12 .policyadmin.StreamPolicyRequest\x1a\x13.policyadmin.Status(\x01B\x1eZ\x1cgivc/modules/api/policyadminb\x06proto3"
 
@@ -146,19 +232,22 @@ func file_policyadmin_policyadmin_proto_rawDescGZIP() []byte {
 	return file_policyadmin_policyadmin_proto_rawDescData
 }
 
+var file_policyadmin_policyadmin_proto_enumTypes = make([]protoimpl.EnumInfo, 1)
 var file_policyadmin_policyadmin_proto_msgTypes = make([]protoimpl.MessageInfo, 2)
 var file_policyadmin_policyadmin_proto_goTypes = []any{
-	(*StreamPolicyRequest)(nil), // 0: policyadmin.StreamPolicyRequest
-	(*Status)(nil),              // 1: policyadmin.Status
+	(PolicyChange)(0),           // 0: policyadmin.PolicyChange
+	(*StreamPolicyRequest)(nil), // 1: policyadmin.StreamPolicyRequest
+	(*Status)(nil),              // 2: policyadmin.Status
 }
 var file_policyadmin_policyadmin_proto_depIdxs = []int32{
-	0, // 0: policyadmin.PolicyAdmin.StreamPolicy:input_type -> policyadmin.StreamPolicyRequest
-	1, // 1: policyadmin.PolicyAdmin.StreamPolicy:output_type -> policyadmin.Status
-	1, // [1:2] is the sub-list for method output_type
-	0, // [0:1] is the sub-list for method input_type
-	0, // [0:0] is the sub-list for extension type_name
-	0, // [0:0] is the sub-list for extension extendee
-	0, // [0:0] is the sub-list for field type_name
+	0, // 0: policyadmin.StreamPolicyRequest.change:type_name -> policyadmin.PolicyChange
+	1, // 1: policyadmin.PolicyAdmin.StreamPolicy:input_type -> policyadmin.StreamPolicyRequest
+	2, // 2: policyadmin.PolicyAdmin.StreamPolicy:output_type -> policyadmin.Status
+	2, // [2:3] is the sub-list for method output_type
+	1, // [1:2] is the sub-list for method input_type
+	1, // [1:1] is the sub-list for extension type_name
+	1, // [1:1] is the sub-list for extension extendee
+	0, // [0:1] is the sub-list for field type_name
 }
 
 func init() { file_policyadmin_policyadmin_proto_init() }
@@ -171,13 +260,14 @@ func file_policyadmin_policyadmin_proto_init() {
 		File: protoimpl.DescBuilder{
 			GoPackagePath: reflect.TypeOf(x{}).PkgPath(),
 			RawDescriptor: unsafe.Slice(unsafe.StringData(file_policyadmin_policyadmin_proto_rawDesc), len(file_policyadmin_policyadmin_proto_rawDesc)),
-			NumEnums:      0,
+			NumEnums:      1,
 			NumMessages:   2,
 			NumExtensions: 0,
 			NumServices:   1,
 		},
 		GoTypes:           file_policyadmin_policyadmin_proto_goTypes,
 		DependencyIndexes: file_policyadmin_policyadmin_proto_depIdxs,
+		EnumInfos:         file_policyadmin_policyadmin_proto_enumTypes,
 		MessageInfos:      file_policyadmin_policyadmin_proto_msgTypes,
 	}.Build()
 	File_policyadmin_policyadmin_proto = out.File
@@ -142,7 +142,10 @@ func (s *ExecServer) startCommand(req *pb.StartCommand, wg *sync.WaitGroup, stre
 	if req.WorkingDirectory != nil {
 		cmd.Dir = *req.WorkingDirectory
 	}
-	cmd.Env = append(cmd.Env, flattenEnv(req.EnvVars)...)
+	// Requested variables extend inherited environment, instead of replacing it
+	if len(req.EnvVars) > 0 {
+		cmd.Env = append(os.Environ(), flattenEnv(req.EnvVars)...)
+	}
 
 	var stdin io.WriteCloser
 	var stdout, stderr io.ReadCloser
@@ -20,6 +20,7 @@ import (
 type PolicyDetail struct {
 	Destination string
 	Sha         string
+	File        string `json:",omitempty"`
 }
 
 /*
@@ -48,7 +49,7 @@ func NewPolicyAdminController(policy givctypes.Policy) (*PolicyAdminController,
 }
 
 /* Updates the policies to the targets as per defined in config */
-func (self *PolicyAdminController) UpdatePolicy(policyName string, pulledPolicyPath string) error {
+func (self *PolicyAdminController) UpdatePolicy(policyName string, fileName string, pulledPolicyPath string) error {
 	self.mu.Lock()
 	defer self.mu.Unlock()
 
@@ -75,6 +76,10 @@ func (self *PolicyAdminController) UpdatePolicy(policyName string, pulledPolicyP
 	}
 
 	if fileHash == pulledPolicyHash {
+		if fileName != "" && policy.File != fileName {
+			policy.File = fileName
+			self.savePolicyMap()
+		}
 		return nil
 	}
 
@@ -86,6 +91,7 @@ func (self *PolicyAdminController) UpdatePolicy(policyName string, pulledPolicyP
 
 		log.Infof("policy-admin: no destination configured for '%s', installation only local", policyName)
 		self.policyMap[policyName].Sha = pulledPolicyHash
+		self.policyMap[policyName].File = fileName
 		self.savePolicyMap()
 		return nil
 	}
@@ -102,11 +108,65 @@ func (self *PolicyAdminController) UpdatePolicy(policyName string, pulledPolicyP
 	}
 
 	self.policyMap[policyName].Sha = pulledPolicyHash
+	self.policyMap[policyName].File = fileName
 	self.savePolicyMap()
 	log.Infof("policy-admin: installed %s → %s", policyFile, policy.Destination)
 	return nil
 }
 
+/*
+ * Removes installed policy and its deployed copy, after its file was deleted
+ * from policy repository. Files other than installed one are ignored.
+ */
+func (self *PolicyAdminController) DeletePolicy(policyName string, fileName string) error {
+	self.mu.Lock()
+	defer self.mu.Unlock()
+
+	policy, exists := self.policyMap[policyName]
+	if !exists {
+		return fmt.Errorf("Unknown policy %s.", policyName)
+	}
+
+	if policy.File != "" && fileName != "" && policy.File != fileName {
+		log.Infof("policy-admin: '%s' is not installed for '%s', nothing to delete", fileName, policyName)
+		return nil
+	}
+
+	policyFile := filepath.Join(self.storePath, policyName, "policy.bin")
+	if err := os.Remove(policyFile); err != nil && !os.IsNotExist(err) {
+		return fmt.Errorf("policy-admin: failed to remove policy locally: %v", err)
+	}
+
+	if policy.Destination != "" {
+		if err := os.Remove(policy.Destination); err != nil && !os.IsNotExist(err) {
+			return fmt.Errorf("policy-admin: failed to remove deployed policy: %v", err)
+		}
+	}
+
+	policy.Sha = ""
+	policy.File = ""
+	self.savePolicyMap()
+	log.Infof("policy-admin: removed policy %s", policyName)
+	return nil
+}
+
+/* Records new name of installed policy file, its content is unchanged */
+func (self *PolicyAdminController) RenamePolicy(policyName string, fileName string, newFileName string) error {
+	self.mu.Lock()
+	defer self.mu.Unlock()
+
+	policy, exists := self.policyMap[policyName]
+	if !exists {
+		return fmt.Errorf("Unknown policy %s.", policyName)
+	}
+
+	if policy.File == fileName {
+		policy.File = newFileName
+		self.savePolicyMap()
+	}
+	return nil
+}
+
 /*
  * Copies a policy file from src to dest.
  * Ensures destination directory exists before writing.
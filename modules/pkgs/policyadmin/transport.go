@@ -51,6 +51,7 @@ func NewPolicyAdminServer(policy givctypes.Policy) (*PolicyAdminServer, error) {
 func (s *PolicyAdminServer) StreamPolicy(stream pb.PolicyAdmin_StreamPolicyServer) error {
 	/* Initialize state variables to track the streaming progress and file handling */
 	var policyName string
+	var fileName string
 	var tempFile *os.File
 	var policyFilePath string
 
@@ -86,6 +87,23 @@ func (s *PolicyAdminServer) StreamPolicy(stream pb.PolicyAdmin_StreamPolicyServe
 			}
 
 			log.Debugf("policy-admin:StreamPolicy() received policy: %s\n\n", policyName)
+			fileName = req.GetFileName()
+
+			/* Deletions and renames carry no policy data */
+			switch req.GetChange() {
+			case pb.PolicyChange_DELETE:
+				if err := s.controller.DeletePolicy(policyName, fileName); err != nil {
+					log.Errorf("policy-admin: failed to delete policy: %v", err)
+					return err
+				}
+				return stream.SendAndClose(&policyadmin.Status{Status: "Success"})
+			case pb.PolicyChange_RENAME:
+				if err := s.controller.RenamePolicy(policyName, fileName, req.GetNewFileName()); err != nil {
+					log.Errorf("policy-admin: failed to rename policy: %v", err)
+					return err
+				}
+				return stream.SendAndClose(&policyadmin.Status{Status: "Success"})
+			}
 
 			/* Create a distinct temporary file to store the incoming binary data */
 			tempFile, err = os.CreateTemp(filepath.Join(s.policy.PolicyStorePath, ".temp"), "policy.bin-*")
@@ -121,7 +139,7 @@ func (s *PolicyAdminServer) StreamPolicy(stream pb.PolicyAdmin_StreamPolicyServe
 	}
 
 	tempFile.Close()
-	if err := s.controller.UpdatePolicy(policyName, policyFilePath); err != nil {
+	if err := s.controller.UpdatePolicy(policyName, fileName, policyFilePath); err != nil {
 		if PathExists(policyFilePath) {
 			os.Remove(policyFilePath)
 		}
@@ -35,6 +35,11 @@ let
   tcpAddresses = lib.filter (addr: addr.protocol == "tcp") cfg.addresses;
   unixAddresses = lib.filter (addr: addr.protocol == "unix") cfg.addresses;
   vsockAddresses = lib.filter (addr: addr.protocol == "vsock") cfg.addresses;
+  enabledUpdaters = lib.filter (name: cfg.policyAdmin.updater.${name}.enable) [
+    "gitURL"
+    "perPolicy"
+    "oci"
+  ];
   jsonPolicies =
     if (cfg.policyAdmin.enable && cfg.policyAdmin.updater.gitURL.enable) then
       {
@@ -43,6 +48,7 @@ let
           inherit (cfg.policyAdmin.updater.gitURL) url;
           inherit (cfg.policyAdmin.updater.gitURL) ref;
           inherit (cfg.policyAdmin.updater.gitURL) poll_interval_secs;
+          trusted_keys = cfg.policyAdmin.trustedKeys;
         };
         inherit (cfg.policyAdmin) policies;
       }
@@ -50,6 +56,19 @@ let
       {
         source = {
           type = "per-policy";
+          trusted_keys = cfg.policyAdmin.trustedKeys;
+        };
+        inherit (cfg.policyAdmin) policies;
+      }
+    else if (cfg.policyAdmin.enable && cfg.policyAdmin.updater.oci.enable) then
+      {
+        source = {
+          type = "oci";
+          inherit (cfg.policyAdmin.updater.oci) insecure;
+          trusted_keys = cfg.policyAdmin.trustedKeys;
+        }
+        // lib.optionalAttrs (cfg.policyAdmin.updater.oci.credentialsFile != null) {
+          credentials_file = cfg.policyAdmin.updater.oci.credentialsFile;
         };
         inherit (cfg.policyAdmin) policies;
       }
@@ -87,6 +106,8 @@ in
 
     debug = mkEnableOption "givc-admin debug logging. This increases the verbosity of the logs";
 
+    persistRegistry = mkEnableOption "persisting the admin registry across givc-admin restarts. Restored entries are re-probed before use";
+
     name = mkOption {
       type = types.str;
       default = "localhost";
@@ -213,6 +234,37 @@ in
         perPolicy = {
           enable = mkEnableOption "updates per policy";
         };
+        oci = {
+          enable = mkEnableOption "updates per policy from OCI registry, where `perPolicyUpdater.url` is OCI reference pinned to tag or digest";
+          insecure = mkOption {
+            type = types.bool;
+            default = false;
+            description = "Use HTTP instead of HTTPS to access OCI registry";
+          };
+          credentialsFile = mkOption {
+            type = types.nullOr types.str;
+            default = null;
+            example = "/run/secrets/policy-registry";
+            description = ''
+              Path to credentials of OCI registry, kept out of nix store. Lines `GIVC_REGISTRY_TOKEN=<token>`,
+              or `GIVC_REGISTRY_USERNAME=<name>` and `GIVC_REGISTRY_PASSWORD=<password>`, as in environment of OTA updater.
+            '';
+          };
+        };
+      };
+
+      trustedKeys = mkOption {
+        type = types.listOf types.str;
+        default = [ ];
+        example = [ "policy-1:2Ui2GbP0Zbv6zXoDbXdvmFwTAvXyDNFqW0XjGVQ6bnU=" ];
+        description = ''
+          Ed25519 keys in `<name>:<base64>` format (as generated by `nix-store --generate-binary-cache-key`),
+          which policy updates must be signed with before they are stored and distributed to VMs.
+          Per-policy artifacts need detached signature at `<url>.sig`, and git revisions need
+          `vm-policies.sig` at repository root, signing hex id of the `vm-policies` tree.
+          Unsigned or mis-signed updates are rejected and the previous revision is kept.
+          If empty, updates are not verified.
+        '';
       };
 
       policies = mkOption {
@@ -229,7 +281,7 @@ in
               perPolicyUpdater = {
                 url = mkOption {
                   type = types.nullOr types.str;
-                  description = "URL for the specific policy artifact, ignored if perPolicy updater is disabled";
+                  description = "URL (or OCI reference, for oci updater) for the specific policy artifact, ignored if perPolicy and oci updaters are disabled";
                   default = "";
                 };
                 poll_interval_secs = mkOption {
@@ -238,6 +290,55 @@ in
                   default = 30;
                 };
               };
+              validators = mkOption {
+                description = ''
+                  Checks of policy files, run by policy admin before they are sent to VMs.
+                  Invalid file is not distributed, and VMs keep enforcing the previous one.
+                '';
+                default = [ ];
+                example = literalExpression ''
+                  [
+                    { type = "max-size"; bytes = 65536; }
+                    { type = "json-schema"; schema = ./proxy-schema.json; }
+                    { type = "cedar"; }
+                    { type = "command"; command = [ "''${pkgs.opa}/bin/opa" "check" ]; }
+                  ]'';
+                type = types.listOf (
+                  types.submodule {
+                    options = {
+                      type = mkOption {
+                        type = types.enum [
+                          "json-schema"
+                          "cedar"
+                          "max-size"
+                          "command"
+                        ];
+                        description = "Kind of validator";
+                      };
+                      schema = mkOption {
+                        type = types.nullOr types.path;
+                        default = null;
+                        description = "JSON schema file, for json-schema validator";
+                      };
+                      bytes = mkOption {
+                        type = types.nullOr types.ints.positive;
+                        default = null;
+                        description = "Size limit of policy file, for max-size validator";
+                      };
+                      command = mkOption {
+                        type = types.listOf types.str;
+                        default = [ ];
+                        description = "Checker command and its arguments, for command validator. Path of policy file is appended, non-zero exit rejects it";
+                      };
+                      timeout_secs = mkOption {
+                        type = types.nullOr types.ints.positive;
+                        default = null;
+                        description = "Time limit of command validator, after which it is killed and file rejected. Defaults to 30 seconds";
+                      };
+                    };
+                  }
+                );
+              };
             };
           }
         );
@@ -258,11 +359,7 @@ in
       }
       {
         assertion =
-          !(
-            cfg.policyAdmin.enable
-            && cfg.policyAdmin.updater.gitURL.enable
-            && cfg.policyAdmin.updater.perPolicy.enable
-          );
+          !cfg.policyAdmin.enable || lib.length enabledUpdaters <= 1;
         message = "Two policy updaters cannot be enabled at the same time.";
       }
     ];
@@ -311,9 +408,13 @@ in
         serviceConfig = {
           Type = "exec";
           ExecStart = "${givc-admin}/bin/givc-admin ${args}";
+          # Re-reads cedar policy file
+          ExecReload = "${pkgs.coreutils}/bin/kill -HUP $MAINPID";
           Restart = "on-failure";
           TimeoutStopSec = 5;
           RestartSec = 1;
+          StateDirectory = mkIf cfg.persistRegistry "givc-admin";
+          LogsDirectory = mkIf cfg.accessControl.auditLog "givc-admin";
           ExecStartPre = mkIf (
             cfg.policyAdmin.enable && cfg.policyAdmin.factoryPolicies.enable
           ) "-!${lib.getExe preStartScript}";
@@ -335,6 +436,18 @@ in
           "HOST_CERT" = "${cfg.tls.certPath}";
           "HOST_KEY" = "${cfg.tls.keyPath}";
         }
+        // attrsets.optionalAttrs (cfg.tls.enable && cfg.tls.crlPath != null) {
+          "CRL_FILE" = "${cfg.tls.crlPath}";
+        }
+        // attrsets.optionalAttrs (cfg.tls.enable && cfg.tls.denylistPath != null) {
+          "CERT_DENYLIST" = "${cfg.tls.denylistPath}";
+        }
+        // attrsets.optionalAttrs cfg.accessControl.auditLog {
+          "AUDIT_LOG" = "/var/log/givc-admin/audit.jsonl";
+        }
+        // attrsets.optionalAttrs cfg.persistRegistry {
+          "REGISTRY_JOURNAL" = "/var/lib/givc-admin/registry.json";
+        }
         // attrsets.optionalAttrs cfg.debug {
           "RUST_BACKTRACE" = "1";
           "GIVC_LOG" = "givc=info";
@@ -326,6 +326,19 @@ in
         type = types.str;
         default = "/etc/givc/key.pem";
       };
+      crlPath = mkOption {
+        description = "Path to the certificate revocation list (PEM or DER), signed by the CA certificate. Only used by the admin service.";
+        type = types.nullOr types.str;
+        default = null;
+      };
+      denylistPath = mkOption {
+        description = ''
+          Path to the denylist of certificates, with `serial <hex>` or `sha256 <hex>` entries per line.
+          Only used by the admin service.
+        '';
+        type = types.nullOr types.str;
+        default = null;
+      };
     };
   };
 
@@ -391,6 +404,10 @@ in
           }
         ];
       };
+      auditLog = lib.mkEnableOption ''
+        audit trail of access control decisions, written as JSON lines to
+        `/var/log/givc-admin/audit.jsonl` and readable with `givc-cli audit-log`
+      '';
     };
   };
 
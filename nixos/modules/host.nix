@@ -22,7 +22,7 @@ let
     literalExpression
     optionalString
     ;
-  inherit (builtins) toJSON;
+  inherit (builtins) toJSON concatStringsSep;
   inherit (import ./definitions.nix { inherit config lib pkgs; })
     transportSubmodule
     tlsSubmodule
@@ -201,6 +201,30 @@ in
 
     };
 
+    bootAssessment = {
+      enable = mkEnableOption ''
+        boot assessment of A/B image slots. Freshly installed slot gets boot counter, and is confirmed by
+        stock `systemd-bless-boot.service` once `boot-complete.target` is reached; slot which used all boot
+        attempts without confirmation is demoted at startup, and previous slot is restored as default boot entry
+      '';
+
+      tries = mkOption {
+        type = types.ints.positive;
+        default = 3;
+        description = "Boot attempts of freshly installed image slot, before it is considered bad.";
+      };
+    };
+
+    imageTrustedKeys = mkOption {
+      type = types.listOf types.str;
+      default = [ ];
+      example = literalExpression ''[ "ghaf-release-1:KVR5pZpOUZbB6URvzq9VPT3w6ufhcVdVeaWTQjINaOE=" ]'';
+      description = ''
+        Public keys (`<name>:<base64>`, as generated by `nix-store --generate-binary-cache-key`),
+        which image update manifests must be signed with. Installed to `/etc/ota-update/trusted-keys`.
+      '';
+    };
+
     debug = mkEnableOption ''
       enable appvm GIVC agent debug logging. This increases the verbosity of the logs.
 
@@ -273,6 +297,10 @@ in
         TimeoutStopSec = 5;
         RestartSec = 1;
       };
+      # Picked up by `ota-update` run via exec, slots get boot counter only if they will be assessed
+      environment = mkIf cfg.bootAssessment.enable {
+        OTA_BOOT_TRIES = toString cfg.bootAssessment.tries;
+      };
       path = [
         config.system.path
         ota-update
@@ -290,6 +318,19 @@ in
       ota-update
       pkgs.nixos-rebuild # Need for ota-update
     ];
+    environment.etc."ota-update/trusted-keys" = mkIf (cfg.imageTrustedKeys != [ ]) {
+      text = concatStringsSep "\n" cfg.imageTrustedKeys + "\n";
+    };
+    systemd.services.ota-update-assess = mkIf cfg.bootAssessment.enable {
+      description = "Demote image slots which failed boot assessment";
+      wantedBy = [ "multi-user.target" ];
+      after = [ "local-fs.target" ];
+      path = [ config.system.path ];
+      serviceConfig = {
+        Type = "oneshot";
+        ExecStart = "${ota-update}/bin/ota-update image assess";
+      };
+    };
     systemd.tmpfiles.rules = [
       "d ${cfg.capabilities.policy.storePath} 0755 1000 100 -"
     ];
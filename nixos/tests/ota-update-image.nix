@@ -24,6 +24,11 @@ _: {
               boot.loader.systemd-boot.enable = true;
               boot.loader.efi.canTouchEfiVariables = true;
 
+              # Public part of test signing key, see `signingKey` below
+              environment.etc."ota-update/trusted-keys".text = ''
+                ota-test-1:A6EHv/POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg=
+              '';
+
               # useBootLoader disables host nix store mounting by default
               virtualisation.mountHostNixStore = true;
 
@@ -32,6 +37,7 @@ _: {
 
               environment.systemPackages = [
                 pkgs.efibootmgr
+                pkgs.jq
                 pkgs.lvm2
                 pkgs.zstd
                 self'.packages.givc-admin.ota
@@ -77,6 +83,8 @@ _: {
               version = "25.12.1";
               verityHash = "44cc41b403a2d323a68f42941131169899545eaceebe332e24426e9ff7d7f3bc";
               hashFragment = builtins.substring 0 16 verityHash;
+              # Test-only key, seed is bytes 0..31
+              signingKey = pkgs.writeText "ota-test-key" "ota-test-1:AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8DoQe/884Qvh1w3RjnS8CZZ+TWMJulDV8d3IZkElUxuA==";
 
               # Fake sysupdate artifacts: tiny zstd-compressed images + dummy UKI + manifest
               suDir = pkgs.runCommand "fake-sysupdate" { nativeBuildInputs = [ pkgs.zstd ]; } ''
@@ -104,6 +112,7 @@ _: {
                   "kernel": { "file": "ghaf_kernel_${version}_${hashFragment}.efi",     "sha256": "$kernel_sha" }
                 }
                 EOF
+                ${ota-update} image sign --manifest "$out/manifest.json" --key ${signingKey}
               '';
             in
             ''
@@ -135,6 +144,11 @@ _: {
                   print(f"Status before install:\n{status}")
                   assert "empty" in status
 
+              with subtest("reject unsigned manifest"):
+                  machine.succeed("mkdir -p /tmp/unsigned && cp ${suDir}/*.zst ${suDir}/*.efi ${suDir}/manifest.json /tmp/unsigned/")
+                  machine.fail("${ota-update} image --dry-run install --manifest /tmp/unsigned/manifest.json")
+                  machine.succeed("${ota-update} image --dry-run install --manifest /tmp/unsigned/manifest.json --allow-unsigned")
+
               with subtest("dry-run install"):
                   output = machine.succeed("${ota-update} image --dry-run install --manifest ${suDir}/manifest.json")
                   print(f"Dry-run output:\n{output}")
@@ -142,6 +156,14 @@ _: {
                   output = machine.succeed("lvs --noheadings -o lv_name pool")
                   assert "root_empty" in output, "dry-run should not rename volumes"
 
+              with subtest("reviewed plan"):
+                  machine.succeed("${ota-update} image install --manifest ${suDir}/manifest.json --plan-json > /tmp/plan.json")
+                  machine.succeed("jq -e '.renames | length == 2' /tmp/plan.json")
+                  output = machine.succeed("${ota-update} image --dry-run install --apply-plan /tmp/plan.json")
+                  assert "lvrename" in output
+                  machine.succeed("jq '.runtime.boot_entries += [\"stale\"]' /tmp/plan.json > /tmp/stale-plan.json")
+                  machine.fail("${ota-update} image --dry-run install --apply-plan /tmp/stale-plan.json")
+
               with subtest("install"):
                   machine.succeed("${ota-update} image install --manifest ${suDir}/manifest.json")
 